@@ -1,167 +1,1618 @@
-/// Procedural macro to validate the all members of a structure implement
-/// `Safecast` and generates a runtime routine that can be used to validate
-/// that no padding bytes are present
-///
-/// Since we implement `Safecast` only a plain-old-data root types
-/// (eg. u8, u32, i32, etc) any structure composed entirely of `Safecast`
-/// types will have no padding or non-POD. This allows us to safely do
-/// byte-level copies and casts of the underlying data between types
-/// implementing `Safecast`
-///
-/// Due to not being able to check sizes of values during a procedural macro
-/// it's important to note that `Safecast::safecast()` must be invoked on a
-/// type to do runtime checks of it's padding. Luckily these checks get
-/// optimized out almost entirely in most cases as the compiler can constprop
-/// the size calculations at compile time. We just can't do it for it here :(
-///
-/// Further this does not use `std` nor does it have third party dependencies
-/// which allows for this codebase to be maximally portable.
-///
-/// Yes I'm aware that proc_macro2 and other crates exist and would help make
-/// our lives easier here but I use this codebase everywhere and I really would
-/// prefer to have zero dependencies.
-///
-/// Since we manually parse syntax here it's possible there are edge cases we
-/// do not handle correctly (generics, where clauses, etc). But we can add
-/// those as time goes on. Further you're not really working with templates
-/// if you're working with POD anyways. So these might not really be needed
-/// to implement anyways.
+//! Procedural macro to validate the all members of a structure implement
+//! `Safecast` and generates a runtime routine that can be used to validate
+//! that no padding bytes are present
+//!
+//! Since we implement `Safecast` only a plain-old-data root types
+//! (eg. u8, u32, i32, etc) any structure composed entirely of `Safecast`
+//! types will have no padding or non-POD. This allows us to safely do
+//! byte-level copies and casts of the underlying data between types
+//! implementing `Safecast`
+//!
+//! Due to not being able to check sizes of values during a procedural macro
+//! it's important to note that `Safecast::safecast()` must be invoked on a
+//! type to do runtime checks of it's padding. Luckily these checks get
+//! optimized out almost entirely in most cases as the compiler can constprop
+//! the size calculations at compile time. We just can't do it for it here :(
+//!
+//! Further this does not use `std` nor does it have third party dependencies
+//! which allows for this codebase to be maximally portable.
+//!
+//! Yes I'm aware that proc_macro2 and other crates exist and would help make
+//! our lives easier here but I use this codebase everywhere and I really would
+//! prefer to have zero dependencies.
+//!
+//! Parsing is done structurally on the `TokenTree`s of the input, thus
+//! formatting, comments, and nested groups do not matter. Types are turned
+//! into strings with insignificant whitespace removed for code generation.
 
 extern crate proc_macro;
 
-use proc_macro::TokenStream;
+use proc_macro::{TokenStream, TokenTree, Delimiter, Group};
+use proc_macro::{Ident, Literal, Punct, Spacing, Span};
 
-#[proc_macro_derive(Safecast)]
-pub fn derive_safecast(item: TokenStream) -> TokenStream {
-    // Convert the `TokenStream` to a string
-    // At this point the structure string representation will be normalized
-    // and things like comments, unnecessary whitespace, etc will be removed.
-    let stream = item.to_string();
-
-    // Split up the structure definition into its lines
-    let mut lines: Vec<&str> = stream.lines().collect();
-
-    // Remove all document comments
-    lines.retain(|x| !x.trim().starts_with("///"));
-    
-    // Join the lines together into one big string
-    let commentless: String = lines.concat();
-   
-    // Make sure this structure is `#[repr(C)]`
-    assert!(lines.iter().fold(false, |acc, &x| acc | (x == "#[repr(C)]")),
-        "Structure must be #[repr(C)] for Safecast");
-
-    // There has to be at least one line of the form:
-    // Regular: `struct Moose {`
-    // Tuple:   `struct Flat(u32, u32);`
-    // Unit:    `struct Unit;`
+/// An error in the input of a derive, reported as a `compile_error!` at the
+/// location of the offending tokens
+struct Error {
+    /// Location of the offending tokens
+    span: Span,
+
+    /// Description of the problem
+    message: String,
+}
+
+impl Error {
+    /// Create a new error with `message` at `span`
+    fn new(span: Span, message: impl Into<String>) -> Self {
+        Error { span, message: message.into() }
+    }
+
+    /// Convert the error into a `::core::compile_error!("...");` invocation
+    /// with every token located at the error's span
+    fn into_compile_error(self) -> TokenStream {
+        let span = self.span;
+        let punct = |chr, spacing| {
+            let mut punct = Punct::new(chr, spacing);
+            punct.set_span(span);
+            TokenTree::Punct(punct)
+        };
+
+        let mut message = Literal::string(&self.message);
+        message.set_span(span);
+        let mut args = Group::new(Delimiter::Parenthesis,
+                                  TokenTree::Literal(message).into());
+        args.set_span(span);
+
+        vec![
+            punct(':', Spacing::Joint), punct(':', Spacing::Alone),
+            TokenTree::Ident(Ident::new("core", span)),
+            punct(':', Spacing::Joint), punct(':', Spacing::Alone),
+            TokenTree::Ident(Ident::new("compile_error", span)),
+            punct('!', Spacing::Alone),
+            TokenTree::Group(args),
+            punct(';', Spacing::Alone),
+        ].into_iter().collect()
+    }
+}
+
+/// Tokens being parsed, with one token of lookahead
+type Tokens = core::iter::Peekable<std::vec::IntoIter<TokenTree>>;
+
+/// Returns `true` if `tt` is the punctuation `chr`
+fn is_punct(tt: &TokenTree, chr: char) -> bool {
+    matches!(tt, TokenTree::Punct(p) if p.as_char() == chr)
+}
+
+/// Returns `true` if `tt` is the identifier `ident`
+fn is_ident(tt: &TokenTree, ident: &str) -> bool {
+    matches!(tt, TokenTree::Ident(x) if x.to_string() == ident)
+}
+
+/// Remove all whitespace from `s` other than that separating two identifiers,
+/// for example `& 'a dyn Trait` becomes `&'a dyn Trait`
+fn collapse_whitespace(s: &str) -> String {
+    let is_ident = |chr: char| chr.is_alphanumeric() || chr == '_';
 
-    let mut structline = None;
-    for (ii, line) in lines.iter().enumerate() {
-        if line.starts_with("struct ") || line.starts_with("pub struct ") {
-            structline = Some(ii);
-            break;
+    let mut ret     = String::new();
+    let mut pending = false;
+    for chr in s.chars() {
+        if chr.is_whitespace() {
+            pending = true;
+            continue;
         }
+
+        if pending && ret.ends_with(is_ident) && is_ident(chr) {
+            ret.push(' ');
+        }
+        ret.push(chr);
+        pending = false;
     }
 
-    // Make sure we found the structure definition line
-    assert!(structline.is_some(), "Failed to find `struct` or `pub struct`, \
-            type not allowed for Safecast");
-    let structline = structline.unwrap();
+    ret
+}
+
+/// Convert `tokens` into a string with insignificant whitespace removed, this
+/// is the form in which types are handled during code generation
+fn to_string(tokens: &[TokenTree]) -> String {
+    collapse_whitespace(
+        &tokens.iter().cloned().collect::<TokenStream>().to_string())
+}
 
-    // Figure out the type of this structure
-    let is_tuple_struct = commentless.ends_with(");");
-    let is_named_struct = commentless.ends_with("}");
-
-    // Make sure it's either a named or tuple struct
-    assert!((is_tuple_struct && !is_named_struct) ||
-            (!is_tuple_struct && is_named_struct),
-            "Unit structures not allowed in Safecast");
-
-    // Now lets get the identifier
-    let ident = if is_named_struct {
-        lines[structline].splitn(2, "struct ").nth(1).unwrap()
-            .splitn(2, " {").nth(0).unwrap()
+/// Find the positions of the punctuation `chr` in `tokens` which are not
+/// nested inside of `<>`. Anything nested inside of `()`, `[]`, or `{}` is
+/// already a single group token.
+fn find_top_level(tokens: &[TokenTree], chr: char) -> Vec<usize> {
+    let mut ret   = Vec::new();
+    let mut depth = 0i32;
+
+    // Set once we're in an expression, such as an enum discriminant, where
+    // `<<` is a shift rather than the start of a qualified path
+    let mut expr = false;
+
+    let mut iter = tokens.iter().enumerate();
+    while let Some((ii, tt)) = iter.next() {
+        if is_punct(tt, '<') {
+            if expr && depth == 0 && tokens.get(ii + 1)
+                    .map(|x| is_punct(x, '<')).unwrap_or(false) {
+                iter.next();
+                continue;
+            }
+            depth += 1;
+        } else if is_punct(tt, '>') {
+            // The `>` of a `->` does not close anything, nor does a `>`
+            // outside of generics, such as in a `>>` shift
+            if depth > 0 && !(ii > 0 && is_punct(&tokens[ii - 1], '-')) {
+                depth -= 1;
+            }
+        } else if depth == 0 && is_punct(tt, chr) {
+            ret.push(ii);
+            expr = false;
+        } else if depth == 0 && is_punct(tt, '=') {
+            expr = true;
+        }
+    }
+
+    ret
+}
+
+/// Split `tokens` on commas which are not nested inside of `<>`. Empty
+/// entries, such as from a trailing comma, are removed.
+fn split_commas(tokens: impl IntoIterator<Item = TokenTree>)
+        -> Vec<Vec<TokenTree>> {
+    let tokens: Vec<TokenTree> = tokens.into_iter().collect();
+
+    let mut ret   = Vec::new();
+    let mut start = 0;
+    for comma in find_top_level(&tokens, ',')
+            .into_iter().chain(core::iter::once(tokens.len())) {
+        if comma > start {
+            ret.push(tokens[start..comma].to_vec());
+        }
+        start = comma + 1;
+    }
+
+    ret
+}
+
+/// An attribute such as `#[repr(C, u8)]`
+struct Attribute {
+    /// Name of the attribute, eg. `repr`
+    name: String,
+
+    /// Tokens inside of the attribute's parentheses, eg. `C, u8`
+    args: TokenStream,
+}
+
+/// Parse all attributes at the front of `tokens`
+fn parse_attributes(tokens: &mut Tokens) -> Result<Vec<Attribute>, Error> {
+    let mut ret = Vec::new();
+
+    while tokens.peek().map(|x| is_punct(x, '#')).unwrap_or(false) {
+        let pound = tokens.next().unwrap();
+        let group = match tokens.next() {
+            Some(TokenTree::Group(group))
+                if group.delimiter() == Delimiter::Bracket => group,
+            _ => return Err(Error::new(pound.span(), "Malformed attribute")),
+        };
+
+        let mut inner = group.stream().into_iter();
+        let name = inner.next().map(|x| x.to_string()).unwrap_or_default();
+        let args = match inner.next() {
+            Some(TokenTree::Group(group))
+                if group.delimiter() == Delimiter::Parenthesis =>
+                    group.stream(),
+            _ => TokenStream::new(),
+        };
+
+        ret.push(Attribute { name, args });
+    }
+
+    Ok(ret)
+}
+
+/// Get all the representations given by `#[repr(...)]` in `attrs`
+fn parse_reprs(attrs: &[Attribute]) -> Vec<String> {
+    attrs.iter()
+        .filter(|x| x.name == "repr")
+        .flat_map(|x| split_commas(x.args.clone()))
+        .map(|x| to_string(&x))
+        .collect()
+}
+
+/// Skip over any visibility such as `pub` or `pub(crate)` at the front of
+/// `tokens`
+fn skip_visibility(tokens: &mut Tokens) {
+    if !tokens.peek().map(|x| is_ident(x, "pub")).unwrap_or(false) {
+        return;
+    }
+    tokens.next();
+
+    // Restricted visibility, eg. `pub(crate)` or `pub(in crate::foo)`. This
+    // is distinct from a `pub` tuple field with a parenthesized type.
+    if let Some(TokenTree::Group(group)) = tokens.peek() {
+        let restricted = group.delimiter() == Delimiter::Parenthesis &&
+            group.stream().into_iter().next().map(|x| {
+                ["crate", "self", "super", "in"].iter()
+                    .any(|kw| is_ident(&x, kw))
+            }).unwrap_or(false);
+        if restricted {
+            tokens.next();
+        }
+    }
+}
+
+/// A structure, union, or enum given to a derive
+struct Item {
+    /// Outer attributes of the item
+    attrs: Vec<Attribute>,
+
+    /// The kind of item, eg. `struct`
+    keyword: String,
+
+    /// Name of the item
+    ident: String,
+
+    /// Location of the name of the item
+    span: Span,
+
+    /// Tokens between the `<>` of the generic parameters, if any
+    generics: Vec<TokenTree>,
+
+    /// Tokens of the predicates of the `where` clause, if any
+    predicates: Vec<TokenTree>,
+
+    /// The braced or parenthesized body of the item, `None` for unit
+    /// structures
+    body: Option<Group>,
+}
+
+/// Parse the item definition `item` given to the derive named `derive`
+fn parse_item(item: TokenStream, derive: &str) -> Result<Item, Error> {
+    let mut tokens = item.into_iter().collect::<Vec<_>>()
+        .into_iter().peekable();
+
+    let attrs = parse_attributes(&mut tokens)?;
+    skip_visibility(&mut tokens);
+    let (keyword, ident) = match (tokens.next(), tokens.next()) {
+        (Some(keyword), Some(ident)) => (keyword, ident),
+        _ => return Err(Error::new(Span::call_site(),
+            format!("Expected an item for {}", derive))),
+    };
+    let (keyword, span, ident) =
+        (keyword.to_string(), ident.span(), ident.to_string());
+
+    // Collect the generic parameters, if any
+    let mut generics = Vec::new();
+    if tokens.peek().map(|x| is_punct(x, '<')).unwrap_or(false) {
+        tokens.next();
+
+        let mut depth = 1;
+        for tt in &mut tokens {
+            let arrow = generics.last().map(|x| is_punct(x, '-'))
+                .unwrap_or(false);
+            if is_punct(&tt, '<') {
+                depth += 1;
+            } else if is_punct(&tt, '>') && !arrow {
+                depth -= 1;
+                if depth == 0 { break; }
+            }
+            generics.push(tt);
+        }
+    }
+
+    // Next is the body, or a `;` for unit structures. A `where` clause
+    // comes before a braced body but after a parenthesized one.
+    let mut body       = None;
+    let mut predicates = Vec::new();
+    let mut in_where   = false;
+    for tt in tokens {
+        match tt {
+            TokenTree::Group(ref group) if !in_where &&
+                    group.delimiter() == Delimiter::Parenthesis => {
+                body = Some(group.clone());
+            }
+            TokenTree::Group(ref group)
+                    if group.delimiter() == Delimiter::Brace => {
+                body = Some(group.clone());
+                break;
+            }
+            ref tt if is_ident(tt, "where") => in_where = true,
+            ref tt if is_punct(tt, ';') => break,
+            tt if in_where => predicates.push(tt),
+            tt => return Err(Error::new(tt.span(),
+                format!("Unexpected `{}` in {} input", tt, derive))),
+        }
+    }
+
+    Ok(Item { attrs, keyword, ident, span, generics, predicates, body })
+}
+
+/// The name of the structure being derived, split into the pieces needed to
+/// implement traits on it
+struct Name {
+    /// Generic parameters for the `impl`, eg. `<T: Copy>`, or empty
+    generics: String,
+
+    /// The structure type with its generic arguments, eg. `Handle<T>`
+    ty: String,
+
+    /// Predicates from the structure's `where` clause each followed by a
+    /// comma, eg. `T: Copy, `, or empty
+    predicates: String,
+}
+
+/// Build the name of a structure from its identifier and the tokens of its
+/// generic parameters and `where` clause, for example `Handle` and
+/// `T: Copy, const N: usize`
+fn parse_name(ident: &str, params: Vec<TokenTree>,
+              predicates: Vec<TokenTree>) -> Name {
+    let predicates: String = split_commas(predicates).iter()
+        .map(|x| format!("{}, ", to_string(x))).collect();
+    if params.is_empty() {
+        return Name { generics: String::new(), ty: ident.into(), predicates };
+    }
+
+    let mut generics = Vec::new();
+    let mut args     = Vec::new();
+    for param in split_commas(params) {
+        // Defaults are not allowed on the generics of an `impl`
+        let end = find_top_level(&param, '=').first().copied()
+            .unwrap_or(param.len());
+        let param = &param[..end];
+        generics.push(to_string(param));
+
+        // The argument is just the name of the parameter
+        args.push(if is_ident(&param[0], "const") {
+            param[1].to_string()
+        } else if is_punct(&param[0], '\'') {
+            format!("'{}", param[1])
+        } else {
+            param[0].to_string()
+        });
+    }
+
+    Name {
+        generics: format!("<{}>", generics.join(", ")),
+        ty:       format!("{}<{}>", ident, args.join(", ")),
+        predicates,
+    }
+}
+
+/// Generate a where clause with the structure's own predicates, and for
+/// generic structures (where the field types may depend on the parameters)
+/// requiring every field type to implement `bound`
+fn where_clause(name: &Name, fields: &[Field], bound: &str) -> String {
+    if name.generics.is_empty() && name.predicates.is_empty() {
+        return String::new();
+    }
+
+    let mut ret = format!(" where {}", name.predicates);
+    if !name.generics.is_empty() {
+        for field in fields {
+            ret += &format!("{}: {}, ", field.ty, bound);
+        }
+    }
+    ret
+}
+
+/// Split an array type (with whitespace removed) such as `[u8;4]` into its
+/// element type and length, or `None` if `ty` is not an array
+fn split_array(ty: &str) -> Option<(&str, &str)> {
+    let inner = ty.strip_prefix('[')?.strip_suffix(']')?;
+    let semi  = inner.rfind(';')?;
+    Some((&inner[..semi], &inner[semi + 1..]))
+}
+
+/// Get the element type of `ty`, looking through any arrays
+fn element_type(ty: &str) -> &str {
+    match split_array(ty) {
+        Some((elem, _)) => element_type(elem),
+        None => ty,
+    }
+}
+
+/// Generate an expression which checks that `bytes`, holding exactly one
+/// `ty`, is a valid `ty`. Arrays are validated element-wise, which allows
+/// arrays of types which are only `CheckedSafecast`.
+fn validate_expr(ty: &str, bytes: &str) -> String {
+    match split_array(ty) {
+        Some((elem, _)) => format!("{}.chunks_exact(\
+            ::core::mem::size_of::<{}>()).all(|x| {})",
+            bytes, elem, validate_expr(elem, "x")),
+        None => format!(
+            "<{} as ::safecast::CheckedSafecast>::is_valid_bytes({})",
+            ty, bytes),
+    }
+}
+
+/// Generate the `INTERIOR_MUTABLE` constant of `trait_`, which is set if the
+/// type `ty(field)` of any of the `fields` is interior mutable
+fn interior_mutable(fields: &[Field], trait_: &str,
+                    ty: fn(&Field) -> &str) -> String {
+    let mut ret = String::from("    const INTERIOR_MUTABLE: bool = {\n");
+    ret += "        #[allow(unused_mut)]\n";
+    ret += "        let mut interior_mutable = false;\n";
+    for field in fields {
+        ret += &format!("        {}{{ interior_mutable |= \
+            <{} as {}>::INTERIOR_MUTABLE; }}\n", field.cfg, ty(field), trait_);
+    }
+    ret += "        interior_mutable\n    };\n";
+    ret
+}
+
+/// Generate the `VALIDATED` constant of `Safecast`, which is set if every
+/// field is `VALIDATED` and there is no user-supplied validation. Evaluating
+/// it also evaluates the layout checks, thus they have passed for any
+/// instantiation where the cast routines skip calling `safecast`.
+fn validated(fields: &[Field]) -> String {
+    let mut ret = String::from("    const VALIDATED: bool = {\n");
+    ret += LAYOUT_CHECK;
+    ret += &format!("        #[allow(unused_mut)]\n        \
+        let mut validated = {};\n",
+        !fields.iter().any(|x| x.validate.is_some()));
+    for field in fields {
+        ret += &format!("        {}{{ validated &= \
+            <{} as ::safecast::Safecast>::VALIDATED; }}\n",
+            field.cfg, field.ty);
+    }
+    ret += "        validated\n    };\n";
+    ret
+}
+
+/// Generate the `CHECKS_VALUES` constant of `Safecast`, which is set if there
+/// is user-supplied validation of any field or of any field's type
+fn checks_values(fields: &[Field]) -> String {
+    let mut ret = String::from("    const CHECKS_VALUES: bool = {\n");
+    ret += &format!("        #[allow(unused_mut)]\n        \
+        let mut checks_values = {};\n",
+        fields.iter().any(|x| x.validate.is_some()));
+    for field in fields {
+        ret += &format!("        {}{{ checks_values |= \
+            <{} as ::safecast::Safecast>::CHECKS_VALUES; }}\n",
+            field.cfg, field.ty);
+    }
+    ret += "        checks_values\n    };\n";
+    ret
+}
+
+/// Generate statements asserting that the sizes of `fields` add up to the
+/// size of the structure, which ensures that there are no padding bytes in
+/// the structure. For structures with an `#[repr(align(N))]` of `align`, the
+/// trailing padding added by the alignment is reported separately.
+fn padding_checks(fields: &[Field], align: Option<&str>) -> String {
+    let mut ret = String::from("        let mut unpadded_struct_size = 0usize;\n");
+    for field in fields {
+        ret += &format!("        {}{{ unpadded_struct_size += \
+            ::core::mem::size_of::<{}>(); }}\n", field.cfg, field.ty);
+    }
+
+    // Find the end of the last field, anything after it is trailing padding
+    if let Some(align) = align {
+        ret += "        let mut struct_end = 0usize;\n";
+        for field in fields {
+            ret += &format!("        {}{{ \
+                let end = ::core::mem::offset_of!(Self, {}) + \
+                    ::core::mem::size_of::<{}>(); \
+                if end > struct_end {{ struct_end = end; }} }}\n",
+                field.cfg, field.name, field.ty);
+        }
+        ret += &format!("        \
+            assert!(struct_end == ::core::mem::size_of::<Self>(), \
+                \"Safecast not allowed on structures with padding bytes, \
+                #[repr(align({}))] adds trailing padding which must be \
+                declared as an explicit field, eg. `_pad: [u8; N]`\");\n",
+            align);
+    }
+
+    // Note: This `size_of::<Self>()` is what prevents us from using a slice
+    //       in a structure. This is quite important to have here!
+    ret += "        \
+        assert!(unpadded_struct_size == ::core::mem::size_of::<Self>(), \
+            \"Safecast not allowed on structures with padding bytes\");\n";
+    ret
+}
+
+/// Generate an inherent associated constant `__SAFECAST_LAYOUT` which runs
+/// the assertions in `checks` on the layout of the structure at compile
+/// time. Without generic parameters the layout is always known and the
+/// constant is evaluated unconditionally, otherwise it is evaluated for each
+/// instantiation when referenced by the generated routines with
+/// `LAYOUT_CHECK`.
+fn impl_layout_check(name: &Name, checks: &str) -> String {
+    let mut ret = format!("impl{} {}{} {{\n", name.generics, name.ty,
+                          where_clause(name, &[], ""));
+    ret += "    #[doc(hidden)]\n    const __SAFECAST_LAYOUT: () = {\n";
+    ret += checks;
+    ret += "    };\n}\n";
+
+    if name.generics.is_empty() {
+        ret += &format!("const _: () = {}::__SAFECAST_LAYOUT;\n", name.ty);
+    }
+    ret
+}
+
+/// Generate statements asserting the alignment of the structure `name` given
+/// by any `#[safecast(assert_align = N)]` options
+fn align_checks(name: &Name, options: &[StructOption]) -> String {
+    let mut ret = String::new();
+    for option in options.iter().filter(|x| x.name == "assert_align") {
+        ret += &format!("        \
+            assert!(::core::mem::align_of::<Self>() == {}, \
+                \"Alignment mismatch in {}\");\n", option.value, name.ty);
+    }
+    ret
+}
+
+/// Statement referencing the constant from `impl_layout_check`, which forces
+/// it to be evaluated for the instantiation of the enclosing routine
+const LAYOUT_CHECK: &str = "        let () = Self::__SAFECAST_LAYOUT;\n";
+
+/// Generate an implementation of `Debug` which prints each field with its
+/// offset and bytes in hex, using the `Layout` implementation
+fn impl_debug(name: &Name) -> String {
+    let mut ret = format!("impl{} ::core::fmt::Debug for {} where {}\
+        Self: ::safecast::IntoBytes {{\n",
+        name.generics, name.ty, name.predicates);
+    ret += &format!("    fn fmt(&self, f: &mut ::core::fmt::Formatter<'_>) \
+        -> ::core::fmt::Result {{\n        \
+        ::safecast::layout::debug_fields::<Self>(\"{}\", \
+            ::safecast::IntoBytes::as_bytes(self), f)\n    }}\n}}\n",
+        name.ty.split('<').next().unwrap().trim_start_matches("r#"));
+    ret
+}
+
+/// Generate implementations of `bytemuck::Pod` and `bytemuck::Zeroable` for
+/// `#[safecast(bytemuck)]`, or nothing if the option is not given
+///
+/// `Pod` has no runtime checks, thus this relies on the layout checks being
+/// evaluated unconditionally, which is only the case without generics. Each
+/// field is required to be `Pod` as well, since `Safecast` field types may
+/// only check their own layout at runtime.
+fn impl_bytemuck(name: &Name, fields: &[Field], options: &[StructOption])
+        -> Result<String, Error> {
+    let option = match options.iter().find(|x| x.name == "bytemuck") {
+        Some(option) => option,
+        None => return Ok(String::new()),
+    };
+    if !name.generics.is_empty() {
+        return Err(Error::new(option.span,
+            "#[safecast(bytemuck)] is not supported on generic structures"));
+    }
+
+    let mut ret = String::new();
+    for field in fields {
+        ret += &format!("{}const _: fn() = || {{ \
+            fn assert_pod<T: ::safecast::bytemuck::Pod>() {{}} \
+            assert_pod::<{}>(); }};\n", field.cfg, field.ty);
+    }
+    for bound in &["Zeroable", "Pod"] {
+        ret += &format!("unsafe impl ::safecast::bytemuck::{} for {}{} {{}}\n",
+                        bound, name.ty, where_clause(name, &[], ""));
+    }
+    Ok(ret)
+}
+
+/// Generate an implementation of `::safecast::reader::Checksummed` for a
+/// structure with a `#[safecast(checksum = path)]` field, or nothing if
+/// there is no such field
+fn impl_checksum(name: &Name, fields: &[Field], packed: bool)
+        -> Result<String, Error> {
+    let mut checksums = fields.iter().filter(|x| x.checksum.is_some());
+    let field = match checksums.next() {
+        Some(field) => field,
+        None => return Ok(String::new()),
+    };
+    if let Some(other) = checksums.next() {
+        return Err(Error::new(other.span,
+            "Only one field may have a #[safecast(checksum = ...)]"));
+    }
+
+    // Checksums are computed with the checksum field zeroed
+    let compute = format!("::safecast::reader::with_zeroed_field(self, \
+        ::core::mem::offset_of!(Self, {}), ::core::mem::size_of::<{}>(), {})",
+        field.name, field.ty, field.checksum.as_ref().unwrap());
+
+    let mut ret = format!("{}impl{} ::safecast::reader::Checksummed for {} \
+        where {}Self: ::safecast::CheckedSafecast {{\n",
+        field.cfg, name.generics, name.ty, name.predicates);
+    ret += &format!("    fn verify_checksum(&self) -> bool {{ \
+        ::core::cmp::PartialEq::eq(&{}, {}) }}\n",
+        compute, field_ref(&field.name, packed));
+    ret += &format!("    fn update_checksum(&mut self) {{ \
+        self.{} = {}; }}\n", field.name, compute);
+    ret += "}\n";
+    Ok(ret)
+}
+
+/// Generate an expression referencing the field `name` of `self`. Fields of
+/// packed structures may be misaligned and cannot be referenced directly,
+/// thus they are referenced through an unaligned copy instead.
+fn field_ref(name: &str, packed: bool) -> String {
+    if packed {
+        format!("&*::core::mem::ManuallyDrop::new(unsafe {{ \
+            ::core::ptr::read_unaligned(::core::ptr::addr_of!(self.{})) }})",
+            name)
     } else {
-        lines[structline].splitn(2, "struct ").nth(1).unwrap()
-            .splitn(2, "(").nth(0).unwrap()
+        format!("&self.{}", name)
+    }
+}
+
+/// Generate the `check_layout` function of `Safecast`, which checks the
+/// layout of each field referenced by `field_ref` like `safecast`, but skips
+/// any user-supplied validation of their values. The fields are skipped for
+/// `VALIDATED` structures, whose layout is fully checked at compile time.
+fn impl_check_layout(fields: &[Field], field_ref: impl Fn(&Field) -> String)
+        -> String {
+    let mut ret = String::from("    fn check_layout(&self) {\n");
+    ret += LAYOUT_CHECK;
+    ret += "        if <Self as ::safecast::Safecast>::VALIDATED { return; }\n";
+    for field in fields {
+        ret += &format!("        {}::safecast::Safecast::check_layout({});\n",
+                        field.cfg, field_ref(field));
+    }
+    ret += "    }\n";
+    ret
+}
+
+/// Generate an implementation of `::safecast::layout::Layout` listing the
+/// name, offset, and size of each of `fields`
+fn impl_field_layout(name: &Name, fields: &[Field]) -> String {
+    let mut ret = format!("impl{} ::safecast::layout::Layout for {}{} {{\n",
+        name.generics, name.ty, where_clause(name, &[], ""));
+    ret += "    const FIELDS: &'static \
+        [::safecast::layout::FieldLayout] = &[\n";
+    for field in fields {
+        ret += &format!("        {}::safecast::layout::FieldLayout {{ \
+            name: \"{}\", \
+            offset: ::core::mem::offset_of!(Self, {}), \
+            size: ::core::mem::size_of::<{}>() }},\n",
+            field.cfg, field.name.trim_start_matches("r#"), field.name,
+            field.ty);
+    }
+    ret += "    ];\n}\n";
+    ret
+}
+
+/// Generate inherent `from_le_bytes`, `from_be_bytes`, `to_le_bytes`, and
+/// `to_be_bytes` methods mirroring those on the primitive integers. These
+/// rely on the `Endian` implementation from `impl_endian`.
+fn impl_bytes(name: &Name) -> String {
+    let ident = &name.ty;
+    let mut ret = format!("impl {} {{\n", ident);
+
+    for (endian, suffix) in &[("little", "le"), ("big", "be")] {
+        ret += &format!("    /// Create a value from its representation as a \
+            byte array in {endian}-endian byte order\n    \
+            pub fn from_{suffix}_bytes(\
+                bytes: [u8; ::core::mem::size_of::<{ident}>()]) -> Self {{ \
+            ::safecast::endian::Endian::from_{suffix}(\
+                &::safecast::Safecast::cast_copy::<Self>(&bytes)) }}\n",
+            ident = ident, endian = endian, suffix = suffix);
+        ret += &format!("    /// Return the memory representation of this \
+            value as a byte array in {endian}-endian byte order\n    \
+            pub fn to_{suffix}_bytes(&self) \
+                -> [u8; ::core::mem::size_of::<{ident}>()] {{ \
+            ::safecast::Safecast::cast_copy(\
+                &::safecast::endian::Endian::to_{suffix}(self)) }}\n",
+            ident = ident, endian = endian, suffix = suffix);
+    }
+
+    ret += "}\n";
+    ret
+}
+
+/// Returns `true` if `ty` (with whitespace removed) mentions a type with a
+/// host-dependent byte order, such as `u32`, `f64`, `Wrapping<u16>`,
+/// `Option<NonZeroU32>`, `AtomicU64`, or arrays of them
+fn is_host_endian(ty: &str) -> bool {
+    ty.split(|x: char| !x.is_alphanumeric() && x != '_').any(|x| {
+        matches!(x, "u16" | "u32" | "u64" | "u128" | "usize" |
+                    "i16" | "i32" | "i64" | "i128" | "isize" |
+                    "f32" | "f64" |
+                    "NonZeroU16" | "NonZeroU32" | "NonZeroU64" |
+                    "NonZeroU128" | "NonZeroUsize" |
+                    "NonZeroI16" | "NonZeroI32" | "NonZeroI64" |
+                    "NonZeroI128" | "NonZeroIsize" |
+                    "AtomicU16" | "AtomicU32" | "AtomicU64" | "AtomicUsize" |
+                    "AtomicI16" | "AtomicI32" | "AtomicI64" | "AtomicIsize" |
+                    "AtomicPtr" | "NonNull")
+    })
+}
+
+/// Returns `true` if `ty` (with whitespace removed) mentions a type whose size
+/// depends on the pointer width of the target, such as `usize`,
+/// `Option<NonZeroIsize>`, or a reference
+fn is_pointer_sized(ty: &str) -> bool {
+    ty.starts_with(['&', '*']) ||
+        ty.split(|x: char| !x.is_alphanumeric() && x != '_').any(|x| {
+            matches!(x, "usize" | "isize" | "NonZeroUsize" | "NonZeroIsize" |
+                        "AtomicUsize" | "AtomicIsize" | "AtomicPtr" |
+                        "NonNull")
+        })
+}
+
+/// A field of the structure being derived
+struct Field {
+    /// Name of the field, or its index for tuple structures
+    name: String,
+
+    /// Location of the field
+    span: Span,
+
+    /// Type of the field with insignificant whitespace removed
+    ty: String,
+
+    /// Byte order given by a `#[safecast(endian = "...")]` attribute
+    endian: Option<&'static str>,
+
+    /// Path to a validation function given by a
+    /// `#[safecast(validate = path)]` attribute
+    validate: Option<String>,
+
+    /// Path to a function computing the checksum of the structure given by a
+    /// `#[safecast(checksum = path)]` attribute
+    checksum: Option<String>,
+
+    /// `#[cfg(...)]` attributes of the field, placed in front of any code
+    /// generated for it so it is only used if the field exists
+    cfg: String,
+}
+
+/// Parse a field attribute of the form
+/// `#[safecast(endian = "big", validate = path, checksum = path)]` into
+/// `field`. `#[cfg(...)]`
+/// attributes are recorded, and all other attributes are ignored.
+fn parse_field_attribute(attr: &Attribute, field: &mut Field)
+        -> Result<(), Error> {
+    if attr.name == "cfg" {
+        field.cfg += &format!("#[cfg({})] ", attr.args);
+        return Ok(());
+    }
+    if attr.name != "safecast" { return Ok(()); }
+
+    for option in split_commas(attr.args.clone()) {
+        let unknown = || Error::new(option[0].span(), format!(
+            "Unknown option `{}` in #[safecast(...)] field attribute",
+            to_string(&option)));
+
+        // Every option is of the form `name = value`
+        let value = if option.len() > 2 && is_punct(&option[1], '=') {
+            to_string(&option[2..])
+        } else {
+            return Err(unknown());
+        };
+
+        if is_ident(&option[0], "endian") {
+            field.endian = match value.trim_matches('"') {
+                "big"    => Some("big"),
+                "little" => Some("little"),
+                endian   => return Err(Error::new(option[2].span(), format!(
+                    "Endianness must be \"big\" or \"little\", got `{}`",
+                    endian))),
+            };
+        } else if is_ident(&option[0], "validate") {
+            field.validate = Some(value);
+        } else if is_ident(&option[0], "checksum") {
+            field.checksum = Some(value);
+        } else {
+            return Err(unknown());
+        }
+    }
+
+    Ok(())
+}
+
+/// Generate inherent accessors for the fields with a
+/// `#[safecast(endian = "...")]` attribute. Each field `x` gets a `x()`
+/// method returning the value in native byte order, and a `set_x()` method
+/// storing a native value in the field's byte order.
+fn impl_accessors(name: &Name, fields: &[Field], packed: bool) -> String {
+    let mut ret = format!("impl{} {}{} {{\n", name.generics, name.ty,
+        where_clause(name, fields, "::safecast::Safecast"));
+
+    for field in fields {
+        let endian = match field.endian {
+            Some(endian) => endian,
+            None => continue,
+        };
+        let (from, to) = if endian == "big" {
+            ("from_be", "to_be")
+        } else {
+            ("from_le", "to_le")
+        };
+
+        ret += &format!("    /// Get `{name}` converted from {endian}-endian \
+            to native byte order\n    {cfg}\
+            pub fn {name}(&self) -> {ty} {{ \
+            ::safecast::endian::Endian::{from}({field}) }}\n",
+            name = field.name, ty = field.ty, endian = endian, from = from,
+            field = field_ref(&field.name, packed), cfg = field.cfg);
+
+        // Raw identifiers such as `r#type` get a `set_type()` setter
+        ret += &format!("    /// Set `{name}` from a native `val`, storing \
+            it {endian}-endian\n    {cfg}\
+            pub fn set_{bare}(&mut self, val: {ty}) {{ self.{name} = \
+            ::safecast::endian::Endian::{to}(&val); }}\n",
+            name = field.name, ty = field.ty, endian = endian, to = to,
+            bare = field.name.trim_start_matches("r#"), cfg = field.cfg);
+    }
+
+    ret += "}\n";
+    ret
+}
+
+/// An option from a `#[safecast(...)]` structure attribute
+struct StructOption {
+    /// Name of the option, eg. `size`
+    name: String,
+
+    /// Value of the option with whitespace removed, eg. `16` for
+    /// `size = 16`, or empty for options without a value
+    value: String,
+
+    /// Location of the option
+    span: Span,
+}
+
+/// Parse the options out of all `#[safecast(...)]` attributes in `attrs`,
+/// for example `#[safecast(byteswap)]`
+fn parse_options(attrs: &[Attribute]) -> Result<Vec<StructOption>, Error> {
+    let mut options = Vec::new();
+
+    for attr in attrs.iter().filter(|x| x.name == "safecast") {
+        for option in split_commas(attr.args.clone()) {
+            let span = option[0].span();
+            let name = option[0].to_string();
+
+            // Only `size` and `assert_align` take a value, as in `size = 16`
+            let value = if name == "size" || name == "assert_align" {
+                if option.len() < 3 || !is_punct(&option[1], '=') {
+                    return Err(Error::new(span, format!(
+                        "Expected #[safecast({} = N)]", name)));
+                }
+                to_string(&option[2..])
+            } else if option.len() == 1 &&
+                    ["byteswap", "bytes", "wire", "portable", "debug",
+                     "bytemuck"].contains(&name.as_str()) {
+                String::new()
+            } else {
+                return Err(Error::new(span, format!(
+                    "Unknown option `{}` in #[safecast(...)] attribute",
+                    to_string(&option))));
+            };
+
+            options.push(StructOption { name, value, span });
+        }
+    }
+
+    Ok(options)
+}
+
+/// Generate an implementation of `::safecast::endian::Endian` which converts
+/// each field individually, along with inherent `to_le` and `to_be` methods
+/// if `inherent` is set
+fn impl_endian(name: &Name, is_named_struct: bool, packed: bool,
+               fields: &[Field], inherent: bool) -> String {
+    let mut ret = String::new();
+
+    // Builds an expression constructing `Self` with `method` applied to each
+    // field
+    let construct = |method: &str| {
+        let mut expr = String::new();
+        for field in fields {
+            let conv = format!("::safecast::endian::Endian::{}({})",
+                               method, field_ref(&field.name, packed));
+            if is_named_struct {
+                expr += &format!("{}{}: {}, ", field.cfg, field.name, conv);
+            } else {
+                expr += &format!("{}, ", conv);
+            }
+        }
+
+        if is_named_struct {
+            format!("Self {{ {}}}", expr)
+        } else {
+            format!("Self({})", expr)
+        }
     };
 
-    // Parse out the fields of the structure
-    // Also remove all spaces, newlines, CRs, and tabs
-    let fields = if is_named_struct {
-        commentless.splitn(2, &format!("struct {} {{", ident)).nth(1)
-            .expect("Could not find struct prefix")
-            .splitn(2, "}").nth(0).expect("Could not find struct postfix")
+    let bounds = where_clause(name, fields, "::safecast::endian::Endian");
+    ret += &format!("impl{} ::safecast::endian::Endian for {}{} {{\n",
+                    name.generics, name.ty, bounds);
+    for method in &["swap_bytes", "to_le", "to_be"] {
+        ret += &format!("    fn {}(&self) -> Self {{ {} }}\n",
+                        method, construct(method));
+    }
+    ret += "}\n";
+
+    if !inherent { return ret; }
+
+    ret += &format!("impl{} {}{} {{\n", name.generics, name.ty, bounds);
+    ret += "    /// Convert each field from native byte order to little-endian\
+        \n";
+    ret += "    pub fn to_le(&self) -> Self { \
+        ::safecast::endian::Endian::to_le(self) }\n";
+    ret += "    /// Convert each field from native byte order to big-endian\n";
+    ret += "    pub fn to_be(&self) -> Self { \
+        ::safecast::endian::Endian::to_be(self) }\n";
+    ret += "}\n";
+
+    ret
+}
+
+/// Generate an implementation of `Safecast` for a union. Every variant must
+/// be `Safecast` and exactly the size of the union, otherwise writing a
+/// smaller variant would leave uninitialized bytes in the union.
+fn impl_union(name: &Name, fields: &[Field], options: &[StructOption],
+              packed: bool) -> Result<String, Error> {
+    // Unions cannot be converted field-by-field
+    if let Some(option) = options.iter()
+            .find(|x| !["size", "assert_align", "portable", "debug",
+                         "bytemuck"].contains(&x.name.as_str())) {
+        return Err(Error::new(option.span, format!(
+            "#[safecast({})] is not supported on unions", option.name)));
+    }
+    if let Some(field) = fields.iter().find(|x| x.endian.is_some()) {
+        return Err(Error::new(field.span,
+            "#[safecast(endian = ...)] is not supported on unions"));
+    }
+    if let Some(field) = fields.iter().find(|x| x.validate.is_some()) {
+        return Err(Error::new(field.span,
+            "#[safecast(validate = ...)] is not supported on unions"));
+    }
+    if let Some(field) = fields.iter().find(|x| x.checksum.is_some()) {
+        return Err(Error::new(field.span,
+            "#[safecast(checksum = ...)] is not supported on unions"));
+    }
+
+    // Check the size of the union against an explicit `#[safecast(size = N)]`
+    let mut checks = align_checks(name, options);
+    for option in options.iter().filter(|x| x.name == "size") {
+        checks += &format!("        \
+            assert!(::core::mem::size_of::<Self>() == {}, \
+                \"Size mismatch in union\");\n", option.value);
+    }
+
+    // Make sure every variant covers the entire union
+    for field in fields {
+        checks += &format!("        {}\
+            assert!(::core::mem::size_of::<{}>() == \
+                ::core::mem::size_of::<Self>(), \
+                \"Safecast not allowed on structures with padding bytes\");\n",
+            field.cfg, field.ty);
+    }
+    let mut ret = impl_layout_check(name, &checks);
+    ret += &impl_field_layout(name, fields);
+    ret += &impl_bytemuck(name, fields, options)?;
+    if options.iter().any(|x| x.name == "debug") {
+        ret += &impl_debug(name);
+    }
+
+    ret += &format!("unsafe impl{} ::safecast::Safecast for {}{} {{\n",
+        name.generics, name.ty,
+        where_clause(name, fields, "::safecast::Safecast"));
+    ret += "    fn safecast(&self) {\n";
+    ret += LAYOUT_CHECK;
+
+    // Every byte of the union is initialized by every variant, thus any
+    // variant can be referenced. Invoke safecast on each of them, which
+    // validates there is no padding inside of the variants.
+    for field in fields {
+        let field_ref = if packed {
+            field_ref(&field.name, packed)
+        } else {
+            format!("unsafe {{ &self.{} }}", field.name)
+        };
+        ret += &format!("        {}::safecast::Safecast::safecast({});\n",
+                        field.cfg, field_ref);
+    }
+    ret += "    }\n";
+    ret += &impl_check_layout(fields, |field| if packed {
+        field_ref(&field.name, packed)
     } else {
-        commentless.splitn(2, &format!("struct {}(", ident)).nth(1).unwrap()
-            .splitn(2, ");").nth(0).unwrap()
-    }.replace(" ", "").replace("\t", "");
-
-    // For a tuple struct fields should look like:
-    // Fields: "u32,u32,usize,u8,usize,usize,u8,usize,usize,u8,usize"
-    //
-    // For a named struct fields should look like:
-    // Fields: "bat:u32,ts:TestStruct,"
-   
+        format!("unsafe {{ &self.{} }}", field.name)
+    });
+
+    // The union is interior mutable if any of its variants are
+    ret += &interior_mutable(fields, "::safecast::Safecast", |x| &x.ty);
+    ret += &validated(fields);
+    ret += &checks_values(fields);
+    ret += "}\n";
+
+    Ok(ret)
+}
+
+/// A structure parsed out of the input to a derive
+struct Struct {
+    /// Name of the structure
+    name: Name,
+
+    /// Location of the name of the structure
+    span: Span,
+
+    /// Set for structures with named fields, clear for tuple structures
+    is_named: bool,
+
+    /// Set if this is a union rather than a structure
+    is_union: bool,
+
+    /// Set for `#[repr(packed)]` structures, whose fields may be misaligned
+    is_packed: bool,
+
+    /// Alignment `N` of `#[repr(align(N))]` structures
+    align: Option<String>,
+
+    /// Fields of the structure
+    fields: Vec<Field>,
+
+    /// Options from `#[safecast(...)]` structure attributes
+    options: Vec<StructOption>,
+}
+
+/// Parse the structure definition `item` given to the derive named `derive`
+fn parse_struct(item: Item, derive: &str) -> Result<Struct, Error> {
+    // Only structures and unions have fields we can check
+    if item.keyword != "struct" && item.keyword != "union" {
+        return Err(Error::new(item.span, format!(
+            "Only structures and unions are allowed for {}", derive)));
+    }
+
+    // Make sure this structure is `#[repr(C)]`, or a `#[repr(transparent)]`
+    // newtype which has the same layout as its only non-zero-sized field
+    let reprs = parse_reprs(&item.attrs);
+    if !reprs.iter().any(|x| x == "C" || x == "transparent") {
+        return Err(Error::new(item.span, format!(
+            "Structure must be #[repr(C)] or #[repr(transparent)] for {}",
+            derive)));
+    }
+
+    // Parse the `#[safecast(...)]` options from the structure attributes
+    let options = parse_options(&item.attrs)?;
+
+    // Figure out the type of this structure
+    // Regular: `struct Moose { .. }`
+    // Tuple:   `struct Flat(u32, u32);`
+    // Unit:    `struct Unit;`
+    let span = item.span;
+    let body = item.body.ok_or_else(|| Error::new(span,
+        format!("Unit structures not allowed in {}", derive)))?;
+    let is_named_struct = body.delimiter() == Delimiter::Brace;
+
     // Now parse out all the field names and their types
     // For tuple structs we automatically make a new name which is the ID
     // of the member
     let mut parsed_fields = Vec::new();
-    for (id, field) in fields.split(",").enumerate() {
-        // Named structs have a trailing comma, thus we will have one empty
-        // string at the end of the CSV list
-        if field.len() == 0 { break; }
-
-        let (name, typ) = if is_named_struct {
-            let mut spl = field.split(":");
-            let name = spl.nth(0).expect("Could not parse member name");
-            let typ  = spl.nth(0).expect("Could not parse member type");
-            assert!(spl.next() == None, "Unexpected data after member type");
-            (name.into(), typ)
-        } else {
-            (format!("{}", id), field)
+    for (id, field) in split_commas(body.stream()).into_iter().enumerate() {
+        let mut tokens = field.into_iter().peekable();
+
+        // Pull off any field attributes and the visibility
+        let mut parsed = Field {
+            name: String::new(), span: Span::call_site(), ty: String::new(),
+            endian: None, validate: None, checksum: None,
+            cfg: String::new(),
         };
+        for attr in parse_attributes(&mut tokens)? {
+            parse_field_attribute(&attr, &mut parsed)?;
+        }
+        skip_visibility(&mut tokens);
+        let field: Vec<TokenTree> = tokens.collect();
+        parsed.span = field.first().map(|x| x.span())
+            .unwrap_or_else(|| body.span());
 
-        parsed_fields.push((name, typ));
+        if is_named_struct {
+            // Named fields are `name: type`
+            if field.len() < 3 || !is_punct(&field[1], ':') {
+                return Err(Error::new(parsed.span,
+                    "Could not parse member type"));
+            }
+            parsed.name = field[0].to_string();
+            parsed.ty   = to_string(&field[2..]);
+        } else {
+            if !parsed.cfg.is_empty() {
+                return Err(Error::new(parsed.span,
+                    "#[cfg(...)] on fields requires named fields"));
+            }
+            if parsed.endian.is_some() {
+                return Err(Error::new(parsed.span,
+                    "#[safecast(endian = ...)] requires named fields"));
+            }
+            parsed.name = format!("{}", id);
+            parsed.ty   = to_string(&field);
+        }
+        parsed_fields.push(parsed);
     }
 
-    let mut impltrait = String::new();
+    // In wire mode, make sure no field has a host-dependent byte order
+    if options.iter().any(|x| x.name == "wire") {
+        if let Some(field) =
+                parsed_fields.iter().find(|x| is_host_endian(&x.ty)) {
+            return Err(Error::new(field.span, format!(
+                "Field `{}` of type `{}` has a host-dependent byte order, \
+                 #[safecast(wire)] requires types from `safecast::endian`",
+                field.name, field.ty)));
+        }
+    }
 
-    // Start implementation of Safecast for ident
-    impltrait += &format!("unsafe impl ::safecast::Safecast for {} {{\n",
-                          ident);
+    // In portable mode, make sure no field changes size between targets
+    if options.iter().any(|x| x.name == "portable") {
+        if let Some(field) =
+                parsed_fields.iter().find(|x| is_pointer_sized(&x.ty)) {
+            return Err(Error::new(field.span, format!(
+                "Field `{}` of type `{}` has a target-dependent size, \
+                 #[safecast(portable)] requires fixed-size types",
+                field.name, field.ty)));
+        }
+    }
 
-    // Implement the `safecast` function
-    impltrait += "    fn safecast(&self) {\n";
+    Ok(Struct {
+        name: parse_name(&item.ident, item.generics, item.predicates),
+        span: item.span,
+        is_named: is_named_struct,
+        is_union: item.keyword == "union",
+        is_packed: reprs.iter().any(|x| x.starts_with("packed")),
+        align: reprs.iter().find_map(|x| {
+            Some(x.strip_prefix("align(")?.strip_suffix(')')?.to_string())
+        }),
+        fields: parsed_fields,
+        options,
+    })
+}
+
+#[proc_macro_derive(Safecast, attributes(safecast))]
+pub fn derive_safecast(item: TokenStream) -> TokenStream {
+    impl_safecast(item).unwrap_or_else(Error::into_compile_error)
+}
+
+/// Generate the implementation of `Safecast` for `item`
+fn impl_safecast(item: TokenStream) -> Result<TokenStream, Error> {
+    let Struct { name, is_named, is_union, is_packed, align,
+                 fields: parsed_fields, options, .. } =
+        parse_struct(parse_item(item, "Safecast")?, "Safecast")?;
+
+    if is_union {
+        return Ok(impl_union(&name, &parsed_fields, &options, is_packed)?
+            .parse()
+            .expect("Failed to convert to TokenStream"));
+    }
+    if let Some(option) = options.iter().find(|x| x.name == "size") {
+        return Err(Error::new(option.span,
+            "#[safecast(size = N)] is only supported on unions"));
+    }
+
+    // Assert at compile time that the size of the entire structure matches
+    // the sum of all of it's members, and that it has any requested alignment
+    let mut impltrait = impl_layout_check(&name, &(
+        padding_checks(&parsed_fields, align.as_deref()) +
+        &align_checks(&name, &options)));
+    impltrait += &impl_field_layout(&name, &parsed_fields);
+    impltrait += &impl_checksum(&name, &parsed_fields, is_packed)?;
+    impltrait += &impl_bytemuck(&name, &parsed_fields, &options)?;
+    if options.iter().any(|x| x.name == "debug") {
+        impltrait += &impl_debug(&name);
+    }
+
+    // Start implementation of Safecast for the structure, generic structures
+    // require each of their field types to be `Safecast`
+    impltrait += &format!("unsafe impl{} ::safecast::Safecast for {}{} {{\n",
+        name.generics, name.ty,
+        where_clause(&name, &parsed_fields, "::safecast::Safecast"));
 
-    // Sum of all the sizes of the individual structures
-    impltrait += "        let mut unpadded_struct_size = 0usize;\n";
+    // Implement the `safecast` function, which first makes sure the layout
+    // has been checked for this instantiation of the structure
+    impltrait += "    fn safecast(&self) {\n";
+    impltrait += LAYOUT_CHECK;
 
-    for (name, _ty) in parsed_fields {
+    for field in &parsed_fields {
         // Invoke safecast on this member, this enforces that Safecast is
         // implemented on the type of this member
-        impltrait += &format!("        \
-            ::safecast::Safecast::safecast(&self.{});\n", name);
+        impltrait += &format!("        {}\
+            ::safecast::Safecast::safecast({});\n",
+            field.cfg, field_ref(&field.name, is_packed));
+    }
 
-        // Accumulate the size of the unpadded structure
-        impltrait += &format!("        \
-            unpadded_struct_size += ::core::mem::size_of_val(&self.{});\n",
-            name);
+    // Run any user-supplied validation on the fields, now that the layout
+    // has been validated
+    for field in parsed_fields.iter().filter(|x| x.validate.is_some()) {
+        impltrait += &format!("        {}\
+            assert!({}({}), \"Validation of field `{}` failed: {{}}\", \
+                ::core::any::type_name::<Self>());\n",
+            field.cfg, field.validate.as_ref().unwrap(),
+            field_ref(&field.name, is_packed), field.name);
     }
 
-    // Assert that the size of the entire structure matches the sum of all
-    // of it's members. This ensures that there are no padding bytes in the
-    // structure.
-    //
-    // Note: This `size_of::<Self>()` is what prevents us from using a slice
-    //       in a structure. This is quite important to have here!
-    impltrait += &format!("        \
-        assert!(unpadded_struct_size == ::core::mem::size_of::<Self>(), \
-            \"Safecast not allowed on structures with padding bytes\");\n");
+    // Close braces for the `safecast` function
+    impltrait += "    }\n";
+    impltrait += &impl_check_layout(&parsed_fields,
+                                    |field| field_ref(&field.name, is_packed));
+
+    // The structure is interior mutable if any of its members are
+    impltrait += &interior_mutable(&parsed_fields, "::safecast::Safecast",
+                                   |x| &x.ty);
+    impltrait += &validated(&parsed_fields);
+    impltrait += &checks_values(&parsed_fields);
+
+    // Close braces for the `impl Safecast`
+    impltrait += "}\n";
+
+    // Generate byte order conversions if requested, these are also needed
+    // for the byte array conversions
+    let byteswap = options.iter().any(|x| x.name == "byteswap");
+    let bytes    = options.iter().find(|x| x.name == "bytes");
+    if byteswap || bytes.is_some() {
+        impltrait += &impl_endian(&name, is_named, is_packed, &parsed_fields,
+                                  byteswap);
+    }
+
+    // Generate byte array conversions if requested, the size of a generic
+    // structure cannot be used as an array length
+    if let Some(bytes) = bytes {
+        if !name.generics.is_empty() {
+            return Err(Error::new(bytes.span,
+                "#[safecast(bytes)] is not supported on generic structures"));
+        }
+        impltrait += &impl_bytes(&name);
+    }
+
+    // Generate accessors for fields with a fixed byte order
+    if parsed_fields.iter().any(|x| x.endian.is_some()) {
+        impltrait += &impl_accessors(&name, &parsed_fields, is_packed);
+    }
+
+    Ok(impltrait.parse().expect("Failed to convert to TokenStream"))
+}
 
-    // Close braces for the `safecast` function and the `impl Safecast`
-    impltrait += &format!("    }}\n}}\n");
-    impltrait.parse().expect("Failed to convert to TokenStream")
+/// Primitive integer types usable as the `#[repr(...)]` of an enum
+const ENUM_REPRS: &[&str] = &[
+    "u8", "u16", "u32", "u64", "u128", "usize",
+    "i8", "i16", "i32", "i64", "i128", "isize",
+];
+
+/// A variant of an enum being derived
+struct Variant {
+    /// Name of the variant
+    name: String,
+
+    /// Types of the fields of the variant with insignificant whitespace
+    /// removed, or `None` for variants without fields
+    fields: Option<Vec<String>>,
+
+    /// Explicit discriminant expression, eg. `5` for `A(u32) = 5`
+    discriminant: Option<String>,
 }
 
+/// Parse the tokens of a single enum variant such as `A(u8, U16Le) = 5`
+fn parse_variant(variant: Vec<TokenTree>) -> Result<Variant, Error> {
+    let mut tokens = variant.into_iter().peekable();
+    parse_attributes(&mut tokens)?;
+    let name = tokens.next()
+        .ok_or_else(|| Error::new(Span::call_site(), "Expected variant"))?;
+
+    // Parse the fields of the variant if it has any
+    let mut fields = None;
+    if let Some(TokenTree::Group(group)) = tokens.peek().cloned() {
+        let named = group.delimiter() == Delimiter::Brace;
+
+        let mut types = Vec::new();
+        for field in split_commas(group.stream()) {
+            let mut tokens = field.into_iter().peekable();
+            parse_attributes(&mut tokens)?;
+            let field: Vec<TokenTree> = tokens.collect();
+
+            // Named fields are `name: type`, we only need the type
+            if named {
+                if field.len() < 3 || !is_punct(&field[1], ':') {
+                    return Err(Error::new(field[0].span(),
+                        "Could not parse member type"));
+                }
+                types.push(to_string(&field[2..]));
+            } else {
+                types.push(to_string(&field));
+            }
+        }
+        fields = Some(types);
+        tokens.next();
+    }
+
+    // Anything left over is an explicit discriminant, eg. `= 5`
+    let rest: Vec<TokenTree> = tokens.collect();
+    let discriminant = match rest.split_first() {
+        None => None,
+        Some((eq, expr)) if is_punct(eq, '=') => Some(to_string(expr)),
+        Some((tt, _)) => return Err(Error::new(tt.span(),
+            format!("Malformed variant `{}`", name))),
+    };
+
+    Ok(Variant { name: name.to_string(), fields, discriminant })
+}
+
+/// Generate an implementation of `CheckedSafecast` for the enum `item`.
+/// Fieldless enums validate that the discriminant is one of the variants,
+/// enums with fields additionally validate the fields of the active variant.
+fn impl_checked_enum(item: Item) -> Result<String, Error> {
+    // Find the primitive integer representation of the enum
+    let reprs = parse_reprs(&item.attrs);
+    let repr = reprs.iter()
+        .find(|x| ENUM_REPRS.contains(&x.as_str()))
+        .ok_or_else(|| Error::new(item.span, "Enum must have a primitive \
+            integer #[repr(...)] for CheckedSafecast"))?;
+
+    let ident = &item.ident;
+    if !item.generics.is_empty() {
+        return Err(Error::new(item.span,
+            "Generic enums not allowed in CheckedSafecast"));
+    }
+
+    // Parse all the variants
+    let body = item.body.as_ref()
+        .ok_or_else(|| Error::new(item.span, "Expected enum variants"))?;
+    let variants = split_commas(body.stream()).into_iter()
+        .map(parse_variant).collect::<Result<Vec<_>, _>>()?;
+
+    let mut ret = format!(
+        "unsafe impl ::safecast::CheckedSafecast for {} {{\n", ident);
+    ret += "    fn is_valid_bytes(bytes: &[u8]) -> bool {\n";
+
+    // Read the discriminant, which is always at the start of the enum
+    ret += &format!("        \
+        let mut raw = [0u8; ::core::mem::size_of::<{}>()];\n", repr);
+    ret += "        \
+        assert!(bytes.len() == ::core::mem::size_of::<Self>(), \
+            \"Size mismatch in is_valid_bytes: {} bytes to {} ({} bytes)\", \
+            bytes.len(), ::core::any::type_name::<Self>(), \
+            ::core::mem::size_of::<Self>());\n";
+    ret += &format!("        \
+        raw.copy_from_slice(&bytes[..::core::mem::size_of::<{}>()]);\n",
+        repr);
+    ret += &format!("        let val = <{}>::from_ne_bytes(raw);\n", repr);
+
+    // Fieldless enums can simply compare against every variant
+    if variants.iter().all(|x| x.fields.is_none()) {
+        ret += "        false";
+        for variant in &variants {
+            ret += &format!(" ||\n            val == Self::{} as {}",
+                            variant.name, repr);
+        }
+        ret += "\n    }\n}\n";
+        return Ok(ret);
+    }
+
+    // Enums with fields are only defined to have a layout we can validate
+    // with `#[repr(C, int)]`
+    if !reprs.iter().any(|x| x == "C") {
+        return Err(Error::new(item.span, format!("Enums with fields must \
+            be #[repr(C, {})] for CheckedSafecast", repr)));
+    }
+
+    // The discriminant and fields of every variant must fill the entire enum,
+    // otherwise the variant has padding
+    let mut checks = String::new();
+    for variant in &variants {
+        checks += &format!("        \
+            assert!(::core::mem::size_of::<{}>()", repr);
+        for field in variant.fields.as_deref().unwrap_or(&[]) {
+            checks += &format!(" + ::core::mem::size_of::<{}>()", field);
+        }
+        checks += " == ::core::mem::size_of::<Self>(), \
+            \"Safecast not allowed on structures with padding bytes\");\n";
+    }
+    let name = Name {
+        generics: String::new(), ty: ident.clone(), predicates: String::new(),
+    };
+    ret = impl_layout_check(&name, &checks) + &ret;
+
+    // The layout of a `#[repr(C, int)]` enum is defined as a `#[repr(C)]`
+    // structure of the discriminant followed by a `#[repr(C)]` union of
+    // `#[repr(C)]` structures of each variant's fields. Declare these so we
+    // can find the offsets of the fields.
+    ret += "        \
+        assert!(bytes.as_ptr().cast::<Self>().is_aligned(), \
+            \"Cast alignment mismatch: {:p} to {} (align {})\", \
+            bytes.as_ptr(), ::core::any::type_name::<Self>(), \
+            ::core::mem::align_of::<Self>());\n";
+    for (ii, variant) in variants.iter().enumerate() {
+        ret += &format!("        #[repr(C)] #[allow(dead_code)] \
+            struct Variant{}({});\n", ii,
+            variant.fields.as_deref().unwrap_or(&[]).join(", "));
+    }
+    ret += "        #[repr(C)] #[allow(dead_code)] union Payload {\n";
+    for ii in 0..variants.len() {
+        ret += &format!("            \
+            v{}: ::core::mem::ManuallyDrop<Variant{}>,\n", ii, ii);
+    }
+    ret += "        }\n";
+    ret += &format!("        #[repr(C)] #[allow(dead_code)] \
+        struct Layout({}, Payload);\n", repr);
+    ret += "        let payload = ::core::mem::offset_of!(Layout, 1);\n";
+
+    // Compute the discriminant of each variant, which is either explicit or
+    // one more than the previous variant
+    for (ii, variant) in variants.iter().enumerate() {
+        let discriminant = match (&variant.discriminant, ii) {
+            (Some(expr), _) => expr.clone(),
+            (None, 0)       => "0".into(),
+            (None, _)       => format!("TAG{} + 1", ii - 1),
+        };
+        ret += &format!("        const TAG{}: {} = {};\n",
+                        ii, repr, discriminant);
+    }
+
+    // Validate the fields of the active variant
+    ret += "        match val {\n";
+    for (ii, variant) in variants.iter().enumerate() {
+        let fields = variant.fields.as_deref().unwrap_or(&[]);
+        ret += &format!("            TAG{} => {{\n", ii);
+
+        ret += "                true";
+        for (jj, field) in fields.iter().enumerate() {
+            let bytes = format!("(&bytes[payload + \
+                ::core::mem::offset_of!(Variant{}, {})..]\
+                [..::core::mem::size_of::<{}>()])", ii, jj, field);
+            ret += &format!(" &&\n                    {}",
+                            validate_expr(field, &bytes));
+        }
+        ret += "\n            }\n";
+    }
+    ret += "            _ => false,\n";
+    ret += "        }\n    }\n}\n";
+
+    Ok(ret)
+}
+
+#[proc_macro_derive(CheckedSafecast, attributes(safecast))]
+pub fn derive_checked_safecast(item: TokenStream) -> TokenStream {
+    impl_checked_safecast(item).unwrap_or_else(Error::into_compile_error)
+}
+
+/// Generate the implementation of `CheckedSafecast` for `item`
+fn impl_checked_safecast(item: TokenStream) -> Result<TokenStream, Error> {
+    // Enums are validated by their discriminant rather than their fields
+    let item = parse_item(item, "CheckedSafecast")?;
+    if item.keyword == "enum" {
+        return Ok(impl_checked_enum(item)?.parse()
+            .expect("Failed to convert to TokenStream"));
+    }
+
+    let Struct { name, span, is_union, is_packed, align, fields, options,
+                 .. } = parse_struct(item, "CheckedSafecast")?;
+    if is_union {
+        return Err(Error::new(span, "Unions not allowed in CheckedSafecast"));
+    }
+
+    // `Pod` requires every bit pattern to be valid
+    if let Some(option) = options.iter().find(|x| x.name == "bytemuck") {
+        return Err(Error::new(option.span,
+            "#[safecast(bytemuck)] is not supported on CheckedSafecast, \
+             use #[derive(Safecast)]"));
+    }
+
+    // Assert at compile time that the size of the entire structure matches
+    // the sum of all of it's members, and that it has any requested alignment
+    let mut impltrait = impl_layout_check(&name, &(
+        padding_checks(&fields, align.as_deref()) +
+        &align_checks(&name, &options)));
+    impltrait += &impl_field_layout(&name, &fields);
+    impltrait += &impl_checksum(&name, &fields, is_packed)?;
+    if options.iter().any(|x| x.name == "debug") {
+        impltrait += &impl_debug(&name);
+    }
+
+    // Start implementation of CheckedSafecast for the structure, generic
+    // structures require each of their field element types to be
+    // `CheckedSafecast`
+    impltrait += &format!(
+        "unsafe impl{} ::safecast::CheckedSafecast for {}",
+        name.generics, name.ty);
+    if !name.generics.is_empty() || !name.predicates.is_empty() {
+        impltrait += &format!(" where {}", name.predicates);
+    }
+    if !name.generics.is_empty() {
+        for field in &fields {
+            impltrait += &format!("{}: ::safecast::CheckedSafecast, ",
+                                  element_type(&field.ty));
+        }
+    }
+    impltrait += " {\n";
+
+    // Implement the `is_valid_bytes` function
+    impltrait += "    fn is_valid_bytes(bytes: &[u8]) -> bool {\n";
+
+    // Make sure the bytes can be split up into the fields
+    impltrait += "        \
+        assert!(bytes.len() == ::core::mem::size_of::<Self>(), \
+            \"Size mismatch in is_valid_bytes: {} bytes to {} ({} bytes)\", \
+            bytes.len(), ::core::any::type_name::<Self>(), \
+            ::core::mem::size_of::<Self>());\n";
+    impltrait += "        \
+        assert!(bytes.as_ptr().cast::<Self>().is_aligned(), \
+            \"Cast alignment mismatch: {:p} to {} (align {})\", \
+            bytes.as_ptr(), ::core::any::type_name::<Self>(), \
+            ::core::mem::align_of::<Self>());\n";
+
+    // Make sure the layout has been checked for this instantiation
+    impltrait += LAYOUT_CHECK;
+
+    // Validate the bytes of each field in turn
+    for field in &fields {
+        impltrait += &format!("        {}{{\n            \
+            let bytes = &bytes[::core::mem::offset_of!(Self, {})..]\
+                [..::core::mem::size_of::<{}>()];\n",
+            field.cfg, field.name, field.ty);
+
+        // Fields of packed structures may be misaligned, validate a suitably
+        // aligned copy of their bytes instead
+        if is_packed {
+            impltrait += &format!("            \
+                let mut aligned = ::core::mem::MaybeUninit::<{}>::uninit();\n\
+                let bytes = unsafe {{ \
+                    ::core::ptr::copy_nonoverlapping(bytes.as_ptr(), \
+                        aligned.as_mut_ptr() as *mut u8, bytes.len()); \
+                    ::core::slice::from_raw_parts(\
+                        aligned.as_ptr() as *const u8, bytes.len()) }};\n",
+                field.ty);
+        }
+        impltrait += &format!("            if !{} {{ return false; }}\n",
+                              validate_expr(&field.ty, "bytes"));
+
+        // Run any user-supplied validation on the field, which is valid and
+        // suitably aligned at this point
+        if let Some(validate) = &field.validate {
+            impltrait += &format!("            \
+                if !{}(unsafe {{ &*(bytes.as_ptr() as *const {}) }}) {{ \
+                    return false; }}\n",
+                validate, field.ty);
+        }
+        impltrait += "        }\n";
+    }
+    impltrait += "        true\n    }\n";
+
+    // The structure is interior mutable if any of its members are
+    impltrait += &interior_mutable(&fields, "::safecast::CheckedSafecast",
+                                   |x| element_type(&x.ty));
+
+    // Close braces for the `impl CheckedSafecast`
+    impltrait += "}\n";
+
+    Ok(impltrait.parse().expect("Failed to convert to TokenStream"))
+}
+
+/// Attribute generating a unit test which round-trips a `Safecast` type
+/// through its bytes with several byte patterns, see
+/// `safecast::asserts::check_roundtrip`
+///
+/// Generic types are tested with the instantiation given to the attribute,
+/// eg. `#[safecast_roundtrip_test(Frame<4>)]`.
+#[proc_macro_attribute]
+pub fn safecast_roundtrip_test(attr: TokenStream, item: TokenStream)
+        -> TokenStream {
+    // The item is emitted unchanged even on errors, such that uses of it do
+    // not produce errors of their own
+    let test = impl_roundtrip_test(attr, item.clone())
+        .unwrap_or_else(Error::into_compile_error);
+    item.into_iter().chain(test).collect()
+}
+
+/// Generate the round-trip test for `item`, an item given to
+/// `#[safecast_roundtrip_test]` with the arguments `attr`
+fn impl_roundtrip_test(attr: TokenStream, item: TokenStream)
+        -> Result<TokenStream, Error> {
+    let item = parse_item(item, "#[safecast_roundtrip_test]")?;
+
+    // Generic types must be given the instantiation to test
+    let attr = attr.into_iter().collect::<Vec<_>>();
+    let ty = if !attr.is_empty() {
+        to_string(&attr)
+    } else if item.generics.is_empty() {
+        item.ident.clone()
+    } else {
+        return Err(Error::new(item.span,
+            format!("Generic types need the instantiation to test, eg. \
+                     #[safecast_roundtrip_test({}<...>)]", item.ident)));
+    };
+
+    // Raw identifiers such as `r#Type` get a `safecast_roundtrip_Type()` test
+    Ok(format!("#[cfg(test)]\n#[test]\n#[allow(non_snake_case)]\n\
+        fn safecast_roundtrip_{}() {{\n    \
+            ::safecast::asserts::check_roundtrip::<{}>();\n}}\n",
+        item.ident.trim_start_matches("r#"), ty)
+        .parse()
+        .expect("Failed to convert to TokenStream"))
+}
@@ -0,0 +1,252 @@
+//! Wrapper types for controlling alignment
+//!
+//! `Unaligned<T>` lowers the alignment of a `Safecast` type to 1, allowing
+//! naturally misaligned fields to be embedded in structures without padding.
+//!
+//! `Aligned<A, T>` and `AlignedBytes<N, A>` raise the alignment of a value or
+//! byte buffer to that of the marker type `A` (eg. `Align16`), guaranteeing
+//! that casts out of them pass the alignment checks.
+
+use crate::Safecast;
+use crate::endian::Endian;
+
+/// A `T` stored with an alignment of 1
+///
+/// Since the inner value may be misaligned it cannot be referenced directly,
+/// thus it is accessed by value with `get()` and `set()`, which perform
+/// unaligned reads and writes.
+#[repr(C, packed)]
+pub struct Unaligned<T: Safecast>(T);
+
+impl<T: Safecast> Unaligned<T> {
+    /// Wrap `val`
+    pub const fn new(val: T) -> Self {
+        Unaligned(val)
+    }
+
+    /// Get a copy of the inner value using an unaligned read
+    pub fn get(&self) -> T {
+        unsafe { core::ptr::read_unaligned(core::ptr::addr_of!(self.0)) }
+    }
+
+    /// Set the inner value to `val` using an unaligned write
+    pub fn set(&mut self, val: T) {
+        unsafe {
+            core::ptr::write_unaligned(core::ptr::addr_of_mut!(self.0), val)
+        }
+    }
+
+    /// Unwrap the inner value
+    pub fn into_inner(self) -> T {
+        self.0
+    }
+}
+
+impl<T: Safecast + Copy> Clone for Unaligned<T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T: Safecast + Copy> Copy for Unaligned<T> {}
+
+impl<T: Safecast + Default> Default for Unaligned<T> {
+    fn default() -> Self {
+        Unaligned(T::default())
+    }
+}
+
+impl<T: Safecast + PartialEq> PartialEq for Unaligned<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.get() == other.get()
+    }
+}
+
+impl<T: Safecast + Eq> Eq for Unaligned<T> {}
+
+impl<T: Safecast + core::fmt::Debug> core::fmt::Debug for Unaligned<T> {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+        f.debug_tuple("Unaligned").field(&self.get()).finish()
+    }
+}
+
+impl<T: Safecast> From<T> for Unaligned<T> {
+    fn from(val: T) -> Self {
+        Unaligned(val)
+    }
+}
+
+// `Unaligned<T>` has the same size as `T` and only lowers the alignment, thus
+// it has no padding as long as `T` has none. We validate `T` on an aligned
+// copy of the inner value.
+unsafe impl<T: Safecast> Safecast for Unaligned<T> {
+    fn safecast(&self) {
+        let val = core::mem::ManuallyDrop::new(self.get());
+        Safecast::safecast(&*val);
+    }
+
+    fn check_layout(&self) {
+        let val = core::mem::ManuallyDrop::new(self.get());
+        Safecast::check_layout(&*val);
+    }
+
+    const INTERIOR_MUTABLE: bool = T::INTERIOR_MUTABLE;
+    const VALIDATED: bool = T::VALIDATED;
+    const CHECKS_VALUES: bool = T::CHECKS_VALUES;
+}
+
+impl<T: Endian> Endian for Unaligned<T> {
+    fn swap_bytes(&self) -> Self {
+        Unaligned(Endian::swap_bytes(&self.get()))
+    }
+
+    fn to_le(&self) -> Self {
+        Unaligned(Endian::to_le(&self.get()))
+    }
+
+    fn to_be(&self) -> Self {
+        Unaligned(Endian::to_be(&self.get()))
+    }
+}
+
+/// Marker types which carry an alignment and nothing else
+pub trait Alignment: Copy + Default {}
+
+macro_rules! alignment {
+    ($($name:ident, $align:literal);*) => {
+        $(
+            #[doc = concat!("Marker type with an alignment of ",
+                            stringify!($align))]
+            #[derive(Clone, Copy, Default, Debug, PartialEq, Eq, Hash)]
+            #[repr(align($align))]
+            pub struct $name;
+
+            impl Alignment for $name {}
+        )*
+    }
+}
+
+alignment!(Align1,    1; Align2,    2; Align4,    4; Align8,       8;
+           Align16,  16; Align32,  32; Align64,  64; Align128,   128;
+           Align256, 256; Align512, 512; Align1024, 1024; Align2048, 2048;
+           Align4096, 4096);
+
+/// A `T` stored with at least the alignment of the marker type `A`
+///
+/// If the alignment of `A` causes the size of `Aligned<A, T>` to differ from
+/// the size of `T` then there is trailing padding, and the `Safecast`
+/// routines will panic.
+#[derive(Clone, Copy, Default, Debug, PartialEq, Eq, Hash)]
+#[repr(C)]
+pub struct Aligned<A: Alignment, T> {
+    /// Zero-sized array which forces the alignment of `A`
+    _align: [A; 0],
+
+    /// The wrapped value
+    value: T,
+}
+
+impl<A: Alignment, T> Aligned<A, T> {
+    /// Wrap `val`
+    pub const fn new(val: T) -> Self {
+        Aligned { _align: [], value: val }
+    }
+
+    /// Unwrap the inner value
+    pub fn into_inner(self) -> T {
+        self.value
+    }
+
+    /// Raising the alignment may have introduced trailing padding
+    fn check_padding() {
+        assert!(core::mem::size_of::<Self>() == core::mem::size_of::<T>(),
+            "Safecast not allowed on structures with padding bytes: {}",
+            core::any::type_name::<Self>());
+    }
+}
+
+impl<A: Alignment, T> core::ops::Deref for Aligned<A, T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.value
+    }
+}
+
+impl<A: Alignment, T> core::ops::DerefMut for Aligned<A, T> {
+    fn deref_mut(&mut self) -> &mut T {
+        &mut self.value
+    }
+}
+
+unsafe impl<A: Alignment, T: Safecast> Safecast for Aligned<A, T> {
+    fn safecast(&self) {
+        Safecast::safecast(&self.value);
+        Self::check_padding();
+    }
+
+    fn check_layout(&self) {
+        Safecast::check_layout(&self.value);
+        Self::check_padding();
+    }
+
+    const INTERIOR_MUTABLE: bool = T::INTERIOR_MUTABLE;
+
+    // Only padding-free instantiations can skip the checks
+    const VALIDATED: bool = T::VALIDATED &&
+        core::mem::size_of::<Self>() == core::mem::size_of::<T>();
+    const CHECKS_VALUES: bool = T::CHECKS_VALUES;
+}
+
+/// A buffer of `N` bytes with at least the alignment of the marker type `A`,
+/// which defaults to 16 bytes
+///
+/// If `N` is not a multiple of the alignment of `A` then there is trailing
+/// padding, and the `Safecast` routines will panic.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[repr(C)]
+pub struct AlignedBytes<const N: usize, A: Alignment = Align16> {
+    /// Zero-sized array which forces the alignment of `A`
+    _align: [A; 0],
+
+    /// The bytes
+    bytes: [u8; N],
+}
+
+impl<const N: usize, A: Alignment> AlignedBytes<N, A> {
+    /// Create a new zeroed buffer
+    pub const fn new() -> Self {
+        AlignedBytes { _align: [], bytes: [0; N] }
+    }
+}
+
+impl<const N: usize, A: Alignment> Default for AlignedBytes<N, A> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<const N: usize, A: Alignment> core::ops::Deref for AlignedBytes<N, A> {
+    type Target = [u8; N];
+
+    fn deref(&self) -> &[u8; N] {
+        &self.bytes
+    }
+}
+
+impl<const N: usize, A: Alignment> core::ops::DerefMut
+        for AlignedBytes<N, A> {
+    fn deref_mut(&mut self) -> &mut [u8; N] {
+        &mut self.bytes
+    }
+}
+
+unsafe impl<const N: usize, A: Alignment> Safecast for AlignedBytes<N, A> {
+    fn safecast(&self) {
+        // Raising the alignment may have introduced trailing padding
+        assert!(core::mem::size_of::<Self>() == N,
+            "Safecast not allowed on structures with padding bytes: {}",
+            core::any::type_name::<Self>());
+    }
+    const VALIDATED: bool = core::mem::size_of::<Self>() == N;
+}
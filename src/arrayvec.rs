@@ -0,0 +1,55 @@
+//! Interoperability with `arrayvec`
+//!
+//! `ArrayVecPodExt` is implemented for `arrayvec::ArrayVec<u8, N>` with the
+//! same methods as `safecast::heapless::HeaplessPodExt`, such that values
+//! can be appended to stack-allocated buffers and parsed back out of them,
+//! with capacity overflows reported as errors. Requires the `arrayvec`
+//! feature.
+
+use crate::{CheckedSafecast, IntoBytes, SafecastError};
+use crate::reader::peek;
+
+/// Extension trait for using an `ArrayVec<u8, N>` as a buffer of values
+pub trait ArrayVecPodExt {
+    /// Append the bytes of `val`
+    ///
+    /// Returns an `OutOfBounds` error if the remaining capacity cannot hold
+    /// a `T`, in which case nothing is written.
+    fn push_pod<T: IntoBytes>(&mut self, val: &T) -> Result<(), SafecastError>;
+
+    /// Remove and return the `T` at the end of the buffer
+    ///
+    /// There are no alignment requirements on the buffer. Returns an
+    /// `OutOfBounds` error if the buffer is shorter than a `T`, or an
+    /// `InvalidValue` error if the bytes do not hold a valid `T`, in which
+    /// case nothing is removed.
+    fn pop_pod<T: CheckedSafecast>(&mut self) -> Result<T, SafecastError>;
+
+    /// View the buffer as a slice of `T`s without copying
+    ///
+    /// Returns the same errors as `safecast::mmap::try_cast_slice`. The
+    /// bytes of an `ArrayVec` are only aligned to 1, thus this can only
+    /// succeed for types with larger alignments if the buffer happens to be
+    /// suitably aligned.
+    fn view_pods<T: CheckedSafecast>(&self) -> Result<&[T], SafecastError>;
+}
+
+impl<const N: usize> ArrayVecPodExt for ::arrayvec::ArrayVec<u8, N> {
+    fn push_pod<T: IntoBytes>(&mut self, val: &T)
+            -> Result<(), SafecastError> {
+        self.try_extend_from_slice(val.as_bytes())
+            .map_err(|_| SafecastError::OutOfBounds)
+    }
+
+    fn pop_pod<T: CheckedSafecast>(&mut self) -> Result<T, SafecastError> {
+        let start = self.len().checked_sub(core::mem::size_of::<T>())
+            .ok_or(SafecastError::OutOfBounds)?;
+        let ret = peek(&self[start..])?;
+        self.truncate(start);
+        Ok(ret)
+    }
+
+    fn view_pods<T: CheckedSafecast>(&self) -> Result<&[T], SafecastError> {
+        crate::mmap::try_cast_slice(self)
+    }
+}
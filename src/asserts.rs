@@ -0,0 +1,158 @@
+//! Static assertions for downstream types
+//!
+//! `assert_pod!` and `assert_no_padding!` fail to compile if a type is not
+//! `Safecast` or has padding, and `assert_layout_eq!` if two types differ in
+//! layout. They can be placed at module scope next to the types they
+//! describe, documenting the intent and catching regressions even for types
+//! whose cast paths are not covered by tests. `check_roundtrip` checks that a
+//! type survives round-trips through its bytes, and is the test generated by
+//! `#[safecast_roundtrip_test]`.
+
+use core::any::type_name;
+use crate::Safecast;
+use crate::layout::Layout;
+
+/// Returns `true` if the fields of `T` leave any of its bytes uncovered
+///
+/// Fields are in increasing order of offset, other than union variants which
+/// all start at zero, thus any byte past the end of the fields so far and
+/// before the next field is padding.
+#[doc(hidden)]
+pub const fn has_padding<T: Layout>() -> bool {
+    let mut end = 0;
+    let mut ii = 0;
+    while ii < T::FIELDS.len() {
+        let field = &T::FIELDS[ii];
+        if field.offset > end {
+            return true;
+        }
+        if field.offset + field.size > end {
+            end = field.offset + field.size;
+        }
+        ii += 1;
+    }
+    end != core::mem::size_of::<T>()
+}
+
+/// Fail to compile unless every given type implements `Safecast`, eg.
+/// `assert_pod!(Header, [Header; 4], u64)`
+#[macro_export]
+macro_rules! assert_pod {
+    ($($ty:ty),+ $(,)?) => {
+        const _: fn() = || {
+            fn assert_pod<T: $crate::Safecast + ?Sized>() {}
+            $(assert_pod::<$ty>();)+
+        };
+    }
+}
+
+/// Fail to compile if a type has padding bytes
+///
+/// Types implementing `Layout` are checked against their fields, eg.
+/// `assert_no_padding!(Header)`. Any other type can be checked by listing the
+/// types of its fields, in which case their sizes must add up to the size of
+/// the type, eg. `assert_no_padding!(Header: u32, u16, u16)`.
+#[macro_export]
+macro_rules! assert_no_padding {
+    ($ty:ty) => {
+        const _: () = assert!(!$crate::asserts::has_padding::<$ty>(),
+            concat!("`", stringify!($ty), "` has padding bytes"));
+    };
+    ($ty:ty: $($field:ty),+ $(,)?) => {
+        const _: () = assert!(
+            0 $(+ ::core::mem::size_of::<$field>())+ ==
+                ::core::mem::size_of::<$ty>(),
+            concat!("`", stringify!($ty), "` has padding bytes"));
+    };
+}
+
+/// Returns `true` if `A` and `B` have the same number of fields, with each
+/// pair of fields at the same offset and of the same size. Field names are
+/// not compared.
+#[doc(hidden)]
+pub const fn same_fields<A: Layout, B: Layout>() -> bool {
+    if A::FIELDS.len() != B::FIELDS.len() {
+        return false;
+    }
+
+    let mut ii = 0;
+    while ii < A::FIELDS.len() {
+        let (a, b) = (&A::FIELDS[ii], &B::FIELDS[ii]);
+        if a.offset != b.offset || a.size != b.size {
+            return false;
+        }
+        ii += 1;
+    }
+    true
+}
+
+/// Fail to compile unless two `Safecast` types have the same size and
+/// alignment
+///
+/// This is meant for mirrors of C structures, such that the mirror is checked
+/// on every build rather than at the first cast. When both types implement
+/// `Layout`, `assert_layout_eq!(A, B, fields)` additionally checks that their
+/// fields are at the same offsets and of the same sizes. `compare_layouts`
+/// reports where the layouts diverge when this fails.
+#[macro_export]
+macro_rules! assert_layout_eq {
+    ($a:ty, $b:ty) => {
+        $crate::assert_pod!($a, $b);
+        const _: () = assert!(
+            ::core::mem::size_of::<$a>() == ::core::mem::size_of::<$b>(),
+            concat!("`", stringify!($a), "` and `", stringify!($b),
+                    "` differ in size"));
+        const _: () = assert!(
+            ::core::mem::align_of::<$a>() == ::core::mem::align_of::<$b>(),
+            concat!("`", stringify!($a), "` and `", stringify!($b),
+                    "` differ in alignment"));
+    };
+    ($a:ty, $b:ty, fields) => {
+        $crate::assert_layout_eq!($a, $b);
+        const _: () = assert!($crate::asserts::same_fields::<$a, $b>(),
+            concat!("`", stringify!($a), "` and `", stringify!($b),
+                    "` differ in the layout of their fields"));
+    };
+}
+
+/// Check that `T` round-trips through its bytes with several byte patterns,
+/// panicking if it does not
+///
+/// For each pattern the bytes are cast to a `T` and back, and the `T` is
+/// copied out to bytes and cast back to a `T`, with the bytes compared at
+/// every step. The patterns are all zeros, all ones, alternating bits,
+/// incrementing bytes, and pseudo-random bytes, thus types with `validate`
+/// fields must accept all of them. This is the test generated by
+/// `#[safecast_roundtrip_test]`.
+pub fn check_roundtrip<T: Safecast>() {
+    // Pseudo-random bytes from a fixed seed, such that failures reproduce
+    let mut seed = 0x2545f4914f6cdd1du64;
+    let mut random = move |_| {
+        seed ^= seed << 13;
+        seed ^= seed >> 7;
+        seed ^= seed << 17;
+        seed as u8
+    };
+
+    let patterns: [&mut dyn FnMut(usize) -> u8; 6] = [
+        &mut |_| 0x00, &mut |_| 0xff, &mut |_| 0x55, &mut |_| 0xaa,
+        &mut |ii| ii as u8, &mut random,
+    ];
+    for pattern in patterns {
+        // Bytes to a `T` and back to bytes
+        let mut bytes = T::zeroed();
+        for (ii, byte) in bytes.cast_mut::<u8>().iter_mut().enumerate() {
+            *byte = pattern(ii);
+        }
+        let val: T = bytes.cast::<u8>().cast_copy();
+        assert!(val.cast::<u8>() == bytes.cast::<u8>(),
+                "Bytes of {} changed in a round-trip", type_name::<T>());
+
+        // A `T` to bytes and back to a `T`
+        let mut copy = T::zeroed();
+        val.cast_copy_into(copy.cast_mut::<u8>());
+        let back: T = copy.cast::<u8>().cast_copy();
+        assert!(back.cast::<u8>() == val.cast::<u8>(),
+                "{} changed in a round-trip", type_name::<T>());
+    }
+}
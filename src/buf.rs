@@ -0,0 +1,67 @@
+//! Integration with the `bytes` crate
+//!
+//! `BufPodExt` and `BufMutPodExt` are implemented for every `bytes::Buf` and
+//! `bytes::BufMut`, such that values can be pulled straight out of `Bytes`
+//! and `BytesMut` buffers with `buf.get_pod::<Header>()?`, eg. in tokio
+//! codecs. Requires the `bytes` feature.
+
+use ::bytes::{Buf, BufMut};
+use crate::{CheckedSafecast, IntoBytes, SafecastError};
+
+/// Extension trait for reading values out of any `bytes::Buf`
+pub trait BufPodExt: Buf {
+    /// Read a `T`, advancing the buffer past it
+    ///
+    /// When the `T` is contiguous in the current chunk, as is always the
+    /// case for `Bytes`, it is read directly out of the chunk. Otherwise it
+    /// is gathered from multiple chunks. Returns an `OutOfBounds` error if
+    /// fewer than `size_of::<T>()` bytes remain, in which case nothing is
+    /// consumed, or an `InvalidValue` error if the bytes do not hold a valid
+    /// `T`, in which case the bytes of the `T` are still consumed.
+    fn get_pod<T: CheckedSafecast>(&mut self) -> Result<T, SafecastError> {
+        let size = core::mem::size_of::<T>();
+        if self.remaining() < size {
+            return Err(SafecastError::OutOfBounds);
+        }
+
+        // Fast path, the whole `T` is in the current chunk
+        if let Some(bytes) = self.chunk().get(..size) {
+            let ret = T::try_read_from(bytes);
+            self.advance(size);
+            return ret;
+        }
+
+        // Zero the storage first, such that the buffer is only ever copied
+        // into initialized bytes
+        let mut ret = core::mem::MaybeUninit::<T>::zeroed();
+        let bytes = unsafe {
+            core::slice::from_raw_parts_mut(ret.as_mut_ptr() as *mut u8, size)
+        };
+        self.copy_to_slice(bytes);
+
+        // Validate the bit pattern
+        if !T::is_valid_bytes(bytes) {
+            return Err(SafecastError::InvalidValue);
+        }
+
+        // Safe to assume initialized here because we filled in _all_ the
+        // bytes, and they were validated to hold a `T`
+        Ok(unsafe { ret.assume_init() })
+    }
+}
+
+impl<B: Buf + ?Sized> BufPodExt for B {}
+
+/// Extension trait for writing values to any `bytes::BufMut`
+pub trait BufMutPodExt: BufMut {
+    /// Write the bytes of `val`
+    ///
+    /// Like the other `put` methods of `BufMut`, this panics if there is not
+    /// enough remaining capacity, which never happens for `BytesMut` and
+    /// `Vec<u8>` as they grow.
+    fn put_pod<T: IntoBytes>(&mut self, val: &T) {
+        self.put_slice(val.as_bytes());
+    }
+}
+
+impl<B: BufMut + ?Sized> BufMutPodExt for B {}
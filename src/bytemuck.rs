@@ -0,0 +1,59 @@
+//! Interoperability with `bytemuck`
+//!
+//! `Bytemuck<T>` wraps a `bytemuck::Pod` type such that it can be used
+//! anywhere a `Safecast` type is expected, eg. `bytes.cast::<Bytemuck<V>>()`.
+//!
+//! In the other direction, `#[safecast(bytemuck)]` makes `#[derive(Safecast)]`
+//! also implement `Pod` and `Zeroable`, and the fixed-endianness integers and
+//! network types of this crate implement them as well, thus a structure only
+//! needs a single derive to be usable with both crates. Requires the
+//! `bytemuck` feature.
+
+use crate::Safecast;
+use crate::endian::{U16Le, U32Le, U64Le, U128Le, I16Le, I32Le, I64Le, I128Le};
+use crate::endian::{U16Be, U32Be, U64Be, U128Be, I16Be, I32Be, I64Be, I128Be};
+use crate::net::{U24Be, Checksum16, MacAddr, Ipv4Addr, Ipv6Addr};
+
+pub use ::bytemuck::{Pod, Zeroable};
+
+/// A `bytemuck::Pod` type usable as a `Safecast` type
+///
+/// `Pod` types have no padding, no invalid bit patterns, and no interior
+/// mutability, thus they uphold everything `Safecast` requires.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Default, Debug)]
+#[repr(transparent)]
+pub struct Bytemuck<T: Pod>(pub T);
+
+unsafe impl<T: Pod> Safecast for Bytemuck<T> {
+    fn safecast(&self) {}
+    const VALIDATED: bool = true;
+}
+
+impl<T: Pod> core::ops::Deref for Bytemuck<T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.0
+    }
+}
+
+impl<T: Pod> core::ops::DerefMut for Bytemuck<T> {
+    fn deref_mut(&mut self) -> &mut T {
+        &mut self.0
+    }
+}
+
+// These are all `repr(transparent)` wrappers of byte arrays, so every bit
+// pattern is valid and zero is too
+macro_rules! pod {
+    ($($ty:ty),*) => {
+        $(
+            unsafe impl Zeroable for $ty {}
+            unsafe impl Pod for $ty {}
+        )*
+    }
+}
+
+pod!(U16Le, U32Le, U64Le, U128Le, I16Le, I32Le, I64Le, I128Le,
+     U16Be, U32Be, U64Be, U128Be, I16Be, I32Be, I64Be, I128Be,
+     U24Be, Checksum16, MacAddr, Ipv4Addr, Ipv6Addr);
@@ -0,0 +1,325 @@
+//! Checked casts for types with invalid bit patterns
+//!
+//! `Safecast` types can be created from any bytes at all. Types such as
+//! `bool`, `char`, and enums have no padding but only some bit patterns are
+//! valid, thus the bytes must be validated before they can be used. The
+//! `CheckedSafecast` trait performs this validation, returning an error for
+//! invalid bytes rather than producing an invalid value.
+//!
+//! Every `Safecast` type is also `CheckedSafecast`, where every bit pattern
+//! is considered valid.
+//!
+//! Casting a value to bytes is possible for more types than casting bytes to
+//! a value. The `IntoBytes` and `FromBytes` traits represent each direction
+//! on its own:
+//!
+//! - `Safecast` types are both `IntoBytes` and `FromBytes`
+//! - `CheckedSafecast` types are `IntoBytes`, as every valid value of them
+//!   has no padding, but bytes must be validated with `try_from_bytes`
+
+use core::any::type_name;
+use crate::{Safecast, SafecastError, run_checks};
+
+/// Trait for types with no padding which can be created from bytes after
+/// validating their bit pattern
+///
+/// # Safety
+///
+/// Implementors must not contain any padding bytes, and `is_valid_bytes`
+/// must return `false` for any bytes which do not hold a valid `Self`. Like
+/// `Safecast::safecast`, `is_valid_bytes` must panic if the layout of `Self`
+/// cannot be guaranteed to have no padding.
+pub unsafe trait CheckedSafecast: Sized {
+    /// Returns `true` if `bytes` holds a valid `Self`
+    ///
+    /// `bytes` must be exactly `size_of::<Self>()` bytes long and suitably
+    /// aligned for `Self`, otherwise this panics.
+    fn is_valid_bytes(bytes: &[u8]) -> bool;
+
+    /// Set for types containing interior mutability, see
+    /// `Safecast::INTERIOR_MUTABLE`
+    const INTERIOR_MUTABLE: bool = false;
+
+    /// Interpret `bytes` as a reference to a `Self` without copying
+    ///
+    /// Returns an error if the length of `bytes` does not exactly match the
+    /// size of `Self`, if `bytes` is not suitably aligned for `Self`, or if
+    /// `bytes` does not hold a valid `Self`
+    fn try_from_bytes(bytes: &[u8]) -> Result<&Self, SafecastError> {
+        // Shared references must not be able to mutate each other
+        cast_assert!(!Self::INTERIOR_MUTABLE,
+                "Shared cast of interior mutable type: {}",
+                type_name::<Self>());
+
+        check_bytes::<Self>(bytes)?;
+
+        // Bytes have been validated to hold a `Self`
+        Ok(unsafe { &*(bytes.as_ptr() as *const Self) })
+    }
+
+    /// Interpret `bytes` as a mutable reference to a `Self` without copying
+    ///
+    /// Only valid values of `Self` can be written through the returned
+    /// reference, thus `bytes` remains valid. Returns the same errors as
+    /// `try_from_bytes`.
+    fn try_from_bytes_mut(bytes: &mut [u8])
+            -> Result<&mut Self, SafecastError> {
+        check_bytes::<Self>(bytes)?;
+
+        // Bytes have been validated to hold a `Self`
+        Ok(unsafe { &mut *(bytes.as_mut_ptr() as *mut Self) })
+    }
+
+    /// Copy `bytes` into a new `Self`
+    ///
+    /// Unlike `try_from_bytes`, this has no alignment requirements on
+    /// `bytes`. Returns an error if the length of `bytes` does not exactly
+    /// match the size of `Self` or if `bytes` does not hold a valid `Self`.
+    fn try_read_from(bytes: &[u8]) -> Result<Self, SafecastError> {
+        // Validate the size
+        if bytes.len() != core::mem::size_of::<Self>() {
+            return Err(SafecastError::size_mismatch(bytes.len(),
+                core::mem::size_of::<Self>(), core::mem::size_of::<Self>()));
+        }
+
+        // Copy into aligned storage so the bytes can be validated in place
+        let mut ret = core::mem::MaybeUninit::<Self>::uninit();
+        let copied = unsafe {
+            core::ptr::copy_nonoverlapping(
+                bytes.as_ptr(), ret.as_mut_ptr() as *mut u8, bytes.len());
+            core::slice::from_raw_parts(ret.as_ptr() as *const u8,
+                                        bytes.len())
+        };
+
+        if !Self::is_valid_bytes(copied) {
+            return Err(SafecastError::InvalidValue);
+        }
+
+        // Safe to assume initialized here because we filled in _all_ the
+        // bytes, and they were validated to hold a `Self`
+        Ok(unsafe { ret.assume_init() })
+    }
+}
+
+/// Make sure `bytes` is exactly the size of a `T`, is suitably aligned for a
+/// `T`, and holds a valid `T`
+fn check_bytes<T: CheckedSafecast>(bytes: &[u8]) -> Result<(), SafecastError> {
+    // Make sure we're not working with zero-size-types
+    cast_assert!(core::mem::size_of::<T>() > 0,
+            "ZST not allowed: {}", type_name::<T>());
+
+    // Validate the size
+    if bytes.len() != core::mem::size_of::<T>() {
+        return Err(SafecastError::size_mismatch(bytes.len(),
+            core::mem::size_of::<T>(), core::mem::size_of::<T>()));
+    }
+
+    // Validate alignment
+    if !bytes.as_ptr().cast::<T>().is_aligned() {
+        return Err(SafecastError::AlignmentMismatch);
+    }
+
+    // Validate the bit pattern
+    if !T::is_valid_bytes(bytes) {
+        return Err(SafecastError::InvalidValue);
+    }
+
+    Ok(())
+}
+
+// Every bit pattern of a `Safecast` type is valid, we only have to run the
+// runtime layout checks on it
+unsafe impl<T: Safecast> CheckedSafecast for T {
+    fn is_valid_bytes(bytes: &[u8]) -> bool {
+        cast_assert!(bytes.len() == core::mem::size_of::<T>(),
+                "Size mismatch in is_valid_bytes: {} bytes to {} ({} bytes)",
+                bytes.len(), type_name::<T>(), core::mem::size_of::<T>());
+        cast_assert!(bytes.as_ptr().cast::<T>().is_aligned(),
+                "Cast alignment mismatch: {:p} to {} (align {})",
+                bytes.as_ptr(), type_name::<T>(), core::mem::align_of::<T>());
+
+        // Validate runtime checks on the bytes as a `T`
+        run_checks(unsafe { &*(bytes.as_ptr() as *const T) });
+        true
+    }
+
+    const INTERIOR_MUTABLE: bool = <T as Safecast>::INTERIOR_MUTABLE;
+}
+
+/// Trait for types whose values can be viewed as bytes
+///
+/// This is implemented for every `CheckedSafecast` type, and thus every
+/// `Safecast` type.
+///
+/// # Safety
+///
+/// Implementors must not contain any padding bytes. `check_into_bytes` must
+/// panic if this cannot be guaranteed, or if `Self` contains interior
+/// mutability.
+pub unsafe trait IntoBytes {
+    /// Runtime checks that the bytes of `self` may be viewed through a shared
+    /// reference
+    fn check_into_bytes(&self);
+
+    /// Get the bytes of `self`
+    fn as_bytes(&self) -> &[u8] {
+        self.check_into_bytes();
+
+        unsafe {
+            core::slice::from_raw_parts(self as *const Self as *const u8,
+                                        core::mem::size_of_val(self))
+        }
+    }
+
+    /// Get the bytes of `self` mutably
+    ///
+    /// As any bytes written must form a valid `Self`, this requires `Self` to
+    /// also be `FromBytes`
+    fn as_bytes_mut(&mut self) -> &mut [u8] where Self: FromBytes {
+        self.check_into_bytes();
+
+        unsafe {
+            core::slice::from_raw_parts_mut(self as *mut Self as *mut u8,
+                                            core::mem::size_of_val(self))
+        }
+    }
+
+    /// Copy the bytes of `self` into `dest`
+    ///
+    /// Returns an error if the length of `dest` does not exactly match the
+    /// size of `self`
+    fn write_to(&self, dest: &mut [u8]) -> Result<(), SafecastError> {
+        // Validate the size
+        if dest.len() != core::mem::size_of_val(self) {
+            return Err(SafecastError::size_mismatch(
+                core::mem::size_of_val(self), dest.len(), dest.len()));
+        }
+
+        dest.copy_from_slice(self.as_bytes());
+        Ok(())
+    }
+}
+
+/// Trait for types which can be created from any bytes at all
+///
+/// This is implemented for every `Safecast` type.
+///
+/// # Safety
+///
+/// Every bit pattern must be a valid `Self`. `check_from_bytes` must panic if
+/// this cannot be guaranteed.
+pub unsafe trait FromBytes: Sized {
+    /// Runtime checks on a `Self` which has been created from bytes
+    fn check_from_bytes(&self);
+
+    /// Copy `bytes` into a new `Self`
+    ///
+    /// This has no alignment requirements on `bytes`. Returns an error if the
+    /// length of `bytes` does not exactly match the size of `Self`.
+    fn read_from_bytes(bytes: &[u8]) -> Result<Self, SafecastError> {
+        // Validate the size
+        if bytes.len() != core::mem::size_of::<Self>() {
+            return Err(SafecastError::size_mismatch(bytes.len(),
+                core::mem::size_of::<Self>(), core::mem::size_of::<Self>()));
+        }
+
+        // Every bit pattern is valid for `Self`
+        let ret = unsafe {
+            core::ptr::read_unaligned(bytes.as_ptr() as *const Self)
+        };
+
+        // Validate runtime checks on output
+        ret.check_from_bytes();
+
+        Ok(ret)
+    }
+
+    /// Create a new `Self` with all of its bytes set to zero
+    fn new_zeroed() -> Self {
+        let ret = unsafe {
+            core::mem::MaybeUninit::<Self>::zeroed().assume_init()
+        };
+
+        // Validate runtime checks on output
+        ret.check_from_bytes();
+
+        ret
+    }
+}
+
+// Valid values of `CheckedSafecast` types have no padding, so they can always
+// be viewed as bytes
+unsafe impl<T: CheckedSafecast> IntoBytes for T {
+    fn check_into_bytes(&self) {
+        // Shared references must not be able to mutate each other
+        cast_assert!(!T::INTERIOR_MUTABLE,
+                "Shared cast of interior mutable type: {}",
+                type_name::<T>());
+
+        // Validate runtime layout checks, `self` is already a valid `T`
+        let bytes = unsafe {
+            core::slice::from_raw_parts(self as *const T as *const u8,
+                                        core::mem::size_of::<T>())
+        };
+        T::is_valid_bytes(bytes);
+    }
+}
+
+unsafe impl<T: Safecast> FromBytes for T {
+    fn check_from_bytes(&self) {
+        run_checks(self);
+    }
+}
+
+// A `bool` is a single byte which must be 0 (`false`) or 1 (`true`)
+unsafe impl CheckedSafecast for bool {
+    fn is_valid_bytes(bytes: &[u8]) -> bool {
+        cast_assert!(bytes.len() == 1,
+                "Size mismatch in is_valid_bytes: {} bytes to bool",
+                bytes.len());
+        bytes[0] <= 1
+    }
+}
+
+// A `char` is a `u32` which must be a Unicode scalar value, that is at most
+// 0x10ffff and not a surrogate
+unsafe impl CheckedSafecast for char {
+    fn is_valid_bytes(bytes: &[u8]) -> bool {
+        cast_assert!(bytes.len() == 4,
+                "Size mismatch in is_valid_bytes: {} bytes to char",
+                bytes.len());
+        let val = u32::from_ne_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]);
+        char::from_u32(val).is_some()
+    }
+}
+
+// The `NonZero*` integers are valid for any bit pattern other than zero. Their
+// `Option`s are guaranteed to use zero as `None`, and are thus `Safecast`.
+macro_rules! checked_nonzero {
+    ($($ty:ident),*) => {
+        $(
+            unsafe impl CheckedSafecast for core::num::$ty {
+                fn is_valid_bytes(bytes: &[u8]) -> bool {
+                    cast_assert!(bytes.len() == core::mem::size_of::<Self>(),
+                            "Size mismatch in is_valid_bytes: {} bytes to {}",
+                            bytes.len(), stringify!($ty));
+                    bytes.iter().any(|&x| x != 0)
+                }
+            }
+        )*
+    }
+}
+
+checked_nonzero!(NonZeroU8, NonZeroU16, NonZeroU32, NonZeroU64, NonZeroU128,
+                 NonZeroUsize, NonZeroI8, NonZeroI16, NonZeroI32, NonZeroI64,
+                 NonZeroI128, NonZeroIsize);
+
+// `NonNull<T>` is valid for any non-null pointer, its `Option` is `Safecast`
+unsafe impl<T> CheckedSafecast for core::ptr::NonNull<T> {
+    fn is_valid_bytes(bytes: &[u8]) -> bool {
+        cast_assert!(bytes.len() == core::mem::size_of::<Self>(),
+                "Size mismatch in is_valid_bytes: {} bytes to {}",
+                bytes.len(), type_name::<Self>());
+        bytes.iter().any(|&x| x != 0)
+    }
+}
@@ -0,0 +1,107 @@
+//! Byte-level diffs between values
+//!
+//! `diff` finds the ranges of bytes which differ between the bytes of two
+//! `Safecast` values, eg. a descriptor before and after a round-trip through
+//! hardware. For types implementing `Layout`, `diff_fields` additionally
+//! names the field owning each range.
+
+use core::ops::Range;
+use crate::Safecast;
+use crate::layout::{Layout, FieldLayout};
+
+/// Iterator over the ranges of bytes which differ between two byte slices,
+/// see `diff`
+pub struct Diff<'a> {
+    /// Bytes of the first value
+    a: &'a [u8],
+
+    /// Bytes of the second value
+    b: &'a [u8],
+
+    /// Offset of the first byte which has not been compared yet
+    pos: usize,
+}
+
+impl Iterator for Diff<'_> {
+    type Item = Range<usize>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        // Bytes past the end of the shorter value always differ
+        let len   = self.a.len().max(self.b.len());
+        let equal = |ii: usize| self.a.get(ii) == self.b.get(ii);
+
+        let start = (self.pos..len).find(|&ii| !equal(ii))?;
+        let end   = (start..len).find(|&ii| equal(ii)).unwrap_or(len);
+        self.pos = end;
+        Some(start..end)
+    }
+}
+
+/// Get the maximal ranges of byte offsets at which the bytes of `a` and `b`
+/// differ, in increasing order
+///
+/// If the values differ in size, the bytes past the end of the smaller value
+/// are reported as differing.
+pub fn diff<'a, A, B>(a: &'a A, b: &'a B) -> Diff<'a>
+        where A: Safecast + ?Sized, B: Safecast + ?Sized {
+    Diff { a: a.cast::<u8>(), b: b.cast::<u8>(), pos: 0 }
+}
+
+/// Iterator over the ranges of bytes which differ between two `T`s along
+/// with the fields they belong to, see `diff_fields`
+pub struct FieldDiff<'a, T: Layout> {
+    /// Bytes of the first value
+    a: &'a [u8],
+
+    /// Bytes of the second value
+    b: &'a [u8],
+
+    /// Index of the field being compared
+    field: usize,
+
+    /// Differing ranges within the field being compared
+    diff: Option<Diff<'a>>,
+
+    /// Marker for the type being compared
+    _marker: core::marker::PhantomData<fn() -> T>,
+}
+
+impl<T: Layout> Iterator for FieldDiff<'_, T> {
+    type Item = (Range<usize>, &'static FieldLayout);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let field = T::FIELDS.get(self.field)?;
+            let (a, b) = (self.a, self.b);
+            let diff = self.diff.get_or_insert_with(|| {
+                let range = field.offset..field.offset + field.size;
+                Diff { a: &a[range.clone()], b: &b[range], pos: 0 }
+            });
+
+            if let Some(range) = diff.next() {
+                let start = field.offset + range.start;
+                return Some((start..field.offset + range.end, field));
+            }
+
+            // Move on to the next field
+            self.field += 1;
+            self.diff = None;
+        }
+    }
+}
+
+/// Get the ranges of byte offsets at which the bytes of `a` and `b` differ,
+/// along with the field owning each range
+///
+/// Ranges never span multiple fields, and are in the order of the fields.
+/// Union variants overlap, thus a byte may be reported for each variant.
+pub fn diff_fields<'a, T>(a: &'a T, b: &'a T) -> FieldDiff<'a, T>
+        where T: Safecast + Layout {
+    FieldDiff {
+        a: a.cast::<u8>(),
+        b: b.cast::<u8>(),
+        field: 0,
+        diff: None,
+        _marker: core::marker::PhantomData,
+    }
+}
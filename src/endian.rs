@@ -0,0 +1,210 @@
+//! Fixed-endianness integer types
+//!
+//! These types store their bytes in a fixed byte order regardless of the
+//! endianness of the host, making structures composed of them portable wire
+//! formats. They have an alignment of 1 and thus never introduce padding.
+//! Values are converted to and from native order with `get()` and `set()`.
+//!
+//! The `Endian` trait provides byte order conversions for integers, these
+//! types, and structures deriving `Safecast` with `#[safecast(byteswap)]`.
+
+use crate::Safecast;
+
+/// Types whose byte order can be converted
+pub trait Endian: Safecast + Sized {
+    /// Reverse the byte order of every multi-byte value in `self`
+    fn swap_bytes(&self) -> Self;
+
+    /// Convert `self` from native byte order to little-endian
+    fn to_le(&self) -> Self {
+        if cfg!(target_endian = "little") {
+            self.cast_copy()
+        } else {
+            Endian::swap_bytes(self)
+        }
+    }
+
+    /// Convert `self` from native byte order to big-endian
+    fn to_be(&self) -> Self {
+        if cfg!(target_endian = "big") {
+            self.cast_copy()
+        } else {
+            Endian::swap_bytes(self)
+        }
+    }
+
+    /// Convert `val` from little-endian to native byte order
+    fn from_le(val: &Self) -> Self {
+        Endian::to_le(val)
+    }
+
+    /// Convert `val` from big-endian to native byte order
+    fn from_be(val: &Self) -> Self {
+        Endian::to_be(val)
+    }
+}
+
+/// Reverse the byte order of every element of `vals` in place
+pub fn swap_bytes_in_place<T: Endian>(vals: &mut [T]) {
+    for val in vals.iter_mut() {
+        *val = Endian::swap_bytes(val);
+    }
+}
+
+macro_rules! endian_int {
+    ($($ty:ty),*) => {
+        $(
+            impl Endian for $ty {
+                fn swap_bytes(&self) -> Self { <$ty>::swap_bytes(*self) }
+            }
+        )*
+    }
+}
+
+endian_int!(u8, u16, u32, u64, u128, usize, i8, i16, i32, i64, i128, isize);
+
+// Arrays are converted element-wise, which allows for arrays of the fixed
+// endianness types below to be left untouched
+impl<T: Endian, const N: usize> Endian for [T; N] {
+    fn swap_bytes(&self) -> Self {
+        core::array::from_fn(|ii| Endian::swap_bytes(&self[ii]))
+    }
+
+    fn to_le(&self) -> Self {
+        core::array::from_fn(|ii| Endian::to_le(&self[ii]))
+    }
+
+    fn to_be(&self) -> Self {
+        core::array::from_fn(|ii| Endian::to_be(&self[ii]))
+    }
+}
+
+impl<T: Endian> Endian for core::num::Wrapping<T> {
+    fn swap_bytes(&self) -> Self {
+        core::num::Wrapping(Endian::swap_bytes(&self.0))
+    }
+
+    fn to_le(&self) -> Self { core::num::Wrapping(Endian::to_le(&self.0)) }
+    fn to_be(&self) -> Self { core::num::Wrapping(Endian::to_be(&self.0)) }
+}
+
+impl<T: Endian> Endian for core::mem::ManuallyDrop<T> {
+    fn swap_bytes(&self) -> Self {
+        core::mem::ManuallyDrop::new(Endian::swap_bytes(&**self))
+    }
+
+    fn to_le(&self) -> Self {
+        core::mem::ManuallyDrop::new(Endian::to_le(&**self))
+    }
+
+    fn to_be(&self) -> Self {
+        core::mem::ManuallyDrop::new(Endian::to_be(&**self))
+    }
+}
+
+impl<T: ?Sized> Endian for core::marker::PhantomData<T> {
+    fn swap_bytes(&self) -> Self { *self }
+}
+
+// Swapping the bytes of a non-zero value leaves it non-zero, and `None` is
+// zero in every byte order
+macro_rules! endian_nonzero {
+    ($($ty:ident),*) => {
+        $(
+            impl Endian for Option<core::num::$ty> {
+                fn swap_bytes(&self) -> Self {
+                    self.and_then(|x| core::num::$ty::new(x.get().swap_bytes()))
+                }
+            }
+        )*
+    }
+}
+
+endian_nonzero!(NonZeroU8, NonZeroU16, NonZeroU32, NonZeroU64, NonZeroU128,
+                NonZeroUsize, NonZeroI8, NonZeroI16, NonZeroI32, NonZeroI64,
+                NonZeroI128, NonZeroIsize);
+
+macro_rules! endian_type {
+    ($name:ident, $native:ty, $to:ident, $from:ident, $desc:expr) => {
+        #[doc = concat!("A `", stringify!($native), "` stored in ", $desc,
+                        " byte order")]
+        #[derive(Clone, Copy, PartialEq, Eq, Hash, Default)]
+        #[cfg_attr(feature = "zerocopy", derive(::zerocopy::FromBytes,
+            ::zerocopy::IntoBytes, ::zerocopy::Immutable,
+            ::zerocopy::KnownLayout, ::zerocopy::Unaligned))]
+        #[repr(transparent)]
+        pub struct $name([u8; core::mem::size_of::<$native>()]);
+
+        impl $name {
+            /// Create a new value from `val` in native byte order
+            pub const fn new(val: $native) -> Self {
+                $name(val.$to())
+            }
+
+            /// Get the value in native byte order
+            pub const fn get(self) -> $native {
+                <$native>::$from(self.0)
+            }
+
+            /// Set the value from `val` in native byte order
+            pub fn set(&mut self, val: $native) {
+                self.0 = val.$to();
+            }
+        }
+
+        impl From<$native> for $name {
+            fn from(val: $native) -> Self {
+                $name::new(val)
+            }
+        }
+
+        impl From<$name> for $native {
+            fn from(val: $name) -> Self {
+                val.get()
+            }
+        }
+
+        impl core::fmt::Debug for $name {
+            fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+                core::fmt::Debug::fmt(&self.get(), f)
+            }
+        }
+
+        // These are just byte arrays, so there is nothing to check
+        unsafe impl Safecast for $name {
+            fn safecast(&self) {}
+            const VALIDATED: bool = true;
+        }
+
+        // The byte order of these is fixed, thus converting to little or big
+        // endian leaves them untouched. Swapping reverses the stored bytes.
+        impl Endian for $name {
+            fn swap_bytes(&self) -> Self {
+                let mut ret = *self;
+                ret.0.reverse();
+                ret
+            }
+
+            fn to_le(&self) -> Self { *self }
+            fn to_be(&self) -> Self { *self }
+        }
+    }
+}
+
+endian_type!(U16Le,  u16,  to_le_bytes, from_le_bytes, "little-endian");
+endian_type!(U32Le,  u32,  to_le_bytes, from_le_bytes, "little-endian");
+endian_type!(U64Le,  u64,  to_le_bytes, from_le_bytes, "little-endian");
+endian_type!(U128Le, u128, to_le_bytes, from_le_bytes, "little-endian");
+endian_type!(I16Le,  i16,  to_le_bytes, from_le_bytes, "little-endian");
+endian_type!(I32Le,  i32,  to_le_bytes, from_le_bytes, "little-endian");
+endian_type!(I64Le,  i64,  to_le_bytes, from_le_bytes, "little-endian");
+endian_type!(I128Le, i128, to_le_bytes, from_le_bytes, "little-endian");
+
+endian_type!(U16Be,  u16,  to_be_bytes, from_be_bytes, "big-endian");
+endian_type!(U32Be,  u32,  to_be_bytes, from_be_bytes, "big-endian");
+endian_type!(U64Be,  u64,  to_be_bytes, from_be_bytes, "big-endian");
+endian_type!(U128Be, u128, to_be_bytes, from_be_bytes, "big-endian");
+endian_type!(I16Be,  i16,  to_be_bytes, from_be_bytes, "big-endian");
+endian_type!(I32Be,  i32,  to_be_bytes, from_be_bytes, "big-endian");
+endian_type!(I64Be,  i64,  to_be_bytes, from_be_bytes, "big-endian");
+endian_type!(I128Be, i128, to_be_bytes, from_be_bytes, "big-endian");
@@ -0,0 +1,57 @@
+//! Hexdump formatting of the bytes of values
+//!
+//! `Dump` prints the bytes of any `Safecast` value, including byte slices, as
+//! rows of 16 bytes in the style of `hexdump -C`: the offset of the row, the
+//! bytes in hex, and the bytes as ASCII with anything unprintable shown as a
+//! `.`. It only relies on `core::fmt`, thus it can be written into any
+//! `fmt::Write` in `no_std` environments.
+
+use core::fmt;
+use crate::Safecast;
+
+/// Number of bytes printed per row
+const ROW: usize = 16;
+
+/// Hexdump of the bytes of a value, printed by both `Display` and `Debug`
+pub struct Dump<'a, T: Safecast + ?Sized>(pub &'a T);
+
+impl<T: Safecast + ?Sized> fmt::Display for Dump<'_, T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for (ii, row) in self.0.cast::<u8>().chunks(ROW).enumerate() {
+            if ii > 0 {
+                writeln!(f)?;
+            }
+            write!(f, "{:08x} ", ii * ROW)?;
+
+            // Bytes in hex, with an extra space between the halves of the
+            // row, padded out such that the ASCII column lines up
+            for col in 0..ROW {
+                if col % (ROW / 2) == 0 {
+                    write!(f, " ")?;
+                }
+                match row.get(col) {
+                    Some(byte) => write!(f, "{:02x} ", byte)?,
+                    None       => write!(f, "   ")?,
+                }
+            }
+
+            write!(f, " |")?;
+            for &byte in row {
+                let chr = if byte.is_ascii_graphic() || byte == b' ' {
+                    byte as char
+                } else {
+                    '.'
+                };
+                write!(f, "{}", chr)?;
+            }
+            write!(f, "|")?;
+        }
+        Ok(())
+    }
+}
+
+impl<T: Safecast + ?Sized> fmt::Debug for Dump<'_, T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Display::fmt(self, f)
+    }
+}
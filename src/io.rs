@@ -0,0 +1,309 @@
+//! Integration with `std::io`
+//!
+//! `ReadPodExt` and `WritePodExt` are implemented for every `Read` and
+//! `Write`, such that values can be read from and written to files and
+//! sockets directly, eg. `file.read_pod::<Header>()?`. Requires the `std`
+//! feature.
+//!
+//! `StructFile` builds on these to expose a file as an indexed array of
+//! records.
+//!
+//! `SocketPodExt` sends and receives values over `TcpStream`s and
+//! `UdpSocket`s, where each value is a single datagram for UDP, and
+//! `UdpPodExt` does the same for unconnected UDP sockets.
+
+use core::convert::TryFrom;
+use std::io::{self, Read, Write, Seek, SeekFrom};
+use std::net::{TcpStream, UdpSocket, SocketAddr, ToSocketAddrs};
+use crate::{CheckedSafecast, IntoBytes, SafecastError};
+
+/// Make sure `bytes` holds a valid `T`, returning an `InvalidData` error
+/// otherwise
+fn validate<T: CheckedSafecast>(bytes: &[u8]) -> io::Result<()> {
+    if !T::is_valid_bytes(bytes) {
+        return Err(io::Error::new(io::ErrorKind::InvalidData,
+                                  SafecastError::InvalidValue));
+    }
+    Ok(())
+}
+
+/// Extension trait for reading values out of any `Read`
+pub trait ReadPodExt: Read {
+    /// Read a `T`
+    ///
+    /// Short reads are retried until all of the bytes of the `T` have been
+    /// read. Returns an `UnexpectedEof` error if the reader ends first, or an
+    /// `InvalidData` error wrapping a `SafecastError::InvalidValue` if the
+    /// bytes do not hold a valid `T`.
+    fn read_pod<T: CheckedSafecast>(&mut self) -> io::Result<T> {
+        // Zero the storage first, such that the reader is only ever given
+        // initialized bytes
+        let mut ret = core::mem::MaybeUninit::<T>::zeroed();
+        let bytes = unsafe {
+            core::slice::from_raw_parts_mut(ret.as_mut_ptr() as *mut u8,
+                                            core::mem::size_of::<T>())
+        };
+        self.read_exact(bytes)?;
+        validate::<T>(bytes)?;
+
+        // Safe to assume initialized here because we filled in _all_ the
+        // bytes, and they were validated to hold a `T`
+        Ok(unsafe { ret.assume_init() })
+    }
+}
+
+impl<R: Read + ?Sized> ReadPodExt for R {}
+
+/// Extension trait for writing values to any `Write`
+pub trait WritePodExt: Write {
+    /// Write the bytes of `val`
+    ///
+    /// Short writes are retried until all of the bytes have been written.
+    fn write_pod<T: IntoBytes>(&mut self, val: &T) -> io::Result<()> {
+        self.write_all(val.as_bytes())
+    }
+}
+
+impl<W: Write + ?Sized> WritePodExt for W {}
+
+/// Create an `InvalidInput` error for an out of bounds record index
+fn out_of_bounds() -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidInput, SafecastError::OutOfBounds)
+}
+
+/// A file exposed as an array of `T` records
+///
+/// Records are read and written in place with seeks, thus the file is never
+/// loaded into memory as a whole. Records read from the file are validated
+/// like any other read of a `T`. By default the backing storage is a
+/// `std::fs::File`, but anything which is `Read`, `Write`, and `Seek` works.
+#[derive(Debug)]
+pub struct StructFile<T, F = std::fs::File> {
+    /// Backing storage of the records
+    inner: F,
+
+    /// Number of records in the file
+    len: u64,
+
+    /// Marker for the type of the records
+    _marker: core::marker::PhantomData<T>,
+}
+
+impl<T: CheckedSafecast> StructFile<T> {
+    /// Open the existing file at `path` for reading and writing
+    pub fn open(path: impl AsRef<std::path::Path>) -> io::Result<Self> {
+        Self::new(std::fs::OpenOptions::new().read(true).write(true)
+            .open(path)?)
+    }
+
+    /// Create a new, empty file at `path`, truncating any existing file
+    pub fn create(path: impl AsRef<std::path::Path>) -> io::Result<Self> {
+        Self::new(std::fs::OpenOptions::new().read(true).write(true)
+            .create(true).truncate(true).open(path)?)
+    }
+}
+
+impl<T: CheckedSafecast, F: Read + Write + Seek> StructFile<T, F> {
+    /// Size of a record in bytes
+    const RECORD_SIZE: u64 = core::mem::size_of::<T>() as u64;
+
+    /// Use `inner` as the backing storage of the records
+    ///
+    /// Returns an `InvalidData` error if the size of `inner` is not a
+    /// multiple of the size of `T`, which indicates that it does not hold
+    /// `T` records or was truncated. The error holds a `SizeMismatch`, or an
+    /// `OutOfBounds` for sizes which do not fit in a `usize`.
+    pub fn new(mut inner: F) -> io::Result<Self> {
+        // Make sure we're not working with zero-size-types
+        cast_assert!(Self::RECORD_SIZE > 0,
+                "ZST not allowed: {}", core::any::type_name::<T>());
+
+        let size = inner.seek(SeekFrom::End(0))?;
+        if size % Self::RECORD_SIZE != 0 {
+            // Files larger than the address space, eg. over 4 GiB on 32-bit
+            // targets, cannot be described by a `SizeMismatch`
+            let err = match usize::try_from(size) {
+                Ok(size) => SafecastError::slice_size_mismatch(size,
+                    core::mem::size_of::<T>()),
+                Err(_) => SafecastError::OutOfBounds,
+            };
+            return Err(io::Error::new(io::ErrorKind::InvalidData, err));
+        }
+
+        Ok(StructFile {
+            inner,
+            len: size / Self::RECORD_SIZE,
+            _marker: core::marker::PhantomData,
+        })
+    }
+
+    /// Get the number of records in the file
+    pub fn len(&self) -> u64 {
+        self.len
+    }
+
+    /// Returns `true` if there are no records in the file
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Seek to the start of the record at `index`, which may be one past the
+    /// last record
+    fn seek_to(&mut self, index: u64) -> io::Result<()> {
+        let offset = index.checked_mul(Self::RECORD_SIZE)
+            .ok_or_else(out_of_bounds)?;
+        self.inner.seek(SeekFrom::Start(offset))?;
+        Ok(())
+    }
+
+    /// Read the record at `index`
+    ///
+    /// Returns an `InvalidInput` error if `index` is out of bounds.
+    pub fn get(&mut self, index: u64) -> io::Result<T> {
+        if index >= self.len {
+            return Err(out_of_bounds());
+        }
+
+        self.seek_to(index)?;
+        self.inner.read_pod()
+    }
+
+    /// Overwrite the record at `index` with `val`
+    ///
+    /// Returns an `InvalidInput` error if `index` is out of bounds.
+    pub fn set(&mut self, index: u64, val: &T) -> io::Result<()> {
+        if index >= self.len {
+            return Err(out_of_bounds());
+        }
+
+        self.seek_to(index)?;
+        self.inner.write_pod(val)
+    }
+
+    /// Add `val` to the end of the file, returning its index
+    pub fn append(&mut self, val: &T) -> io::Result<u64> {
+        self.seek_to(self.len)?;
+        self.inner.write_pod(val)?;
+        self.len += 1;
+        Ok(self.len - 1)
+    }
+
+    /// Unwrap the backing storage of the records
+    pub fn into_inner(self) -> F {
+        self.inner
+    }
+}
+
+/// Receive a `T` as a single datagram with `recv`, which returns the length
+/// of the datagram along with its source
+///
+/// `recv` is given a buffer one byte larger than a `T`, such that datagrams
+/// which would otherwise be silently truncated can be detected.
+fn recv_datagram<T: CheckedSafecast, A>(
+        recv: impl FnOnce(&mut [u8]) -> io::Result<(usize, A)>)
+        -> io::Result<(T, A)> {
+    /// Storage for a `T` followed by a single extra byte
+    #[repr(C)]
+    struct Datagram<T> {
+        val:   T,
+        extra: u8,
+    }
+
+    // Zero the storage first, such that the socket is only ever given
+    // initialized bytes
+    let size = core::mem::size_of::<T>();
+    let mut ret = core::mem::MaybeUninit::<Datagram<T>>::zeroed();
+    let bytes = unsafe {
+        core::slice::from_raw_parts_mut(ret.as_mut_ptr() as *mut u8, size + 1)
+    };
+
+    let (len, src) = recv(bytes)?;
+    if len != size {
+        return Err(io::Error::new(io::ErrorKind::InvalidData,
+            SafecastError::size_mismatch(len, size, size)));
+    }
+    validate::<T>(&bytes[..size])?;
+
+    // Safe to read here because we filled in _all_ the bytes of the `T`, and
+    // they were validated to hold a `T`
+    Ok((unsafe { core::ptr::read(ret.as_ptr() as *const T) }, src))
+}
+
+/// Make sure all `expected` bytes of a datagram were sent
+fn check_sent(sent: usize, expected: usize) -> io::Result<()> {
+    if sent != expected {
+        return Err(io::Error::new(io::ErrorKind::WriteZero,
+            SafecastError::size_mismatch(sent, expected, expected)));
+    }
+    Ok(())
+}
+
+/// Extension trait for sending and receiving values over sockets
+pub trait SocketPodExt {
+    /// Send the bytes of `val`
+    ///
+    /// For TCP, short writes are retried until all of the bytes have been
+    /// written. For UDP the socket must be connected, and `val` is sent as a
+    /// single datagram.
+    fn send_pod<T: IntoBytes>(&self, val: &T) -> io::Result<()>;
+
+    /// Receive a `T`
+    ///
+    /// For TCP, short reads are retried until all of the bytes of the `T`
+    /// have been received, returning an `UnexpectedEof` error if the
+    /// connection is closed first. For UDP the socket must be connected, and
+    /// the `T` is received as a single datagram, returning an `InvalidData`
+    /// error wrapping a `SafecastError::SizeMismatch` if the datagram is not
+    /// exactly the size of a `T`. In both cases bytes which do not hold a
+    /// valid `T` return an `InvalidData` error.
+    fn recv_pod<T: CheckedSafecast>(&self) -> io::Result<T>;
+}
+
+impl SocketPodExt for TcpStream {
+    fn send_pod<T: IntoBytes>(&self, val: &T) -> io::Result<()> {
+        (&mut &*self).write_pod(val)
+    }
+
+    fn recv_pod<T: CheckedSafecast>(&self) -> io::Result<T> {
+        (&mut &*self).read_pod()
+    }
+}
+
+impl SocketPodExt for UdpSocket {
+    fn send_pod<T: IntoBytes>(&self, val: &T) -> io::Result<()> {
+        let bytes = val.as_bytes();
+        check_sent(self.send(bytes)?, bytes.len())
+    }
+
+    fn recv_pod<T: CheckedSafecast>(&self) -> io::Result<T> {
+        recv_datagram(|buf| Ok((self.recv(buf)?, ()))).map(|(ret, _)| ret)
+    }
+}
+
+/// Extension trait for sending and receiving values as datagrams over
+/// unconnected UDP sockets
+pub trait UdpPodExt {
+    /// Send the bytes of `val` as a single datagram to `addr`
+    fn send_pod_to<T: IntoBytes>(&self, val: &T, addr: impl ToSocketAddrs)
+        -> io::Result<()>;
+
+    /// Receive a `T` as a single datagram, along with the address it was
+    /// sent from
+    ///
+    /// Returns the same errors as `SocketPodExt::recv_pod`.
+    fn recv_pod_from<T: CheckedSafecast>(&self)
+        -> io::Result<(T, SocketAddr)>;
+}
+
+impl UdpPodExt for UdpSocket {
+    fn send_pod_to<T: IntoBytes>(&self, val: &T, addr: impl ToSocketAddrs)
+            -> io::Result<()> {
+        let bytes = val.as_bytes();
+        check_sent(self.send_to(bytes, addr)?, bytes.len())
+    }
+
+    fn recv_pod_from<T: CheckedSafecast>(&self)
+            -> io::Result<(T, SocketAddr)> {
+        recv_datagram(|buf| self.recv_from(buf))
+    }
+}
@@ -0,0 +1,234 @@
+//! Reflection of the field layout of derived structures
+//!
+//! `#[derive(Safecast)]` and `#[derive(CheckedSafecast)]` implement `Layout`
+//! for structures and unions, describing where each field lives within the
+//! raw bytes. This allows generic tooling such as hexdumps or byte diffs to
+//! annotate bytes with field names without per-type offset constants.
+
+use core::fmt;
+
+/// Location of a single field within the bytes of a structure
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct FieldLayout {
+    /// Name of the field, or its index for tuple structures. Raw identifiers
+    /// are given without the `r#` prefix.
+    pub name: &'static str,
+
+    /// Offset of the field in bytes from the start of the structure
+    pub offset: usize,
+
+    /// Size of the field in bytes
+    pub size: usize,
+}
+
+/// Trait for types with a known field layout, implemented by the derives
+pub trait Layout {
+    /// Every field of the type in declaration order. Fields which are
+    /// configured out with `#[cfg(...)]` are left out.
+    const FIELDS: &'static [FieldLayout];
+
+    /// Get the field which contains the byte at `offset`, if any. For unions
+    /// this is the first variant covering the byte.
+    fn field_at(offset: usize) -> Option<&'static FieldLayout> {
+        Self::FIELDS.iter().find(|x| {
+            offset >= x.offset && offset - x.offset < x.size
+        })
+    }
+}
+
+/// The bytes of a single field, printed as its offset followed by the bytes
+/// in hex, eg. `@0x4 de ad be ef`
+struct FieldBytes<'a> {
+    /// Offset of the field in bytes from the start of the structure
+    offset: usize,
+
+    /// Bytes of the field
+    bytes: &'a [u8],
+}
+
+impl fmt::Debug for FieldBytes<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "@{:#x}", self.offset)?;
+        for byte in self.bytes {
+            write!(f, " {:02x}", byte)?;
+        }
+        Ok(())
+    }
+}
+
+/// Format `bytes`, the bytes of a `T`, as a structure named `name` with each
+/// field printed as its offset followed by its bytes in hex. This is the
+/// `Debug` implementation generated by `#[safecast(debug)]`, eg.
+/// `Header { magic: @0x0 de ad be ef, len: @0x4 00 10 }`.
+pub fn debug_fields<T: Layout>(name: &str, bytes: &[u8],
+                               f: &mut fmt::Formatter) -> fmt::Result {
+    let mut ret = f.debug_struct(name);
+    for field in T::FIELDS {
+        ret.field(field.name, &FieldBytes {
+            offset: field.offset,
+            bytes:  &bytes[field.offset..][..field.size],
+        });
+    }
+    ret.finish()
+}
+
+/// Human-readable description of the layout of a `T`, see `explain_layout`
+pub struct ExplainLayout<T: Layout> {
+    /// Marker for the described type
+    _marker: core::marker::PhantomData<fn() -> T>,
+}
+
+impl<T: Layout> fmt::Display for ExplainLayout<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let size = core::mem::size_of::<T>();
+        writeln!(f, "{}: {} bytes, align {}", core::any::type_name::<T>(),
+                 size, core::mem::align_of::<T>())?;
+
+        // Fields are in increasing order of offset, other than union
+        // variants which all start at zero, thus any byte past the end of
+        // the fields so far and before the next field is padding
+        let mut end = 0;
+        for field in T::FIELDS {
+            if field.offset > end {
+                writeln!(f, "  {:#06x}..{:#06x} padding ({} bytes)",
+                         end, field.offset, field.offset - end)?;
+            }
+            writeln!(f, "  {:#06x}..{:#06x} {} ({} bytes)", field.offset,
+                     field.offset + field.size, field.name, field.size)?;
+            end = end.max(field.offset + field.size);
+        }
+        if size > end {
+            writeln!(f, "  {:#06x}..{:#06x} padding ({} bytes)",
+                     end, size, size - end)?;
+        }
+        Ok(())
+    }
+}
+
+/// Describe the layout of a `T`: its size, alignment, the offset and size of
+/// every field, and where padding would appear
+///
+/// The result implements `Display`, thus it can be written into any
+/// `fmt::Write` or printed directly. The derives reject padding, thus it only
+/// shows up for hand written `Layout` impls, eg. `explain_layout::<Header>()`
+/// gives:
+///
+/// ```text
+/// Header: 8 bytes, align 4
+///   0x0000..0x0004 magic (4 bytes)
+///   0x0004..0x0006 len (2 bytes)
+///   0x0006..0x0008 padding (2 bytes)
+/// ```
+pub fn explain_layout<T: Layout>() -> ExplainLayout<T> {
+    ExplainLayout { _marker: core::marker::PhantomData }
+}
+
+/// Where the layouts of two types diverge, see `compare_layouts`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LayoutDiff {
+    /// Names of the two types
+    pub names: (&'static str, &'static str),
+
+    /// Sizes of the two types in bytes
+    pub sizes: (usize, usize),
+
+    /// Alignments of the two types in bytes
+    pub aligns: (usize, usize),
+
+    /// Offset of the first byte where the fields of the two types differ in
+    /// offset or size, or where one of them runs out of bytes
+    pub offset: Option<usize>,
+
+    /// The first fields of each type which differ, if any
+    pub fields: (Option<&'static FieldLayout>, Option<&'static FieldLayout>),
+}
+
+impl LayoutDiff {
+    /// Returns `true` if the two types have identical layouts. Field names
+    /// are not compared.
+    pub fn is_identical(&self) -> bool {
+        self.offset.is_none() && self.aligns.0 == self.aligns.1
+    }
+
+    /// Size of the second type minus the size of the first type in bytes
+    pub fn size_delta(&self) -> isize {
+        self.sizes.1 as isize - self.sizes.0 as isize
+    }
+}
+
+/// A field formatted as its name and byte range, or `nothing` if absent
+struct FieldRange(Option<&'static FieldLayout>);
+
+impl fmt::Display for FieldRange {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.0 {
+            Some(field) => write!(f, "`{}` ({:#x}..{:#x})", field.name,
+                                  field.offset, field.offset + field.size),
+            None => write!(f, "nothing"),
+        }
+    }
+}
+
+impl fmt::Display for LayoutDiff {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} ({} bytes, align {}) vs {} ({} bytes, align {})",
+               self.names.0, self.sizes.0, self.aligns.0,
+               self.names.1, self.sizes.1, self.aligns.1)?;
+        if self.is_identical() {
+            return write!(f, ": identical layouts");
+        }
+        if self.size_delta() != 0 {
+            write!(f, ", size differs by {:+} bytes", self.size_delta())?;
+        }
+        if self.aligns.0 != self.aligns.1 {
+            write!(f, ", alignment differs")?;
+        }
+        if let Some(offset) = self.offset {
+            write!(f, ", diverging at {:#x}: {} vs {}", offset,
+                   FieldRange(self.fields.0), FieldRange(self.fields.1))?;
+        }
+        Ok(())
+    }
+}
+
+/// Compare the layouts of `A` and `B`, reporting the first offset where
+/// their fields differ in offset or size, along with the fields involved
+///
+/// This is meant for diagnosing size mismatches between types which are
+/// supposed to be identical, eg. a structure and its counterpart generated
+/// from a C header. The result implements `Display`, eg.
+/// `Header (8 bytes, align 4) vs CHeader (12 bytes, align 4), size differs by
+/// +4 bytes, diverging at 0x4: `len` (0x4..0x6) vs `flags` (0x4..0x8)`.
+pub fn compare_layouts<A: Layout, B: Layout>() -> LayoutDiff {
+    let sizes = (core::mem::size_of::<A>(), core::mem::size_of::<B>());
+    let mut diff = LayoutDiff {
+        names:  (core::any::type_name::<A>(), core::any::type_name::<B>()),
+        sizes,
+        aligns: (core::mem::align_of::<A>(), core::mem::align_of::<B>()),
+        offset: None,
+        fields: (None, None),
+    };
+
+    // Find the first pair of fields which differ, where a missing field
+    // differs from any field
+    let mut ii = 0;
+    loop {
+        let pair = (A::FIELDS.get(ii), B::FIELDS.get(ii));
+        match pair {
+            (None, None) => break,
+            (Some(a), Some(b)) if a.offset == b.offset && a.size == b.size => {}
+            (a, b) => {
+                diff.offset = a.into_iter().chain(b).map(|x| x.offset).min();
+                diff.fields = pair;
+                return diff;
+            }
+        }
+        ii += 1;
+    }
+
+    // The fields all match, but one of the types may have trailing bytes
+    if sizes.0 != sizes.1 {
+        diff.offset = Some(sizes.0.min(sizes.1));
+    }
+    diff
+}
@@ -1,8 +1,164 @@
 #![no_std]
 
-/// Re-export the Safecast derive procedural macro
-pub use bytesafe::Safecast;
+#[cfg(feature = "alloc")]
+extern crate alloc;
 
+#[cfg(feature = "std")]
+extern crate std;
+
+#[cfg(feature = "alloc")]
+use alloc::{boxed::Box, vec::Vec};
+
+use core::any::type_name;
+
+/// Assert a condition the cast routines rely on for safety
+///
+/// With the `lean_asserts` feature in release builds, failures panic through
+/// the shared `cast_failed` rather than formatting a message at every call
+/// site. The check itself is always performed, the casts are unsound without
+/// it.
+macro_rules! cast_assert {
+    ($cond:expr, $($msg:tt)+) => {
+        if cfg!(all(feature = "lean_asserts", not(debug_assertions))) {
+            if !$cond {
+                $crate::cast_failed();
+            }
+        } else {
+            assert!($cond, $($msg)+);
+        }
+    }
+}
+
+/// Report the failure of a `cast_assert!` with the `lean_asserts` feature
+#[cold]
+#[inline(never)]
+fn cast_failed() -> ! {
+    panic!("Safecast check failed")
+}
+
+/// Re-export the Safecast and CheckedSafecast derive procedural macros, and
+/// the round-trip test attribute
+pub use bytesafe::{Safecast, CheckedSafecast, safecast_roundtrip_test};
+
+pub mod align;
+#[cfg(feature = "arrayvec")]
+pub mod arrayvec;
+pub mod asserts;
+#[cfg(feature = "bytes")]
+pub mod buf;
+#[cfg(feature = "bytemuck")]
+pub mod bytemuck;
+pub mod checked;
+pub mod diff;
+pub mod endian;
+#[cfg(feature = "heapless")]
+pub mod heapless;
+pub mod hex;
+#[cfg(feature = "std")]
+pub mod io;
+pub mod layout;
+pub mod mmap;
+pub mod net;
+pub mod reader;
+pub mod records;
+#[cfg(feature = "serde")]
+pub mod serde_bytes;
+pub mod validated;
+pub mod view;
+pub mod writer;
+#[cfg(feature = "zerocopy")]
+pub mod zerocopy;
+
+pub use checked::{CheckedSafecast, IntoBytes, FromBytes};
+pub use validated::Validated;
+
+/// Errors which can be returned from the fallible `Safecast` routines
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SafecastError {
+    /// The size of the source does not match what the destination requires
+    SizeMismatch {
+        /// Size of the source in bytes
+        src: usize,
+
+        /// Size in bytes the destination requires, for slices of elements
+        /// this is the size of the source rounded up to whole elements
+        dest: usize,
+
+        /// Size of a single destination element in bytes
+        elem: usize,
+
+        /// Number of bytes of the source past the last whole element
+        remainder: usize,
+    },
+
+    /// The source is not suitably aligned for the destination type
+    AlignmentMismatch,
+
+    /// The requested range falls outside of the bounds of the buffer
+    OutOfBounds,
+
+    /// The source bytes are not a valid bit pattern for the destination type
+    InvalidValue,
+
+    /// The checksum stored in a record does not match its contents
+    ChecksumMismatch,
+
+    /// The destination type is interior mutable, and cannot be viewed through
+    /// a shared reference to bytes which may be aliased
+    InteriorMutable,
+}
+
+impl core::fmt::Display for SafecastError {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+        match self {
+            SafecastError::SizeMismatch { src, dest, elem, remainder } =>
+                write!(f, "Size mismatch in cast: {} bytes given, {} bytes \
+                       required ({} byte elements, {} bytes remaining)",
+                       src, dest, elem, remainder),
+            SafecastError::AlignmentMismatch =>
+                write!(f, "Cast alignment mismatch"),
+            SafecastError::OutOfBounds =>
+                write!(f, "Out of bounds access"),
+            SafecastError::InvalidValue =>
+                write!(f, "Invalid bit pattern"),
+            SafecastError::ChecksumMismatch =>
+                write!(f, "Checksum mismatch"),
+            SafecastError::InteriorMutable =>
+                write!(f, "Shared cast of interior mutable type"),
+        }
+    }
+}
+
+impl SafecastError {
+    /// Create a `SizeMismatch` for a source of `src` bytes cast to a
+    /// destination of exactly `dest` bytes made of `elem` byte elements
+    pub fn size_mismatch(src: usize, dest: usize, elem: usize) -> Self {
+        SafecastError::SizeMismatch {
+            src, dest, elem,
+            remainder: src.checked_rem(elem).unwrap_or(0),
+        }
+    }
+
+    /// Create a `SizeMismatch` for a source of `src` bytes which does not
+    /// hold a whole number of `elem` byte elements
+    pub fn slice_size_mismatch(src: usize, elem: usize) -> Self {
+        let dest = src.checked_next_multiple_of(elem).unwrap_or(src);
+        Self::size_mismatch(src, dest, elem)
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for SafecastError {}
+
+/// Trait for plain-old-data types which can be safely cast to and from raw
+/// bytes
+///
+/// # Safety
+///
+/// Implementors must be composed entirely of types which have no invalid
+/// underlying binary encodings and must not contain any padding bytes. The
+/// `safecast` routine must panic if these properties cannot be guaranteed.
+/// This is normally implemented via `#[derive(Safecast)]`.
 pub unsafe trait Safecast {
     /// Function that does runtime checks on the underlying structure to
     /// validate things that we could not check at compile time (like checking
@@ -10,25 +166,71 @@ pub unsafe trait Safecast {
     /// `Safecast` structures as POD.
     fn safecast(&self);
 
+    /// Set for types containing interior mutability, such as atomics
+    ///
+    /// The bytes of these types may change underneath a shared reference,
+    /// thus they may only be reinterpreted through mutable references or
+    /// moved by value. Routines which would read or alias them through a
+    /// shared reference panic instead.
+    const INTERIOR_MUTABLE: bool = false;
+
+    /// Set if `safecast` is statically known to never panic, ie. the layout
+    /// of the type is fully checked at compile time
+    ///
+    /// The cast routines skip calling `safecast` for these types, which
+    /// saves walking the fields of large structures on every cast in debug
+    /// builds. This is set by `#[derive(Safecast)]` for structures without
+    /// `validate` fields, and for the primitive types.
+    const VALIDATED: bool = false;
+
+    /// Set if `safecast` checks the values held in the type, such as
+    /// `#[safecast(validate = ...)]` fields, rather than its layout alone
+    ///
+    /// This is set by `#[derive(Safecast)]` for structures with `validate`
+    /// fields, or with fields of such types. Hand written impls are expected
+    /// to only check their layout, and default to `false`.
+    const CHECKS_VALUES: bool = false;
+
+    /// Runtime checks of the layout of `Self`, like `safecast` but without
+    /// checking any of the values held in `self`, such as `validate` fields
+    ///
+    /// This is run on copy destinations before they are overwritten, when the
+    /// values they hold are about to be replaced. The default runs `safecast`,
+    /// which is correct for types whose checks only depend on their layout.
+    /// `#[derive(Safecast)]` and the containers of other types override it.
+    fn check_layout(&self) {
+        if !Self::VALIDATED {
+            Safecast::safecast(self);
+        }
+    }
+
     /// Copy the underlying bytes of `self` into a different type `T` given
     /// they're both representing plain-old-data with no padding and they have
     /// identical sizes.
     fn cast_copy_into<T: Safecast + ?Sized>(&self, dest: &mut T) {
-        // Make sure we're not working with zero-size-types
-        assert!(core::mem::size_of_val(self) > 0, "ZST not allowed");
-        assert!(core::mem::size_of_val(dest) > 0, "ZST not allowed");
+        // Zero-length copies, eg. between empty slices, are allowed as they
+        // copy nothing
 
         // Make sure sizes match between the two things
-        assert!(core::mem::size_of_val(self) == core::mem::size_of_val(dest),
-                "Size mismatch in cast_copy_into");
+        cast_assert!(core::mem::size_of_val(self) == core::mem::size_of_val(dest),
+                "Size mismatch in cast_copy_into: \
+                 {} ({} bytes) to {} ({} bytes)",
+                type_name::<Self>(), core::mem::size_of_val(self),
+                type_name::<T>(), core::mem::size_of_val(dest));
+
+        // Make sure nothing can modify `self` while we read it
+        cast_assert!(!Self::INTERIOR_MUTABLE,
+                "Shared cast of interior mutable type: {}",
+                type_name::<Self>());
 
-        // Validate runtime checks on the structures we're working with
-        Safecast::safecast(self);
-        Safecast::safecast(dest);
+        // Validate runtime checks on the input, and the layout of the output
+        // before overwriting it
+        run_checks(self);
+        run_dest_checks(dest);
 
-        // At this point the structures have been validated to have no padding
-        // bytes, and both are entirely composed of types that also have no
-        // padding and are only PoD
+        // At this point `self` has been validated to have no padding bytes,
+        // and is entirely composed of types that also have no padding and
+        // are only PoD
 
         // Perform the copy
         unsafe {
@@ -37,42 +239,384 @@ pub unsafe trait Safecast {
                 dest as *mut   T    as *mut   u8,
                 core::mem::size_of_val(self));
         }
+
+        // Validate runtime checks on output, now that it holds the bytes it
+        // will be used with
+        run_value_checks(dest);
+    }
+
+    /// Copy the underlying bytes of `self` into `dest` like `cast_copy_into`,
+    /// for sized types
+    ///
+    /// As both sizes are known at compile time the copy is a single typed
+    /// read and write of a `Self`, rather than a `copy_nonoverlapping` of a
+    /// runtime length, which lets small copies such as per-packet header
+    /// reads be done in registers. This will panic unless both `self` and `T`
+    /// are equal in size.
+    #[inline]
+    fn cast_copy_into_sized<T: Safecast>(&self, dest: &mut T)
+            where Self: Sized {
+        // Make sure we're not working with zero-size-types
+        cast_assert!(core::mem::size_of::<Self>() > 0,
+                "ZST not allowed: {}", type_name::<Self>());
+
+        // Make sure sizes match between the two things
+        cast_assert!(core::mem::size_of::<Self>() == core::mem::size_of::<T>(),
+                "Size mismatch in cast_copy_into_sized: \
+                 {} ({} bytes) to {} ({} bytes)",
+                type_name::<Self>(), core::mem::size_of::<Self>(),
+                type_name::<T>(), core::mem::size_of::<T>());
+
+        // Make sure nothing can modify `self` while we read it
+        cast_assert!(!Self::INTERIOR_MUTABLE,
+                "Shared cast of interior mutable type: {}",
+                type_name::<Self>());
+
+        // Validate runtime checks on the input, and the layout of the output
+        // before overwriting it
+        run_checks(self);
+        run_dest_checks(dest);
+
+        // Perform the copy, `dest` may be less aligned than a `Self`
+        unsafe {
+            core::ptr::write_unaligned(dest as *mut T as *mut Self,
+                                       core::ptr::read(self));
+        }
+
+        // Validate runtime checks on output
+        run_value_checks(dest);
+    }
+
+    /// Copy the underlying bytes of the elements of `src` into the elements of
+    /// `dest` in a single copy
+    ///
+    /// This converts whole arrays of records between representations of the
+    /// same size, eg. newtypes, checking both element types once rather than
+    /// once per element as a loop of `cast_copy_into` would. This will panic
+    /// unless `Self` and `T` are equal in size and `src` and `dest` have the
+    /// same number of elements.
+    fn cast_copy_slice<T: Safecast>(src: &[Self], dest: &mut [T])
+            where Self: Sized {
+        // Make sure the elements match up one to one
+        cast_assert!(core::mem::size_of::<Self>() == core::mem::size_of::<T>(),
+                "Element size mismatch in cast_copy_slice: \
+                 {} ({} bytes) to {} ({} bytes)",
+                type_name::<Self>(), core::mem::size_of::<Self>(),
+                type_name::<T>(), core::mem::size_of::<T>());
+        cast_assert!(src.len() == dest.len(),
+                "Length mismatch in cast_copy_slice: {} {}s to {} {}s",
+                src.len(), type_name::<Self>(), dest.len(), type_name::<T>());
+
+        src.cast_copy_into(dest);
+    }
+
+    /// Copy the underlying bytes of `self` into `dest` like `cast_copy_into`,
+    /// using non-temporal stores
+    ///
+    /// Non-temporal stores bypass the cache, which avoids evicting everything
+    /// else from it when copying multi-megabyte buffers, eg. when restoring
+    /// snapshots. For small copies this is slower than `cast_copy_into`, as
+    /// the bytes of `dest` are not in the cache when they are next used.
+    ///
+    /// This will panic unless both `self` and `T` are equal in size.
+    #[cfg(any(target_arch = "x86_64",
+              all(target_arch = "x86", target_feature = "sse2")))]
+    fn cast_copy_into_nontemporal<T: Safecast + ?Sized>(&self, dest: &mut T) {
+        #[cfg(target_arch = "x86")]
+        use core::arch::x86::*;
+        #[cfg(target_arch = "x86_64")]
+        use core::arch::x86_64::*;
+
+        // Zero-length copies, eg. between empty slices, are allowed as they
+        // copy nothing
+
+        // Make sure sizes match between the two things
+        cast_assert!(
+            core::mem::size_of_val(self) == core::mem::size_of_val(dest),
+            "Size mismatch in cast_copy_into_nontemporal: \
+             {} ({} bytes) to {} ({} bytes)",
+            type_name::<Self>(), core::mem::size_of_val(self),
+            type_name::<T>(), core::mem::size_of_val(dest));
+
+        // Make sure nothing can modify `self` while we read it
+        cast_assert!(!Self::INTERIOR_MUTABLE,
+                "Shared cast of interior mutable type: {}",
+                type_name::<Self>());
+
+        // Validate runtime checks on the input, and the layout of the output
+        // before overwriting it
+        run_checks(self);
+        run_dest_checks(dest);
+
+        let len = core::mem::size_of_val(self);
+        let src = self as *const Self as *const u8;
+        let dst = dest as *mut   T    as *mut   u8;
+
+        // Streaming stores require 16-byte alignment, copy the bytes up to
+        // the first aligned address of `dest` normally
+        let head = core::cmp::min(dst.align_offset(16), len);
+        let body = (len - head) & !15;
+        unsafe {
+            core::ptr::copy_nonoverlapping(src, dst, head);
+
+            for off in (head..head + body).step_by(16) {
+                let val = _mm_loadu_si128(src.add(off) as *const __m128i);
+                _mm_stream_si128(dst.add(off) as *mut __m128i, val);
+            }
+
+            core::ptr::copy_nonoverlapping(src.add(head + body),
+                dst.add(head + body), len - head - body);
+
+            // Streaming stores are weakly ordered, make sure they're visible
+            // before anything following the copy
+            _mm_sfence();
+        }
+
+        // Validate runtime checks on output
+        run_value_checks(dest);
     }
 
     /// Create a new value of type `T`, copy the raw byte contents of `self`
     /// into it, and return it.
     fn cast_copy<T: Safecast>(&self) -> T {
-        // Safe to use uninitialized here because we will fill in _all_ the
-        // output bytes
-        let mut ret: T = unsafe { core::mem::uninitialized() };
-        self.cast_copy_into(&mut ret);
+        // Make sure we're not working with zero-size-types
+        cast_assert!(core::mem::size_of_val(self) > 0,
+                "ZST not allowed: {}", type_name::<Self>());
+        cast_assert!(core::mem::size_of::<T>() > 0,
+                "ZST not allowed: {}", type_name::<T>());
+
+        // Make sure sizes match between the two things
+        cast_assert!(core::mem::size_of_val(self) == core::mem::size_of::<T>(),
+                "Size mismatch in cast_copy: {} ({} bytes) to {} ({} bytes)",
+                type_name::<Self>(), core::mem::size_of_val(self),
+                type_name::<T>(), core::mem::size_of::<T>());
+
+        // Make sure nothing can modify `self` while we read it
+        cast_assert!(!Self::INTERIOR_MUTABLE,
+                "Shared cast of interior mutable type: {}",
+                type_name::<Self>());
+
+        // Validate runtime checks on the input (we can't work on the output
+        // until it has been initialized)
+        run_checks(self);
+
+        // Copy the bytes of `self` into uninitialized storage for a `T`
+        let mut ret = core::mem::MaybeUninit::<T>::uninit();
+        unsafe {
+            core::ptr::copy_nonoverlapping(
+                self as *const Self as *const u8,
+                ret.as_mut_ptr()    as *mut   u8,
+                core::mem::size_of::<T>());
+        }
+
+        // Safe to assume initialized here because we filled in _all_ the
+        // output bytes, and `T` has no invalid underlying binary encodings
+        let ret = unsafe { ret.assume_init() };
+
+        // Validate runtime checks on output
+        run_checks(&ret);
+
         ret
     }
 
+    /// Copy the underlying bytes of `self` into the uninitialized `dest`,
+    /// returning a reference to the now initialized `T`
+    ///
+    /// This avoids having to construct a dummy `T` to copy into, which can be
+    /// expensive for large structures. This will panic unless both `self`
+    /// and `T` are equal in size.
+    fn cast_copy_into_uninit<'a, T: Safecast>(&self,
+            dest: &'a mut core::mem::MaybeUninit<T>) -> &'a mut T {
+        // Make sure we're not working with zero-size-types
+        cast_assert!(core::mem::size_of_val(self) > 0,
+                "ZST not allowed: {}", type_name::<Self>());
+        cast_assert!(core::mem::size_of::<T>() > 0,
+                "ZST not allowed: {}", type_name::<T>());
+
+        // Make sure sizes match between the two things
+        cast_assert!(core::mem::size_of_val(self) == core::mem::size_of::<T>(),
+                "Size mismatch in cast_copy_into_uninit: \
+                 {} ({} bytes) to {} ({} bytes)",
+                type_name::<Self>(), core::mem::size_of_val(self),
+                type_name::<T>(), core::mem::size_of::<T>());
+
+        // Make sure nothing can modify `self` while we read it
+        cast_assert!(!Self::INTERIOR_MUTABLE,
+                "Shared cast of interior mutable type: {}",
+                type_name::<Self>());
+
+        // Validate runtime checks on the input (we can't work on the output
+        // until it has been initialized)
+        run_checks(self);
+
+        // Perform the copy
+        unsafe {
+            core::ptr::copy_nonoverlapping(
+                self as *const Self as *const u8,
+                dest.as_mut_ptr()   as *mut   u8,
+                core::mem::size_of::<T>());
+        }
+
+        // Safe to assume initialized here because we filled in _all_ the
+        // output bytes, and `T` has no invalid underlying binary encodings
+        let ret = unsafe { dest.assume_init_mut() };
+
+        // Validate runtime checks on output
+        run_checks(ret);
+
+        ret
+    }
+
+    /// Create a new value of type `T` from the raw bytes of `self`, where
+    /// the fields of `T` are stored little-endian in `self`, converting each
+    /// field to native byte order
+    ///
+    /// This will panic unless both `self` and `T` are equal in size.
+    fn cast_copy_le<T: endian::Endian>(&self) -> T {
+        endian::Endian::from_le(&self.cast_copy::<T>())
+    }
+
+    /// Create a new value of type `T` from the raw bytes of `self`, where
+    /// the fields of `T` are stored big-endian in `self`, converting each
+    /// field to native byte order
+    ///
+    /// This will panic unless both `self` and `T` are equal in size.
+    fn cast_copy_be<T: endian::Endian>(&self) -> T {
+        endian::Endian::from_be(&self.cast_copy::<T>())
+    }
+
+    /// Create a new `Self` with all of its bytes set to zero
+    ///
+    /// Since `Safecast` types have no padding and no invalid underlying binary
+    /// encodings, an all-zero bit pattern is always valid
+    fn zeroed() -> Self where Self: Sized {
+        let ret = unsafe {
+            core::mem::MaybeUninit::<Self>::zeroed().assume_init()
+        };
+
+        // Validate runtime checks on output
+        run_checks(&ret);
+
+        ret
+    }
+
+    /// Overwrite every byte of `self` with `pattern`
+    fn fill_bytes(&mut self, pattern: u8) {
+        self.cast_mut::<u8>().fill(pattern);
+    }
+
+    /// Read a new value of type `T` out of the raw bytes of `self` using an
+    /// unaligned read
+    ///
+    /// Unlike `cast`, this has no alignment requirements on `self`, making it
+    /// suitable for reading values out of packed network or file buffers.
+    ///
+    /// This will panic unless both `self` and `T` are equal in size.
+    fn cast_copy_unaligned<T: Safecast>(&self) -> T {
+        // Make sure we're not working with zero-size-types
+        cast_assert!(core::mem::size_of_val(self) > 0,
+                "ZST not allowed: {}", type_name::<Self>());
+        cast_assert!(core::mem::size_of::<T>() > 0,
+                "ZST not allowed: {}", type_name::<T>());
+
+        // Make sure sizes match between the two things
+        cast_assert!(core::mem::size_of_val(self) == core::mem::size_of::<T>(),
+                "Size mismatch in cast_copy_unaligned: \
+                 {} ({} bytes) to {} ({} bytes)",
+                type_name::<Self>(), core::mem::size_of_val(self),
+                type_name::<T>(), core::mem::size_of::<T>());
+
+        // Make sure nothing can modify `self` while we read it
+        cast_assert!(!Self::INTERIOR_MUTABLE,
+                "Shared cast of interior mutable type: {}",
+                type_name::<Self>());
+
+        // Validate runtime checks on the input
+        run_checks(self);
+
+        // Perform the read, `read_unaligned` has no alignment requirements on
+        // the source pointer
+        let ret = unsafe {
+            core::ptr::read_unaligned(self as *const Self as *const T)
+        };
+
+        // Validate runtime checks on output
+        run_checks(&ret);
+
+        ret
+    }
+
+    /// Read a `Self` out of `bytes` starting at byte `offset` using an
+    /// unaligned read
+    ///
+    /// This will panic if `bytes` does not contain enough bytes at `offset`
+    /// to hold a `Self`.
+    fn read_unaligned_from(bytes: &[u8], offset: usize) -> Self
+            where Self: Sized {
+        // Make sure the entire `Self` is in bounds of `bytes`
+        let end = offset.checked_add(core::mem::size_of::<Self>())
+            .expect("Integer overflow in read_unaligned_from");
+        cast_assert!(end <= bytes.len(),
+                "Out of bounds read_unaligned_from: {} at offset {} of {} \
+                 bytes",
+                type_name::<Self>(), offset, bytes.len());
+
+        bytes[offset..end].cast_copy_unaligned()
+    }
+
+    /// Copy the raw bytes of `self` into a newly allocated `Vec<u8>`
+    #[cfg(feature = "alloc")]
+    fn cast_copy_to_vec(&self) -> Vec<u8> {
+        self.cast::<u8>().to_vec()
+    }
+
+    /// Copy the raw bytes of `self` into a newly allocated `Box<[u8]>`
+    #[cfg(feature = "alloc")]
+    fn to_boxed_bytes(&self) -> Box<[u8]> {
+        self.cast::<u8>().into()
+    }
+
     /// Cast `self` into a slice of type `T`s
     ///
     /// Since casting is only safe if alignment matches, this can panic if
     /// the types do not have the same alignments
     fn cast<T: Safecast>(&self) -> &[T] {
-        // Make sure we're not working with zero-size-types
-        assert!(core::mem::size_of_val(self) > 0, "ZST not allowed");
-        assert!(core::mem::size_of::<T>()    > 0, "ZST not allowed");
+        // Make sure we're not casting to zero-size-types, empty sources
+        // simply cast to empty slices
+        cast_assert!(core::mem::size_of::<T>() > 0,
+                "ZST not allowed: {}", type_name::<T>());
+
+        // Shared references must not be able to mutate each other
+        cast_assert!(!Self::INTERIOR_MUTABLE && !T::INTERIOR_MUTABLE,
+                "Shared cast of interior mutable type: {} to {}",
+                type_name::<Self>(), type_name::<T>());
         
         // Validate runtime checks on the input (we can't work on the output
-        // yet)
-        Safecast::safecast(self);
+        // yet). Byte sources are `VALIDATED`, thus when casting bytes to most
+        // types these compile out, leaving only the alignment and size checks
+        run_checks(self);
         
+        // Empty sources hold no `T`s to misalign, thus they are castable from
+        // any address
+        if core::mem::size_of_val(self) == 0 {
+            return &[];
+        }
+
         // Validate alignment
-        let src_ptr = self as *const Self as *const u8 as usize;
-        assert!(core::mem::align_of::<T>() > 0 &&
-                (src_ptr % core::mem::align_of::<T>()) == 0,
-                "Cast alignment mismatch");
+        let src_ptr = self as *const Self as *const T;
+        cast_assert!(src_ptr.is_aligned(),
+                "Cast alignment mismatch: {} at {:p} to {} (align {})",
+                type_name::<Self>(), src_ptr, type_name::<T>(),
+                core::mem::align_of::<T>());
 
         // Validate that self is evenly divisible by T
         let dest_sz = core::mem::size_of::<T>();
         let src_sz  = core::mem::size_of_val(self);
-        assert!((src_sz % dest_sz) == 0,
-            "cast src cannot be evenly divided by T");
+        cast_assert!(src_sz.is_multiple_of(dest_sz),
+            "cast src cannot be evenly divided by T: {} ({} bytes) into {} \
+             ({} bytes), {} bytes remaining",
+            type_name::<Self>(), src_sz, type_name::<T>(), dest_sz,
+            src_sz % dest_sz);
 
         // Perform the cast!
         let casted = unsafe {
@@ -81,35 +625,87 @@ pub unsafe trait Safecast {
         };
 
         // Validate runtime checks on output
-        Safecast::safecast(casted);
+        run_checks(casted);
 
         casted
     }
 
+    /// Cast `self` into a reference to a single `T`
+    ///
+    /// This will panic unless both `self` and `T` are equal in size, or if
+    /// `self` is not suitably aligned for `T`
+    fn cast_ref<T: Safecast>(&self) -> &T {
+        // Make sure sizes match between the two things
+        cast_assert!(core::mem::size_of_val(self) == core::mem::size_of::<T>(),
+                "Size mismatch in cast_ref: {} ({} bytes) to {} ({} bytes)",
+                type_name::<Self>(), core::mem::size_of_val(self),
+                type_name::<T>(), core::mem::size_of::<T>());
+
+        &self.cast::<T>()[0]
+    }
+
+    /// Split `self` into leading bytes, a slice of aligned `T`s, and trailing
+    /// bytes
+    ///
+    /// The middle slice contains as many `T`s as can fit in `self` starting
+    /// at the first address suitably aligned for `T`. Unlike `cast`, this
+    /// never panics due to alignment or size mismatches.
+    fn cast_aligned<T: Safecast>(&self) -> (&[u8], &[T], &[u8]) {
+        // Make sure we're not working with zero-size-types
+        cast_assert!(core::mem::size_of::<T>() > 0,
+                "ZST not allowed: {}", type_name::<T>());
+
+        let bytes = self.cast::<u8>();
+
+        // Find the number of bytes until the first address aligned for `T`
+        let head = core::cmp::min(
+            bytes.as_ptr().align_offset(core::mem::align_of::<T>()),
+            bytes.len());
+        let (head, rest) = bytes.split_at(head);
+
+        // Take as many whole `T`s as we can, leaving the remainder as bytes
+        let body = rest.len() - (rest.len() % core::mem::size_of::<T>());
+        let (body, tail) = rest.split_at(body);
+
+        (head, body.cast::<T>(), tail)
+    }
+
     /// Cast `self` into a mutable slice of type `T`s
     ///
     /// Since casting is only safe if alignment matches, this can panic if
     /// the types do not have the same alignments
     fn cast_mut<T: Safecast>(&mut self) -> &mut [T] {
-        // Make sure we're not working with zero-size-types
-        assert!(core::mem::size_of_val(self) > 0, "ZST not allowed");
-        assert!(core::mem::size_of::<T>()    > 0, "ZST not allowed");
+        // Make sure we're not casting to zero-size-types, empty sources
+        // simply cast to empty slices
+        cast_assert!(core::mem::size_of::<T>() > 0,
+                "ZST not allowed: {}", type_name::<T>());
         
         // Validate runtime checks on the input (we can't work on the output
-        // yet)
-        Safecast::safecast(self);
+        // yet). Byte sources are `VALIDATED`, thus when casting bytes to most
+        // types these compile out, leaving only the alignment and size checks
+        run_checks(self);
         
+        // Empty sources hold no `T`s to misalign, thus they are castable from
+        // any address
+        if core::mem::size_of_val(self) == 0 {
+            return &mut [];
+        }
+
         // Validate alignment
-        let src_ptr = self as *const Self as *const u8 as usize;
-        assert!(core::mem::align_of::<T>() > 0 &&
-                (src_ptr % core::mem::align_of::<T>()) == 0,
-                "Cast alignment mismatch");
+        let src_ptr = self as *const Self as *const T;
+        cast_assert!(src_ptr.is_aligned(),
+                "Cast alignment mismatch: {} at {:p} to {} (align {})",
+                type_name::<Self>(), src_ptr, type_name::<T>(),
+                core::mem::align_of::<T>());
 
         // Validate that self is evenly divisible by T
         let dest_sz = core::mem::size_of::<T>();
         let src_sz  = core::mem::size_of_val(self);
-        assert!((src_sz % dest_sz) == 0,
-            "cast src cannot be evenly divided by T");
+        cast_assert!(src_sz.is_multiple_of(dest_sz),
+            "cast src cannot be evenly divided by T: {} ({} bytes) into {} \
+             ({} bytes), {} bytes remaining",
+            type_name::<Self>(), src_sz, type_name::<T>(), dest_sz,
+            src_sz % dest_sz);
 
         // Perform the cast!
         let casted = unsafe {
@@ -118,10 +714,608 @@ pub unsafe trait Safecast {
         };
 
         // Validate runtime checks on output
-        Safecast::safecast(casted);
+        run_checks(casted);
 
         casted
     }
+
+    /// Cast `self` into a mutable reference to a single `T`
+    ///
+    /// This will panic unless both `self` and `T` are equal in size, or if
+    /// `self` is not suitably aligned for `T`
+    fn cast_mut_ref<T: Safecast>(&mut self) -> &mut T {
+        // Make sure sizes match between the two things
+        cast_assert!(core::mem::size_of_val(self) == core::mem::size_of::<T>(),
+                "Size mismatch in cast_mut_ref: {} ({} bytes) to {} ({} bytes)",
+                type_name::<Self>(), core::mem::size_of_val(self),
+                type_name::<T>(), core::mem::size_of::<T>());
+
+        &mut self.cast_mut::<T>()[0]
+    }
+
+    /// Interpret `bytes` as a reference to a `Self` without copying
+    ///
+    /// Returns an error if the length of `bytes` does not exactly match the
+    /// size of `Self` or if `bytes` is not suitably aligned for `Self`
+    fn from_bytes(bytes: &[u8]) -> Result<&Self, SafecastError>
+            where Self: Sized {
+        // Make sure we're not working with zero-size-types
+        cast_assert!(core::mem::size_of::<Self>() > 0,
+                "ZST not allowed: {}", type_name::<Self>());
+
+        // Shared references must not be able to mutate each other
+        cast_assert!(!Self::INTERIOR_MUTABLE,
+                "Shared cast of interior mutable type: {}",
+                type_name::<Self>());
+
+        // Validate the size
+        if bytes.len() != core::mem::size_of::<Self>() {
+            return Err(SafecastError::size_mismatch(bytes.len(),
+                core::mem::size_of::<Self>(), core::mem::size_of::<Self>()));
+        }
+
+        // Validate alignment
+        if !bytes.as_ptr().cast::<Self>().is_aligned() {
+            return Err(SafecastError::AlignmentMismatch);
+        }
+
+        // Perform the cast!
+        let casted = unsafe { &*(bytes.as_ptr() as *const Self) };
+
+        // Validate runtime checks on output
+        run_checks(casted);
+
+        Ok(casted)
+    }
+
+    /// Interpret `bytes` as a mutable reference to a `Self` without copying
+    ///
+    /// Returns an error if the length of `bytes` does not exactly match the
+    /// size of `Self` or if `bytes` is not suitably aligned for `Self`
+    fn from_bytes_mut(bytes: &mut [u8]) -> Result<&mut Self, SafecastError>
+            where Self: Sized {
+        // Make sure we're not working with zero-size-types
+        cast_assert!(core::mem::size_of::<Self>() > 0,
+                "ZST not allowed: {}", type_name::<Self>());
+
+        // Validate the size
+        if bytes.len() != core::mem::size_of::<Self>() {
+            return Err(SafecastError::size_mismatch(bytes.len(),
+                core::mem::size_of::<Self>(), core::mem::size_of::<Self>()));
+        }
+
+        // Validate alignment
+        if !bytes.as_ptr().cast::<Self>().is_aligned() {
+            return Err(SafecastError::AlignmentMismatch);
+        }
+
+        // Perform the cast!
+        let casted = unsafe { &mut *(bytes.as_mut_ptr() as *mut Self) };
+
+        // Validate runtime checks on output
+        run_checks(casted);
+
+        Ok(casted)
+    }
+
+    /// Split the bytes of `self` into a reference to a `T` from the start of
+    /// `self` and the remaining bytes following it
+    ///
+    /// Returns an error if `self` is too small to hold a `T` or if the start
+    /// of `self` is not suitably aligned for `T`
+    fn cast_prefix<T: Safecast>(&self) -> Result<(&T, &[u8]), SafecastError> {
+        let bytes = self.cast::<u8>();
+
+        // Make sure there is enough room for a `T`
+        if bytes.len() < core::mem::size_of::<T>() {
+            return Err(SafecastError::size_mismatch(bytes.len(),
+                core::mem::size_of::<T>(), core::mem::size_of::<T>()));
+        }
+
+        let (prefix, rest) = bytes.split_at(core::mem::size_of::<T>());
+        Ok((T::from_bytes(prefix)?, rest))
+    }
+
+    /// Split the bytes of `self` into the leading bytes and a reference to a
+    /// `T` from the end of `self`
+    ///
+    /// Returns an error if `self` is too small to hold a `T` or if the end
+    /// of `self` is not suitably aligned for `T`
+    fn cast_suffix<T: Safecast>(&self) -> Result<(&[u8], &T), SafecastError> {
+        let bytes = self.cast::<u8>();
+
+        // Make sure there is enough room for a `T`
+        if bytes.len() < core::mem::size_of::<T>() {
+            return Err(SafecastError::size_mismatch(bytes.len(),
+                core::mem::size_of::<T>(), core::mem::size_of::<T>()));
+        }
+
+        let (rest, suffix) =
+            bytes.split_at(bytes.len() - core::mem::size_of::<T>());
+        Ok((rest, T::from_bytes(suffix)?))
+    }
+}
+
+/// Read a `T` out of `buf` starting at byte `offset`
+///
+/// There are no alignment requirements on `buf` or `offset`. Returns an error
+/// if `buf` does not contain enough bytes at `offset` to hold a `T`.
+pub fn read_at<T: Safecast>(buf: &[u8], offset: usize)
+        -> Result<T, SafecastError> {
+    // Make sure the entire `T` is in bounds of `buf`
+    let end = offset.checked_add(core::mem::size_of::<T>())
+        .ok_or(SafecastError::OutOfBounds)?;
+    let bytes = buf.get(offset..end).ok_or(SafecastError::OutOfBounds)?;
+
+    Ok(bytes.cast_copy_unaligned())
+}
+
+/// Write the bytes of `value` into `buf` starting at byte `offset`
+///
+/// There are no alignment requirements on `buf` or `offset`. Returns an error
+/// if `buf` does not contain enough bytes at `offset` to hold a `T`.
+pub fn write_at<T: Safecast>(buf: &mut [u8], offset: usize, value: &T)
+        -> Result<(), SafecastError> {
+    // Make sure the entire `T` is in bounds of `buf`
+    let end = offset.checked_add(core::mem::size_of::<T>())
+        .ok_or(SafecastError::OutOfBounds)?;
+    let bytes = buf.get_mut(offset..end).ok_or(SafecastError::OutOfBounds)?;
+
+    value.cast_copy_into(bytes);
+    Ok(())
+}
+
+/// Iterate over copies of each `T` in `bytes`, along with the bytes at the
+/// end of `bytes` which are too short to hold another `T`
+///
+/// There are no alignment requirements on `bytes`, and unlike `cast` the
+/// length of `bytes` does not have to be a multiple of the size of `T`.
+pub fn cast_iter<T: Safecast>(bytes: &[u8]) -> (CastIter<'_, T>, &[u8]) {
+    // Make sure we're not working with zero-size-types
+    cast_assert!(core::mem::size_of::<T>() > 0,
+            "ZST not allowed: {}", type_name::<T>());
+
+    let chunks = bytes.chunks_exact(core::mem::size_of::<T>());
+    let rest = chunks.remainder();
+    (CastIter { chunks, _marker: core::marker::PhantomData }, rest)
+}
+
+/// Iterator over copies of each `T` in some bytes, created with `cast_iter`
+#[derive(Debug, Clone)]
+pub struct CastIter<'a, T> {
+    /// Chunks of bytes which are exactly the size of a `T`
+    chunks: core::slice::ChunksExact<'a, u8>,
+
+    /// Marker for the type being read
+    _marker: core::marker::PhantomData<T>,
+}
+
+impl<T: Safecast> Iterator for CastIter<'_, T> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<T> {
+        self.chunks.next().map(|x| x.cast_copy_unaligned())
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.chunks.size_hint()
+    }
+}
+
+impl<T: Safecast> DoubleEndedIterator for CastIter<'_, T> {
+    fn next_back(&mut self) -> Option<T> {
+        self.chunks.next_back().map(|x| x.cast_copy_unaligned())
+    }
+}
+
+impl<T: Safecast> ExactSizeIterator for CastIter<'_, T> {}
+
+impl<T: Safecast> core::iter::FusedIterator for CastIter<'_, T> {}
+
+/// Convert a `Vec<u8>` into a `Vec<T>` in place, without copying
+///
+/// The allocation must be freed with the same alignment it was allocated
+/// with, thus this is only possible when `T` has an alignment of 1. Further,
+/// both the length and capacity of `bytes` must be evenly divisible by the
+/// size of `T`. On failure the error is returned along with the original
+/// `bytes`.
+#[cfg(feature = "alloc")]
+pub fn cast_vec<T: Safecast>(bytes: Vec<u8>)
+        -> Result<Vec<T>, (SafecastError, Vec<u8>)> {
+    // Make sure we're not working with zero-size-types
+    cast_assert!(core::mem::size_of::<T>() > 0,
+            "ZST not allowed: {}", type_name::<T>());
+
+    // Validate the alignment of the allocation
+    if core::mem::align_of::<T>() != 1 {
+        return Err((SafecastError::AlignmentMismatch, bytes));
+    }
+
+    // Validate that both the length and capacity can be scaled to `T`s
+    let size = core::mem::size_of::<T>();
+    if !bytes.len().is_multiple_of(size) ||
+            !bytes.capacity().is_multiple_of(size) {
+        // Report the length, unless only the capacity is the problem
+        let src = if bytes.len().is_multiple_of(size) {
+            bytes.capacity()
+        } else {
+            bytes.len()
+        };
+        return Err((SafecastError::slice_size_mismatch(src, size), bytes));
+    }
+
+    // Take ownership of the allocation and rebuild it as a `Vec<T>`
+    let mut bytes = core::mem::ManuallyDrop::new(bytes);
+    let casted = unsafe {
+        Vec::from_raw_parts(bytes.as_mut_ptr() as *mut T,
+            bytes.len() / size, bytes.capacity() / size)
+    };
+
+    // Validate runtime checks on output
+    run_checks(&casted[..]);
+
+    Ok(casted)
+}
+
+/// Convert a `Vec<T>` into a `Vec<u8>` in place, without copying
+///
+/// The allocation must be freed with the same alignment it was allocated
+/// with, thus this is only possible when `T` has an alignment of 1. On failure
+/// the error is returned along with the original `vec`.
+#[cfg(feature = "alloc")]
+pub fn vec_to_bytes<T: Safecast>(vec: Vec<T>)
+        -> Result<Vec<u8>, (SafecastError, Vec<T>)> {
+    // Make sure we're not working with zero-size-types
+    cast_assert!(core::mem::size_of::<T>() > 0,
+            "ZST not allowed: {}", type_name::<T>());
+
+    // Validate the alignment of the allocation
+    if core::mem::align_of::<T>() != 1 {
+        return Err((SafecastError::AlignmentMismatch, vec));
+    }
+
+    // Validate runtime checks on the input
+    run_checks(&vec[..]);
+
+    // Take ownership of the allocation and rebuild it as a `Vec<u8>`
+    let size = core::mem::size_of::<T>();
+    let mut vec = core::mem::ManuallyDrop::new(vec);
+    Ok(unsafe {
+        Vec::from_raw_parts(vec.as_mut_ptr() as *mut u8,
+            vec.len() * size, vec.capacity() * size)
+    })
+}
+
+/// Convert a `Box<[u8]>` into a `Box<T>` in place, without copying
+///
+/// The allocation must be freed with the same alignment it was allocated
+/// with, thus this is only possible when `T` has an alignment of 1. Further,
+/// the length of `bytes` must exactly match the size of `T`. On failure the
+/// error is returned along with the original `bytes`.
+#[cfg(feature = "alloc")]
+pub fn cast_box<T: Safecast>(bytes: Box<[u8]>)
+        -> Result<Box<T>, (SafecastError, Box<[u8]>)> {
+    // Make sure we're not working with zero-size-types
+    cast_assert!(core::mem::size_of::<T>() > 0,
+            "ZST not allowed: {}", type_name::<T>());
+
+    // Validate the alignment of the allocation
+    if core::mem::align_of::<T>() != 1 {
+        return Err((SafecastError::AlignmentMismatch, bytes));
+    }
+
+    // Validate the size
+    if bytes.len() != core::mem::size_of::<T>() {
+        let size = core::mem::size_of::<T>();
+        return Err((SafecastError::size_mismatch(bytes.len(), size, size),
+                    bytes));
+    }
+
+    // Take ownership of the allocation and rebuild it as a `Box<T>`
+    let casted = unsafe {
+        Box::from_raw(
+            Box::into_raw(bytes) as *mut u8 as *mut T)
+    };
+
+    // Validate runtime checks on output
+    run_checks(&*casted);
+
+    Ok(casted)
+}
+
+/// Convert a `Box<[u8]>` into a `Box<[T]>` in place, without copying
+///
+/// The allocation must be freed with the same alignment it was allocated
+/// with, thus this is only possible when `T` has an alignment of 1. Further,
+/// the length of `bytes` must be evenly divisible by the size of `T`. On
+/// failure the error is returned along with the original `bytes`.
+#[cfg(feature = "alloc")]
+#[allow(clippy::type_complexity)]
+pub fn cast_box_slice<T: Safecast>(bytes: Box<[u8]>)
+        -> Result<Box<[T]>, (SafecastError, Box<[u8]>)> {
+    // Make sure we're not working with zero-size-types
+    cast_assert!(core::mem::size_of::<T>() > 0,
+            "ZST not allowed: {}", type_name::<T>());
+
+    // Validate the alignment of the allocation
+    if core::mem::align_of::<T>() != 1 {
+        return Err((SafecastError::AlignmentMismatch, bytes));
+    }
+
+    // Validate that the length can be scaled to `T`s
+    let size = core::mem::size_of::<T>();
+    if !bytes.len().is_multiple_of(size) {
+        return Err((SafecastError::slice_size_mismatch(bytes.len(), size),
+                    bytes));
+    }
+
+    // Take ownership of the allocation and rebuild it as a `Box<[T]>`
+    let len = bytes.len() / size;
+    let casted = unsafe {
+        Box::from_raw(core::ptr::slice_from_raw_parts_mut(
+            Box::into_raw(bytes) as *mut u8 as *mut T, len))
+    };
+
+    // Validate runtime checks on output
+    run_checks(&casted[..]);
+
+    Ok(casted)
+}
+
+/// Convert a `Box<T>` into a `Box<[u8]>` in place, without copying
+///
+/// The allocation must be freed with the same alignment it was allocated
+/// with, thus this is only possible when `T` has an alignment of 1. On failure
+/// the error is returned along with the original `val`.
+#[cfg(feature = "alloc")]
+pub fn box_to_bytes<T: Safecast>(val: Box<T>)
+        -> Result<Box<[u8]>, (SafecastError, Box<T>)> {
+    // Make sure we're not working with zero-size-types
+    cast_assert!(core::mem::size_of::<T>() > 0,
+            "ZST not allowed: {}", type_name::<T>());
+
+    // Validate the alignment of the allocation
+    if core::mem::align_of::<T>() != 1 {
+        return Err((SafecastError::AlignmentMismatch, val));
+    }
+
+    // Validate runtime checks on the input
+    run_checks(&*val);
+
+    // Take ownership of the allocation and rebuild it as a `Box<[u8]>`
+    Ok(unsafe {
+        Box::from_raw(core::ptr::slice_from_raw_parts_mut(
+            Box::into_raw(val) as *mut u8,
+            core::mem::size_of::<T>()))
+    })
+}
+
+/// Convert a `Box<[T]>` into a `Box<[u8]>` in place, without copying
+///
+/// The allocation must be freed with the same alignment it was allocated
+/// with, thus this is only possible when `T` has an alignment of 1. On failure
+/// the error is returned along with the original `vals`.
+#[cfg(feature = "alloc")]
+#[allow(clippy::type_complexity)]
+pub fn box_slice_to_bytes<T: Safecast>(vals: Box<[T]>)
+        -> Result<Box<[u8]>, (SafecastError, Box<[T]>)> {
+    // Make sure we're not working with zero-size-types
+    cast_assert!(core::mem::size_of::<T>() > 0,
+            "ZST not allowed: {}", type_name::<T>());
+
+    // Validate the alignment of the allocation
+    if core::mem::align_of::<T>() != 1 {
+        return Err((SafecastError::AlignmentMismatch, vals));
+    }
+
+    // Validate runtime checks on the input
+    run_checks(&vals[..]);
+
+    // Take ownership of the allocation and rebuild it as a `Box<[u8]>`
+    let len = core::mem::size_of_val(&*vals);
+    Ok(unsafe {
+        Box::from_raw(core::ptr::slice_from_raw_parts_mut(
+            Box::into_raw(vals) as *mut u8, len))
+    })
+}
+
+/// Get the bytes of the `T` shared by `val` as a `Rc<[u8]>`
+///
+/// When `T` has an alignment of 1 the returned `Rc` shares the allocation
+/// (and reference count) of `val`. Otherwise the allocation cannot be freed
+/// as bytes, and the bytes are instead copied into a new allocation.
+#[cfg(feature = "alloc")]
+pub fn rc_as_bytes<T: Safecast>(val: &alloc::rc::Rc<T>) -> alloc::rc::Rc<[u8]> {
+    // Make sure we're not working with zero-size-types
+    cast_assert!(core::mem::size_of::<T>() > 0,
+            "ZST not allowed: {}", type_name::<T>());
+
+    // Shared references must not be able to mutate each other
+    cast_assert!(!T::INTERIOR_MUTABLE,
+            "Shared cast of interior mutable type: {}",
+            type_name::<T>());
+
+    // Validate runtime checks on the input
+    run_checks(&**val);
+
+    if core::mem::align_of::<T>() == 1 {
+        // Take a new reference to the allocation and rebuild it as bytes
+        let raw = alloc::rc::Rc::into_raw(val.clone());
+        unsafe {
+            alloc::rc::Rc::from_raw(core::ptr::slice_from_raw_parts(
+                raw as *const u8, core::mem::size_of::<T>()))
+        }
+    } else {
+        alloc::rc::Rc::from(val.cast::<u8>())
+    }
+}
+
+/// Get the bytes shared by `bytes` as a `Rc<T>`
+///
+/// When `T` has an alignment of 1 the returned `Rc` shares the allocation
+/// (and reference count) of `bytes`. Otherwise the bytes are copied into a
+/// new allocation. Returns an error if the length of `bytes` does not exactly
+/// match the size of `T`.
+#[cfg(feature = "alloc")]
+pub fn rc_from_bytes<T: Safecast>(bytes: &alloc::rc::Rc<[u8]>)
+        -> Result<alloc::rc::Rc<T>, SafecastError> {
+    // Make sure we're not working with zero-size-types
+    cast_assert!(core::mem::size_of::<T>() > 0,
+            "ZST not allowed: {}", type_name::<T>());
+
+    // Shared references must not be able to mutate each other
+    cast_assert!(!T::INTERIOR_MUTABLE,
+            "Shared cast of interior mutable type: {}",
+            type_name::<T>());
+
+    // Validate the size
+    if bytes.len() != core::mem::size_of::<T>() {
+        return Err(SafecastError::size_mismatch(bytes.len(),
+            core::mem::size_of::<T>(), core::mem::size_of::<T>()));
+    }
+
+    if core::mem::align_of::<T>() == 1 {
+        // Take a new reference to the allocation and rebuild it as a `T`
+        let raw = alloc::rc::Rc::into_raw(bytes.clone());
+        let casted = unsafe {
+            alloc::rc::Rc::from_raw(raw as *const u8 as *const T)
+        };
+
+        // Validate runtime checks on output
+        run_checks(&*casted);
+
+        Ok(casted)
+    } else {
+        Ok(alloc::rc::Rc::new(bytes.cast_copy_unaligned()))
+    }
+}
+
+/// Get the bytes of the `T` shared by `val` as a `Arc<[u8]>`
+///
+/// When `T` has an alignment of 1 the returned `Arc` shares the allocation
+/// (and reference count) of `val`. Otherwise the allocation cannot be freed
+/// as bytes, and the bytes are instead copied into a new allocation.
+#[cfg(all(feature = "alloc", target_has_atomic = "ptr"))]
+pub fn arc_as_bytes<T: Safecast>(val: &alloc::sync::Arc<T>)
+        -> alloc::sync::Arc<[u8]> {
+    // Make sure we're not working with zero-size-types
+    cast_assert!(core::mem::size_of::<T>() > 0,
+            "ZST not allowed: {}", type_name::<T>());
+
+    // Shared references must not be able to mutate each other
+    cast_assert!(!T::INTERIOR_MUTABLE,
+            "Shared cast of interior mutable type: {}",
+            type_name::<T>());
+
+    // Validate runtime checks on the input
+    run_checks(&**val);
+
+    if core::mem::align_of::<T>() == 1 {
+        // Take a new reference to the allocation and rebuild it as bytes
+        let raw = alloc::sync::Arc::into_raw(val.clone());
+        unsafe {
+            alloc::sync::Arc::from_raw(core::ptr::slice_from_raw_parts(
+                raw as *const u8, core::mem::size_of::<T>()))
+        }
+    } else {
+        alloc::sync::Arc::from(val.cast::<u8>())
+    }
+}
+
+/// Get the bytes shared by `bytes` as a `Arc<T>`
+///
+/// When `T` has an alignment of 1 the returned `Arc` shares the allocation
+/// (and reference count) of `bytes`. Otherwise the bytes are copied into a
+/// new allocation. Returns an error if the length of `bytes` does not exactly
+/// match the size of `T`.
+#[cfg(all(feature = "alloc", target_has_atomic = "ptr"))]
+pub fn arc_from_bytes<T: Safecast>(bytes: &alloc::sync::Arc<[u8]>)
+        -> Result<alloc::sync::Arc<T>, SafecastError> {
+    // Make sure we're not working with zero-size-types
+    cast_assert!(core::mem::size_of::<T>() > 0,
+            "ZST not allowed: {}", type_name::<T>());
+
+    // Shared references must not be able to mutate each other
+    cast_assert!(!T::INTERIOR_MUTABLE,
+            "Shared cast of interior mutable type: {}",
+            type_name::<T>());
+
+    // Validate the size
+    if bytes.len() != core::mem::size_of::<T>() {
+        return Err(SafecastError::size_mismatch(bytes.len(),
+            core::mem::size_of::<T>(), core::mem::size_of::<T>()));
+    }
+
+    if core::mem::align_of::<T>() == 1 {
+        // Take a new reference to the allocation and rebuild it as a `T`
+        let raw = alloc::sync::Arc::into_raw(bytes.clone());
+        let casted = unsafe {
+            alloc::sync::Arc::from_raw(raw as *const u8 as *const T)
+        };
+
+        // Validate runtime checks on output
+        run_checks(&*casted);
+
+        Ok(casted)
+    } else {
+        Ok(alloc::sync::Arc::new(bytes.cast_copy_unaligned()))
+    }
+}
+
+/// Compile-time layout of a sized `Safecast` or `CheckedSafecast` type
+///
+/// Implemented for every sized `CheckedSafecast` type, and thus for every
+/// sized `Safecast` type, such that the size and
+/// alignment can be used in const contexts, eg. `[u8; Header::SIZE]`.
+pub trait SafecastLayout: CheckedSafecast {
+    /// Size of the type in bytes, as given by `core::mem::size_of`
+    const SIZE: usize = core::mem::size_of::<Self>();
+
+    /// Alignment of the type in bytes, as given by `core::mem::align_of`
+    const ALIGN: usize = core::mem::align_of::<Self>();
+}
+
+impl<T: CheckedSafecast> SafecastLayout for T {}
+
+/// Run the runtime checks of `val`, unless its type is `VALIDATED`
+#[inline(always)]
+pub(crate) fn run_checks<T: Safecast + ?Sized>(val: &T) {
+    if !T::VALIDATED {
+        Safecast::safecast(val);
+    }
+}
+
+/// Run the runtime checks of `T` on a zeroed `T`, for containers of `T`s
+///
+/// Only used for `VALIDATED` types, whose checks depend on the layout of `T`
+/// alone, thus this checks the layout without touching any of the `T`s in the
+/// container. The checks of other types may validate values, and must be run
+/// on the real `T`s.
+#[inline(always)]
+pub(crate) fn probe<T: Safecast>() {
+    let probe = core::mem::MaybeUninit::<T>::zeroed();
+    Safecast::safecast(unsafe { &*probe.as_ptr() });
+}
+
+/// Run the layout checks of `dest`, before bytes are copied into it
+///
+/// This makes sure a copy into a `T` which can never be valid panics without
+/// modifying `dest`. The checks compile out for `VALIDATED` types.
+#[inline(always)]
+pub(crate) fn run_dest_checks<T: Safecast + ?Sized>(dest: &T) {
+    dest.check_layout();
+}
+
+/// Run the runtime checks of `dest` after bytes were copied into it, when the
+/// layout of `dest` was already checked by `run_dest_checks`
+///
+/// Only types which check their values have anything left to check, thus the
+/// layout checks are not repeated for other types.
+#[inline(always)]
+pub(crate) fn run_value_checks<T: Safecast + ?Sized>(dest: &T) {
+    if T::CHECKS_VALUES {
+        run_checks(dest);
+    }
 }
 
 // Create impls for the root types we can build upon
@@ -129,18 +1323,124 @@ pub unsafe trait Safecast {
 // there is no padding bytes in the structures. Since these types are just
 // primitives, the safecast() routine just does nothing at all
 
-unsafe impl Safecast for u8    { fn safecast(&self) {} }
-unsafe impl Safecast for u16   { fn safecast(&self) {} }
-unsafe impl Safecast for u32   { fn safecast(&self) {} }
-unsafe impl Safecast for u64   { fn safecast(&self) {} }
-unsafe impl Safecast for u128  { fn safecast(&self) {} }
-unsafe impl Safecast for usize { fn safecast(&self) {} }
-unsafe impl Safecast for i8    { fn safecast(&self) {} }
-unsafe impl Safecast for i16   { fn safecast(&self) {} }
-unsafe impl Safecast for i32   { fn safecast(&self) {} }
-unsafe impl Safecast for i64   { fn safecast(&self) {} }
-unsafe impl Safecast for i128  { fn safecast(&self) {} }
-unsafe impl Safecast for isize { fn safecast(&self) {} }
+macro_rules! primitive {
+    ($($ty:ty),*) => {
+        $(
+            unsafe impl Safecast for $ty {
+                fn safecast(&self) {}
+                const VALIDATED: bool = true;
+            }
+        )*
+    }
+}
+
+primitive!(u8, u16, u32, u64, u128, usize, i8, i16, i32, i64, i128, isize);
+
+// `Option<NonZero*>` is guaranteed to have the same layout as the underlying
+// integer, with `None` represented as zero. Thus every bit pattern is valid.
+// The `NonZero*` types themselves are only `CheckedSafecast` as zero is
+// invalid.
+macro_rules! option_nonzero {
+    ($($ty:ty),*) => {
+        $(
+            unsafe impl Safecast for Option<$ty> {
+                fn safecast(&self) {}
+                const VALIDATED: bool = true;
+            }
+        )*
+    }
+}
+
+option_nonzero!(core::num::NonZeroU8, core::num::NonZeroU16,
+                core::num::NonZeroU32, core::num::NonZeroU64,
+                core::num::NonZeroU128, core::num::NonZeroUsize,
+                core::num::NonZeroI8, core::num::NonZeroI16,
+                core::num::NonZeroI32, core::num::NonZeroI64,
+                core::num::NonZeroI128, core::num::NonZeroIsize);
+
+// `Option<NonNull<T>>` is likewise guaranteed to be a pointer with `None` as
+// null. Pointers created from bytes have no provenance, which is fine for
+// opaque handles, but they must be given one before being dereferenced. Only
+// thin pointers are allowed, as the metadata of others may be invalid.
+unsafe impl<T> Safecast for Option<core::ptr::NonNull<T>> {
+    fn safecast(&self) {}
+    const VALIDATED: bool = true;
+}
+
+// `Wrapping<T>` is `repr(transparent)` over `T`, thus it is safe whenever `T`
+// is. We forward the runtime checks to the inner value.
+unsafe impl<T: Safecast> Safecast for core::num::Wrapping<T> {
+    fn safecast(&self) { Safecast::safecast(&self.0) }
+    fn check_layout(&self) { Safecast::check_layout(&self.0) }
+    const INTERIOR_MUTABLE: bool = T::INTERIOR_MUTABLE;
+    const VALIDATED: bool = T::VALIDATED;
+    const CHECKS_VALUES: bool = T::CHECKS_VALUES;
+}
+
+// `ManuallyDrop<T>` is `repr(transparent)` over `T` as well
+unsafe impl<T: Safecast> Safecast for core::mem::ManuallyDrop<T> {
+    fn safecast(&self) { Safecast::safecast(&**self) }
+    fn check_layout(&self) { Safecast::check_layout(&**self) }
+    const INTERIOR_MUTABLE: bool = T::INTERIOR_MUTABLE;
+    const VALIDATED: bool = T::VALIDATED;
+    const CHECKS_VALUES: bool = T::CHECKS_VALUES;
+}
+
+// `PhantomData<T>` is a zero-sized marker with no bytes at all, so it never
+// affects the layout of the structure containing it
+unsafe impl<T: ?Sized> Safecast for core::marker::PhantomData<T> {
+    fn safecast(&self) {}
+    const VALIDATED: bool = true;
+}
+
+// Atomic integers have the same size as their underlying integer and no
+// invalid bit patterns, but they may be modified through shared references
+macro_rules! atomic {
+    ($($width:literal => $($ty:ident),*);*) => {
+        $($(
+            #[cfg(target_has_atomic = $width)]
+            unsafe impl Safecast for core::sync::atomic::$ty {
+                fn safecast(&self) {}
+                const INTERIOR_MUTABLE: bool = true;
+                const VALIDATED: bool = true;
+            }
+        )*)*
+    }
+}
+
+atomic!("8"   => AtomicU8,    AtomicI8;
+        "16"  => AtomicU16,   AtomicI16;
+        "32"  => AtomicU32,   AtomicI32;
+        "64"  => AtomicU64,   AtomicI64;
+        "ptr" => AtomicUsize, AtomicIsize);
+
+// SIMD vectors are just fixed-size bags of lanes with no padding and no
+// invalid bit patterns. The types themselves are always available, even if
+// the target features needed to operate on them are not enabled.
+#[cfg(feature = "simd")]
+macro_rules! simd {
+    ($arch:ident => $($ty:ident),*) => {
+        $(
+            unsafe impl Safecast for core::arch::$arch::$ty {
+                fn safecast(&self) {}
+                const VALIDATED: bool = true;
+            }
+        )*
+    }
+}
+
+#[cfg(all(feature = "simd", target_arch = "x86"))]
+simd!(x86 => __m128, __m128d, __m128i, __m256, __m256d, __m256i);
+
+#[cfg(all(feature = "simd", target_arch = "x86_64"))]
+simd!(x86_64 => __m128, __m128d, __m128i, __m256, __m256d, __m256i);
+
+#[cfg(all(feature = "simd", target_arch = "aarch64"))]
+simd!(aarch64 => uint8x8_t, uint8x16_t, uint16x4_t, uint16x8_t,
+                 uint32x2_t, uint32x4_t, uint64x1_t, uint64x2_t,
+                 int8x8_t, int8x16_t, int16x4_t, int16x8_t,
+                 int32x2_t, int32x4_t, int64x1_t, int64x2_t,
+                 float32x2_t, float32x4_t, float64x1_t, float64x2_t);
 
 // We implement `Safecast` for slices which also are composed of only
 // `Safecast` members. We cannot put a slice in a structure that derives
@@ -158,268 +1458,51 @@ unsafe impl Safecast for isize { fn safecast(&self) {} }
 // we cannot make an array/vector/slice out of members which are ?Sized so I
 // don't see any way this can be used to violate safety.
 //
-// We invoke the safecast function on one member of the slice to ensure that
-// runtime checks are done on T to validate safety
-unsafe impl<T: Safecast> Safecast for [T] { fn safecast(&self) { Safecast::safecast(&self[0]) }}
-
-// Generic fixed-sized array impls
-// We invoke the safecast function on one member of the array to ensure that
-// runtime checks are done on T to validate safety
-
-unsafe impl<T: Safecast> Safecast for [T;   1] { fn safecast(&self) { Safecast::safecast(&self[0]) }}
-unsafe impl<T: Safecast> Safecast for [T;   2] { fn safecast(&self) { Safecast::safecast(&self[0]) }}
-unsafe impl<T: Safecast> Safecast for [T;   3] { fn safecast(&self) { Safecast::safecast(&self[0]) }}
-unsafe impl<T: Safecast> Safecast for [T;   4] { fn safecast(&self) { Safecast::safecast(&self[0]) }}
-unsafe impl<T: Safecast> Safecast for [T;   5] { fn safecast(&self) { Safecast::safecast(&self[0]) }}
-unsafe impl<T: Safecast> Safecast for [T;   6] { fn safecast(&self) { Safecast::safecast(&self[0]) }}
-unsafe impl<T: Safecast> Safecast for [T;   7] { fn safecast(&self) { Safecast::safecast(&self[0]) }}
-unsafe impl<T: Safecast> Safecast for [T;   8] { fn safecast(&self) { Safecast::safecast(&self[0]) }}
-unsafe impl<T: Safecast> Safecast for [T;   9] { fn safecast(&self) { Safecast::safecast(&self[0]) }}
-unsafe impl<T: Safecast> Safecast for [T;  10] { fn safecast(&self) { Safecast::safecast(&self[0]) }}
-unsafe impl<T: Safecast> Safecast for [T;  11] { fn safecast(&self) { Safecast::safecast(&self[0]) }}
-unsafe impl<T: Safecast> Safecast for [T;  12] { fn safecast(&self) { Safecast::safecast(&self[0]) }}
-unsafe impl<T: Safecast> Safecast for [T;  13] { fn safecast(&self) { Safecast::safecast(&self[0]) }}
-unsafe impl<T: Safecast> Safecast for [T;  14] { fn safecast(&self) { Safecast::safecast(&self[0]) }}
-unsafe impl<T: Safecast> Safecast for [T;  15] { fn safecast(&self) { Safecast::safecast(&self[0]) }}
-unsafe impl<T: Safecast> Safecast for [T;  16] { fn safecast(&self) { Safecast::safecast(&self[0]) }}
-unsafe impl<T: Safecast> Safecast for [T;  17] { fn safecast(&self) { Safecast::safecast(&self[0]) }}
-unsafe impl<T: Safecast> Safecast for [T;  18] { fn safecast(&self) { Safecast::safecast(&self[0]) }}
-unsafe impl<T: Safecast> Safecast for [T;  19] { fn safecast(&self) { Safecast::safecast(&self[0]) }}
-unsafe impl<T: Safecast> Safecast for [T;  20] { fn safecast(&self) { Safecast::safecast(&self[0]) }}
-unsafe impl<T: Safecast> Safecast for [T;  21] { fn safecast(&self) { Safecast::safecast(&self[0]) }}
-unsafe impl<T: Safecast> Safecast for [T;  22] { fn safecast(&self) { Safecast::safecast(&self[0]) }}
-unsafe impl<T: Safecast> Safecast for [T;  23] { fn safecast(&self) { Safecast::safecast(&self[0]) }}
-unsafe impl<T: Safecast> Safecast for [T;  24] { fn safecast(&self) { Safecast::safecast(&self[0]) }}
-unsafe impl<T: Safecast> Safecast for [T;  25] { fn safecast(&self) { Safecast::safecast(&self[0]) }}
-unsafe impl<T: Safecast> Safecast for [T;  26] { fn safecast(&self) { Safecast::safecast(&self[0]) }}
-unsafe impl<T: Safecast> Safecast for [T;  27] { fn safecast(&self) { Safecast::safecast(&self[0]) }}
-unsafe impl<T: Safecast> Safecast for [T;  28] { fn safecast(&self) { Safecast::safecast(&self[0]) }}
-unsafe impl<T: Safecast> Safecast for [T;  29] { fn safecast(&self) { Safecast::safecast(&self[0]) }}
-unsafe impl<T: Safecast> Safecast for [T;  30] { fn safecast(&self) { Safecast::safecast(&self[0]) }}
-unsafe impl<T: Safecast> Safecast for [T;  31] { fn safecast(&self) { Safecast::safecast(&self[0]) }}
-unsafe impl<T: Safecast> Safecast for [T;  32] { fn safecast(&self) { Safecast::safecast(&self[0]) }}
-unsafe impl<T: Safecast> Safecast for [T;  33] { fn safecast(&self) { Safecast::safecast(&self[0]) }}
-unsafe impl<T: Safecast> Safecast for [T;  34] { fn safecast(&self) { Safecast::safecast(&self[0]) }}
-unsafe impl<T: Safecast> Safecast for [T;  35] { fn safecast(&self) { Safecast::safecast(&self[0]) }}
-unsafe impl<T: Safecast> Safecast for [T;  36] { fn safecast(&self) { Safecast::safecast(&self[0]) }}
-unsafe impl<T: Safecast> Safecast for [T;  37] { fn safecast(&self) { Safecast::safecast(&self[0]) }}
-unsafe impl<T: Safecast> Safecast for [T;  38] { fn safecast(&self) { Safecast::safecast(&self[0]) }}
-unsafe impl<T: Safecast> Safecast for [T;  39] { fn safecast(&self) { Safecast::safecast(&self[0]) }}
-unsafe impl<T: Safecast> Safecast for [T;  40] { fn safecast(&self) { Safecast::safecast(&self[0]) }}
-unsafe impl<T: Safecast> Safecast for [T;  41] { fn safecast(&self) { Safecast::safecast(&self[0]) }}
-unsafe impl<T: Safecast> Safecast for [T;  42] { fn safecast(&self) { Safecast::safecast(&self[0]) }}
-unsafe impl<T: Safecast> Safecast for [T;  43] { fn safecast(&self) { Safecast::safecast(&self[0]) }}
-unsafe impl<T: Safecast> Safecast for [T;  44] { fn safecast(&self) { Safecast::safecast(&self[0]) }}
-unsafe impl<T: Safecast> Safecast for [T;  45] { fn safecast(&self) { Safecast::safecast(&self[0]) }}
-unsafe impl<T: Safecast> Safecast for [T;  46] { fn safecast(&self) { Safecast::safecast(&self[0]) }}
-unsafe impl<T: Safecast> Safecast for [T;  47] { fn safecast(&self) { Safecast::safecast(&self[0]) }}
-unsafe impl<T: Safecast> Safecast for [T;  48] { fn safecast(&self) { Safecast::safecast(&self[0]) }}
-unsafe impl<T: Safecast> Safecast for [T;  49] { fn safecast(&self) { Safecast::safecast(&self[0]) }}
-unsafe impl<T: Safecast> Safecast for [T;  50] { fn safecast(&self) { Safecast::safecast(&self[0]) }}
-unsafe impl<T: Safecast> Safecast for [T;  51] { fn safecast(&self) { Safecast::safecast(&self[0]) }}
-unsafe impl<T: Safecast> Safecast for [T;  52] { fn safecast(&self) { Safecast::safecast(&self[0]) }}
-unsafe impl<T: Safecast> Safecast for [T;  53] { fn safecast(&self) { Safecast::safecast(&self[0]) }}
-unsafe impl<T: Safecast> Safecast for [T;  54] { fn safecast(&self) { Safecast::safecast(&self[0]) }}
-unsafe impl<T: Safecast> Safecast for [T;  55] { fn safecast(&self) { Safecast::safecast(&self[0]) }}
-unsafe impl<T: Safecast> Safecast for [T;  56] { fn safecast(&self) { Safecast::safecast(&self[0]) }}
-unsafe impl<T: Safecast> Safecast for [T;  57] { fn safecast(&self) { Safecast::safecast(&self[0]) }}
-unsafe impl<T: Safecast> Safecast for [T;  58] { fn safecast(&self) { Safecast::safecast(&self[0]) }}
-unsafe impl<T: Safecast> Safecast for [T;  59] { fn safecast(&self) { Safecast::safecast(&self[0]) }}
-unsafe impl<T: Safecast> Safecast for [T;  60] { fn safecast(&self) { Safecast::safecast(&self[0]) }}
-unsafe impl<T: Safecast> Safecast for [T;  61] { fn safecast(&self) { Safecast::safecast(&self[0]) }}
-unsafe impl<T: Safecast> Safecast for [T;  62] { fn safecast(&self) { Safecast::safecast(&self[0]) }}
-unsafe impl<T: Safecast> Safecast for [T;  63] { fn safecast(&self) { Safecast::safecast(&self[0]) }}
-unsafe impl<T: Safecast> Safecast for [T;  64] { fn safecast(&self) { Safecast::safecast(&self[0]) }}
-unsafe impl<T: Safecast> Safecast for [T;  65] { fn safecast(&self) { Safecast::safecast(&self[0]) }}
-unsafe impl<T: Safecast> Safecast for [T;  66] { fn safecast(&self) { Safecast::safecast(&self[0]) }}
-unsafe impl<T: Safecast> Safecast for [T;  67] { fn safecast(&self) { Safecast::safecast(&self[0]) }}
-unsafe impl<T: Safecast> Safecast for [T;  68] { fn safecast(&self) { Safecast::safecast(&self[0]) }}
-unsafe impl<T: Safecast> Safecast for [T;  69] { fn safecast(&self) { Safecast::safecast(&self[0]) }}
-unsafe impl<T: Safecast> Safecast for [T;  70] { fn safecast(&self) { Safecast::safecast(&self[0]) }}
-unsafe impl<T: Safecast> Safecast for [T;  71] { fn safecast(&self) { Safecast::safecast(&self[0]) }}
-unsafe impl<T: Safecast> Safecast for [T;  72] { fn safecast(&self) { Safecast::safecast(&self[0]) }}
-unsafe impl<T: Safecast> Safecast for [T;  73] { fn safecast(&self) { Safecast::safecast(&self[0]) }}
-unsafe impl<T: Safecast> Safecast for [T;  74] { fn safecast(&self) { Safecast::safecast(&self[0]) }}
-unsafe impl<T: Safecast> Safecast for [T;  75] { fn safecast(&self) { Safecast::safecast(&self[0]) }}
-unsafe impl<T: Safecast> Safecast for [T;  76] { fn safecast(&self) { Safecast::safecast(&self[0]) }}
-unsafe impl<T: Safecast> Safecast for [T;  77] { fn safecast(&self) { Safecast::safecast(&self[0]) }}
-unsafe impl<T: Safecast> Safecast for [T;  78] { fn safecast(&self) { Safecast::safecast(&self[0]) }}
-unsafe impl<T: Safecast> Safecast for [T;  79] { fn safecast(&self) { Safecast::safecast(&self[0]) }}
-unsafe impl<T: Safecast> Safecast for [T;  80] { fn safecast(&self) { Safecast::safecast(&self[0]) }}
-unsafe impl<T: Safecast> Safecast for [T;  81] { fn safecast(&self) { Safecast::safecast(&self[0]) }}
-unsafe impl<T: Safecast> Safecast for [T;  82] { fn safecast(&self) { Safecast::safecast(&self[0]) }}
-unsafe impl<T: Safecast> Safecast for [T;  83] { fn safecast(&self) { Safecast::safecast(&self[0]) }}
-unsafe impl<T: Safecast> Safecast for [T;  84] { fn safecast(&self) { Safecast::safecast(&self[0]) }}
-unsafe impl<T: Safecast> Safecast for [T;  85] { fn safecast(&self) { Safecast::safecast(&self[0]) }}
-unsafe impl<T: Safecast> Safecast for [T;  86] { fn safecast(&self) { Safecast::safecast(&self[0]) }}
-unsafe impl<T: Safecast> Safecast for [T;  87] { fn safecast(&self) { Safecast::safecast(&self[0]) }}
-unsafe impl<T: Safecast> Safecast for [T;  88] { fn safecast(&self) { Safecast::safecast(&self[0]) }}
-unsafe impl<T: Safecast> Safecast for [T;  89] { fn safecast(&self) { Safecast::safecast(&self[0]) }}
-unsafe impl<T: Safecast> Safecast for [T;  90] { fn safecast(&self) { Safecast::safecast(&self[0]) }}
-unsafe impl<T: Safecast> Safecast for [T;  91] { fn safecast(&self) { Safecast::safecast(&self[0]) }}
-unsafe impl<T: Safecast> Safecast for [T;  92] { fn safecast(&self) { Safecast::safecast(&self[0]) }}
-unsafe impl<T: Safecast> Safecast for [T;  93] { fn safecast(&self) { Safecast::safecast(&self[0]) }}
-unsafe impl<T: Safecast> Safecast for [T;  94] { fn safecast(&self) { Safecast::safecast(&self[0]) }}
-unsafe impl<T: Safecast> Safecast for [T;  95] { fn safecast(&self) { Safecast::safecast(&self[0]) }}
-unsafe impl<T: Safecast> Safecast for [T;  96] { fn safecast(&self) { Safecast::safecast(&self[0]) }}
-unsafe impl<T: Safecast> Safecast for [T;  97] { fn safecast(&self) { Safecast::safecast(&self[0]) }}
-unsafe impl<T: Safecast> Safecast for [T;  98] { fn safecast(&self) { Safecast::safecast(&self[0]) }}
-unsafe impl<T: Safecast> Safecast for [T;  99] { fn safecast(&self) { Safecast::safecast(&self[0]) }}
-unsafe impl<T: Safecast> Safecast for [T; 100] { fn safecast(&self) { Safecast::safecast(&self[0]) }}
-unsafe impl<T: Safecast> Safecast for [T; 101] { fn safecast(&self) { Safecast::safecast(&self[0]) }}
-unsafe impl<T: Safecast> Safecast for [T; 102] { fn safecast(&self) { Safecast::safecast(&self[0]) }}
-unsafe impl<T: Safecast> Safecast for [T; 103] { fn safecast(&self) { Safecast::safecast(&self[0]) }}
-unsafe impl<T: Safecast> Safecast for [T; 104] { fn safecast(&self) { Safecast::safecast(&self[0]) }}
-unsafe impl<T: Safecast> Safecast for [T; 105] { fn safecast(&self) { Safecast::safecast(&self[0]) }}
-unsafe impl<T: Safecast> Safecast for [T; 106] { fn safecast(&self) { Safecast::safecast(&self[0]) }}
-unsafe impl<T: Safecast> Safecast for [T; 107] { fn safecast(&self) { Safecast::safecast(&self[0]) }}
-unsafe impl<T: Safecast> Safecast for [T; 108] { fn safecast(&self) { Safecast::safecast(&self[0]) }}
-unsafe impl<T: Safecast> Safecast for [T; 109] { fn safecast(&self) { Safecast::safecast(&self[0]) }}
-unsafe impl<T: Safecast> Safecast for [T; 110] { fn safecast(&self) { Safecast::safecast(&self[0]) }}
-unsafe impl<T: Safecast> Safecast for [T; 111] { fn safecast(&self) { Safecast::safecast(&self[0]) }}
-unsafe impl<T: Safecast> Safecast for [T; 112] { fn safecast(&self) { Safecast::safecast(&self[0]) }}
-unsafe impl<T: Safecast> Safecast for [T; 113] { fn safecast(&self) { Safecast::safecast(&self[0]) }}
-unsafe impl<T: Safecast> Safecast for [T; 114] { fn safecast(&self) { Safecast::safecast(&self[0]) }}
-unsafe impl<T: Safecast> Safecast for [T; 115] { fn safecast(&self) { Safecast::safecast(&self[0]) }}
-unsafe impl<T: Safecast> Safecast for [T; 116] { fn safecast(&self) { Safecast::safecast(&self[0]) }}
-unsafe impl<T: Safecast> Safecast for [T; 117] { fn safecast(&self) { Safecast::safecast(&self[0]) }}
-unsafe impl<T: Safecast> Safecast for [T; 118] { fn safecast(&self) { Safecast::safecast(&self[0]) }}
-unsafe impl<T: Safecast> Safecast for [T; 119] { fn safecast(&self) { Safecast::safecast(&self[0]) }}
-unsafe impl<T: Safecast> Safecast for [T; 120] { fn safecast(&self) { Safecast::safecast(&self[0]) }}
-unsafe impl<T: Safecast> Safecast for [T; 121] { fn safecast(&self) { Safecast::safecast(&self[0]) }}
-unsafe impl<T: Safecast> Safecast for [T; 122] { fn safecast(&self) { Safecast::safecast(&self[0]) }}
-unsafe impl<T: Safecast> Safecast for [T; 123] { fn safecast(&self) { Safecast::safecast(&self[0]) }}
-unsafe impl<T: Safecast> Safecast for [T; 124] { fn safecast(&self) { Safecast::safecast(&self[0]) }}
-unsafe impl<T: Safecast> Safecast for [T; 125] { fn safecast(&self) { Safecast::safecast(&self[0]) }}
-unsafe impl<T: Safecast> Safecast for [T; 126] { fn safecast(&self) { Safecast::safecast(&self[0]) }}
-unsafe impl<T: Safecast> Safecast for [T; 127] { fn safecast(&self) { Safecast::safecast(&self[0]) }}
-unsafe impl<T: Safecast> Safecast for [T; 128] { fn safecast(&self) { Safecast::safecast(&self[0]) }}
-unsafe impl<T: Safecast> Safecast for [T; 129] { fn safecast(&self) { Safecast::safecast(&self[0]) }}
-unsafe impl<T: Safecast> Safecast for [T; 130] { fn safecast(&self) { Safecast::safecast(&self[0]) }}
-unsafe impl<T: Safecast> Safecast for [T; 131] { fn safecast(&self) { Safecast::safecast(&self[0]) }}
-unsafe impl<T: Safecast> Safecast for [T; 132] { fn safecast(&self) { Safecast::safecast(&self[0]) }}
-unsafe impl<T: Safecast> Safecast for [T; 133] { fn safecast(&self) { Safecast::safecast(&self[0]) }}
-unsafe impl<T: Safecast> Safecast for [T; 134] { fn safecast(&self) { Safecast::safecast(&self[0]) }}
-unsafe impl<T: Safecast> Safecast for [T; 135] { fn safecast(&self) { Safecast::safecast(&self[0]) }}
-unsafe impl<T: Safecast> Safecast for [T; 136] { fn safecast(&self) { Safecast::safecast(&self[0]) }}
-unsafe impl<T: Safecast> Safecast for [T; 137] { fn safecast(&self) { Safecast::safecast(&self[0]) }}
-unsafe impl<T: Safecast> Safecast for [T; 138] { fn safecast(&self) { Safecast::safecast(&self[0]) }}
-unsafe impl<T: Safecast> Safecast for [T; 139] { fn safecast(&self) { Safecast::safecast(&self[0]) }}
-unsafe impl<T: Safecast> Safecast for [T; 140] { fn safecast(&self) { Safecast::safecast(&self[0]) }}
-unsafe impl<T: Safecast> Safecast for [T; 141] { fn safecast(&self) { Safecast::safecast(&self[0]) }}
-unsafe impl<T: Safecast> Safecast for [T; 142] { fn safecast(&self) { Safecast::safecast(&self[0]) }}
-unsafe impl<T: Safecast> Safecast for [T; 143] { fn safecast(&self) { Safecast::safecast(&self[0]) }}
-unsafe impl<T: Safecast> Safecast for [T; 144] { fn safecast(&self) { Safecast::safecast(&self[0]) }}
-unsafe impl<T: Safecast> Safecast for [T; 145] { fn safecast(&self) { Safecast::safecast(&self[0]) }}
-unsafe impl<T: Safecast> Safecast for [T; 146] { fn safecast(&self) { Safecast::safecast(&self[0]) }}
-unsafe impl<T: Safecast> Safecast for [T; 147] { fn safecast(&self) { Safecast::safecast(&self[0]) }}
-unsafe impl<T: Safecast> Safecast for [T; 148] { fn safecast(&self) { Safecast::safecast(&self[0]) }}
-unsafe impl<T: Safecast> Safecast for [T; 149] { fn safecast(&self) { Safecast::safecast(&self[0]) }}
-unsafe impl<T: Safecast> Safecast for [T; 150] { fn safecast(&self) { Safecast::safecast(&self[0]) }}
-unsafe impl<T: Safecast> Safecast for [T; 151] { fn safecast(&self) { Safecast::safecast(&self[0]) }}
-unsafe impl<T: Safecast> Safecast for [T; 152] { fn safecast(&self) { Safecast::safecast(&self[0]) }}
-unsafe impl<T: Safecast> Safecast for [T; 153] { fn safecast(&self) { Safecast::safecast(&self[0]) }}
-unsafe impl<T: Safecast> Safecast for [T; 154] { fn safecast(&self) { Safecast::safecast(&self[0]) }}
-unsafe impl<T: Safecast> Safecast for [T; 155] { fn safecast(&self) { Safecast::safecast(&self[0]) }}
-unsafe impl<T: Safecast> Safecast for [T; 156] { fn safecast(&self) { Safecast::safecast(&self[0]) }}
-unsafe impl<T: Safecast> Safecast for [T; 157] { fn safecast(&self) { Safecast::safecast(&self[0]) }}
-unsafe impl<T: Safecast> Safecast for [T; 158] { fn safecast(&self) { Safecast::safecast(&self[0]) }}
-unsafe impl<T: Safecast> Safecast for [T; 159] { fn safecast(&self) { Safecast::safecast(&self[0]) }}
-unsafe impl<T: Safecast> Safecast for [T; 160] { fn safecast(&self) { Safecast::safecast(&self[0]) }}
-unsafe impl<T: Safecast> Safecast for [T; 161] { fn safecast(&self) { Safecast::safecast(&self[0]) }}
-unsafe impl<T: Safecast> Safecast for [T; 162] { fn safecast(&self) { Safecast::safecast(&self[0]) }}
-unsafe impl<T: Safecast> Safecast for [T; 163] { fn safecast(&self) { Safecast::safecast(&self[0]) }}
-unsafe impl<T: Safecast> Safecast for [T; 164] { fn safecast(&self) { Safecast::safecast(&self[0]) }}
-unsafe impl<T: Safecast> Safecast for [T; 165] { fn safecast(&self) { Safecast::safecast(&self[0]) }}
-unsafe impl<T: Safecast> Safecast for [T; 166] { fn safecast(&self) { Safecast::safecast(&self[0]) }}
-unsafe impl<T: Safecast> Safecast for [T; 167] { fn safecast(&self) { Safecast::safecast(&self[0]) }}
-unsafe impl<T: Safecast> Safecast for [T; 168] { fn safecast(&self) { Safecast::safecast(&self[0]) }}
-unsafe impl<T: Safecast> Safecast for [T; 169] { fn safecast(&self) { Safecast::safecast(&self[0]) }}
-unsafe impl<T: Safecast> Safecast for [T; 170] { fn safecast(&self) { Safecast::safecast(&self[0]) }}
-unsafe impl<T: Safecast> Safecast for [T; 171] { fn safecast(&self) { Safecast::safecast(&self[0]) }}
-unsafe impl<T: Safecast> Safecast for [T; 172] { fn safecast(&self) { Safecast::safecast(&self[0]) }}
-unsafe impl<T: Safecast> Safecast for [T; 173] { fn safecast(&self) { Safecast::safecast(&self[0]) }}
-unsafe impl<T: Safecast> Safecast for [T; 174] { fn safecast(&self) { Safecast::safecast(&self[0]) }}
-unsafe impl<T: Safecast> Safecast for [T; 175] { fn safecast(&self) { Safecast::safecast(&self[0]) }}
-unsafe impl<T: Safecast> Safecast for [T; 176] { fn safecast(&self) { Safecast::safecast(&self[0]) }}
-unsafe impl<T: Safecast> Safecast for [T; 177] { fn safecast(&self) { Safecast::safecast(&self[0]) }}
-unsafe impl<T: Safecast> Safecast for [T; 178] { fn safecast(&self) { Safecast::safecast(&self[0]) }}
-unsafe impl<T: Safecast> Safecast for [T; 179] { fn safecast(&self) { Safecast::safecast(&self[0]) }}
-unsafe impl<T: Safecast> Safecast for [T; 180] { fn safecast(&self) { Safecast::safecast(&self[0]) }}
-unsafe impl<T: Safecast> Safecast for [T; 181] { fn safecast(&self) { Safecast::safecast(&self[0]) }}
-unsafe impl<T: Safecast> Safecast for [T; 182] { fn safecast(&self) { Safecast::safecast(&self[0]) }}
-unsafe impl<T: Safecast> Safecast for [T; 183] { fn safecast(&self) { Safecast::safecast(&self[0]) }}
-unsafe impl<T: Safecast> Safecast for [T; 184] { fn safecast(&self) { Safecast::safecast(&self[0]) }}
-unsafe impl<T: Safecast> Safecast for [T; 185] { fn safecast(&self) { Safecast::safecast(&self[0]) }}
-unsafe impl<T: Safecast> Safecast for [T; 186] { fn safecast(&self) { Safecast::safecast(&self[0]) }}
-unsafe impl<T: Safecast> Safecast for [T; 187] { fn safecast(&self) { Safecast::safecast(&self[0]) }}
-unsafe impl<T: Safecast> Safecast for [T; 188] { fn safecast(&self) { Safecast::safecast(&self[0]) }}
-unsafe impl<T: Safecast> Safecast for [T; 189] { fn safecast(&self) { Safecast::safecast(&self[0]) }}
-unsafe impl<T: Safecast> Safecast for [T; 190] { fn safecast(&self) { Safecast::safecast(&self[0]) }}
-unsafe impl<T: Safecast> Safecast for [T; 191] { fn safecast(&self) { Safecast::safecast(&self[0]) }}
-unsafe impl<T: Safecast> Safecast for [T; 192] { fn safecast(&self) { Safecast::safecast(&self[0]) }}
-unsafe impl<T: Safecast> Safecast for [T; 193] { fn safecast(&self) { Safecast::safecast(&self[0]) }}
-unsafe impl<T: Safecast> Safecast for [T; 194] { fn safecast(&self) { Safecast::safecast(&self[0]) }}
-unsafe impl<T: Safecast> Safecast for [T; 195] { fn safecast(&self) { Safecast::safecast(&self[0]) }}
-unsafe impl<T: Safecast> Safecast for [T; 196] { fn safecast(&self) { Safecast::safecast(&self[0]) }}
-unsafe impl<T: Safecast> Safecast for [T; 197] { fn safecast(&self) { Safecast::safecast(&self[0]) }}
-unsafe impl<T: Safecast> Safecast for [T; 198] { fn safecast(&self) { Safecast::safecast(&self[0]) }}
-unsafe impl<T: Safecast> Safecast for [T; 199] { fn safecast(&self) { Safecast::safecast(&self[0]) }}
-unsafe impl<T: Safecast> Safecast for [T; 200] { fn safecast(&self) { Safecast::safecast(&self[0]) }}
-unsafe impl<T: Safecast> Safecast for [T; 201] { fn safecast(&self) { Safecast::safecast(&self[0]) }}
-unsafe impl<T: Safecast> Safecast for [T; 202] { fn safecast(&self) { Safecast::safecast(&self[0]) }}
-unsafe impl<T: Safecast> Safecast for [T; 203] { fn safecast(&self) { Safecast::safecast(&self[0]) }}
-unsafe impl<T: Safecast> Safecast for [T; 204] { fn safecast(&self) { Safecast::safecast(&self[0]) }}
-unsafe impl<T: Safecast> Safecast for [T; 205] { fn safecast(&self) { Safecast::safecast(&self[0]) }}
-unsafe impl<T: Safecast> Safecast for [T; 206] { fn safecast(&self) { Safecast::safecast(&self[0]) }}
-unsafe impl<T: Safecast> Safecast for [T; 207] { fn safecast(&self) { Safecast::safecast(&self[0]) }}
-unsafe impl<T: Safecast> Safecast for [T; 208] { fn safecast(&self) { Safecast::safecast(&self[0]) }}
-unsafe impl<T: Safecast> Safecast for [T; 209] { fn safecast(&self) { Safecast::safecast(&self[0]) }}
-unsafe impl<T: Safecast> Safecast for [T; 210] { fn safecast(&self) { Safecast::safecast(&self[0]) }}
-unsafe impl<T: Safecast> Safecast for [T; 211] { fn safecast(&self) { Safecast::safecast(&self[0]) }}
-unsafe impl<T: Safecast> Safecast for [T; 212] { fn safecast(&self) { Safecast::safecast(&self[0]) }}
-unsafe impl<T: Safecast> Safecast for [T; 213] { fn safecast(&self) { Safecast::safecast(&self[0]) }}
-unsafe impl<T: Safecast> Safecast for [T; 214] { fn safecast(&self) { Safecast::safecast(&self[0]) }}
-unsafe impl<T: Safecast> Safecast for [T; 215] { fn safecast(&self) { Safecast::safecast(&self[0]) }}
-unsafe impl<T: Safecast> Safecast for [T; 216] { fn safecast(&self) { Safecast::safecast(&self[0]) }}
-unsafe impl<T: Safecast> Safecast for [T; 217] { fn safecast(&self) { Safecast::safecast(&self[0]) }}
-unsafe impl<T: Safecast> Safecast for [T; 218] { fn safecast(&self) { Safecast::safecast(&self[0]) }}
-unsafe impl<T: Safecast> Safecast for [T; 219] { fn safecast(&self) { Safecast::safecast(&self[0]) }}
-unsafe impl<T: Safecast> Safecast for [T; 220] { fn safecast(&self) { Safecast::safecast(&self[0]) }}
-unsafe impl<T: Safecast> Safecast for [T; 221] { fn safecast(&self) { Safecast::safecast(&self[0]) }}
-unsafe impl<T: Safecast> Safecast for [T; 222] { fn safecast(&self) { Safecast::safecast(&self[0]) }}
-unsafe impl<T: Safecast> Safecast for [T; 223] { fn safecast(&self) { Safecast::safecast(&self[0]) }}
-unsafe impl<T: Safecast> Safecast for [T; 224] { fn safecast(&self) { Safecast::safecast(&self[0]) }}
-unsafe impl<T: Safecast> Safecast for [T; 225] { fn safecast(&self) { Safecast::safecast(&self[0]) }}
-unsafe impl<T: Safecast> Safecast for [T; 226] { fn safecast(&self) { Safecast::safecast(&self[0]) }}
-unsafe impl<T: Safecast> Safecast for [T; 227] { fn safecast(&self) { Safecast::safecast(&self[0]) }}
-unsafe impl<T: Safecast> Safecast for [T; 228] { fn safecast(&self) { Safecast::safecast(&self[0]) }}
-unsafe impl<T: Safecast> Safecast for [T; 229] { fn safecast(&self) { Safecast::safecast(&self[0]) }}
-unsafe impl<T: Safecast> Safecast for [T; 230] { fn safecast(&self) { Safecast::safecast(&self[0]) }}
-unsafe impl<T: Safecast> Safecast for [T; 231] { fn safecast(&self) { Safecast::safecast(&self[0]) }}
-unsafe impl<T: Safecast> Safecast for [T; 232] { fn safecast(&self) { Safecast::safecast(&self[0]) }}
-unsafe impl<T: Safecast> Safecast for [T; 233] { fn safecast(&self) { Safecast::safecast(&self[0]) }}
-unsafe impl<T: Safecast> Safecast for [T; 234] { fn safecast(&self) { Safecast::safecast(&self[0]) }}
-unsafe impl<T: Safecast> Safecast for [T; 235] { fn safecast(&self) { Safecast::safecast(&self[0]) }}
-unsafe impl<T: Safecast> Safecast for [T; 236] { fn safecast(&self) { Safecast::safecast(&self[0]) }}
-unsafe impl<T: Safecast> Safecast for [T; 237] { fn safecast(&self) { Safecast::safecast(&self[0]) }}
-unsafe impl<T: Safecast> Safecast for [T; 238] { fn safecast(&self) { Safecast::safecast(&self[0]) }}
-unsafe impl<T: Safecast> Safecast for [T; 239] { fn safecast(&self) { Safecast::safecast(&self[0]) }}
-unsafe impl<T: Safecast> Safecast for [T; 240] { fn safecast(&self) { Safecast::safecast(&self[0]) }}
-unsafe impl<T: Safecast> Safecast for [T; 241] { fn safecast(&self) { Safecast::safecast(&self[0]) }}
-unsafe impl<T: Safecast> Safecast for [T; 242] { fn safecast(&self) { Safecast::safecast(&self[0]) }}
-unsafe impl<T: Safecast> Safecast for [T; 243] { fn safecast(&self) { Safecast::safecast(&self[0]) }}
-unsafe impl<T: Safecast> Safecast for [T; 244] { fn safecast(&self) { Safecast::safecast(&self[0]) }}
-unsafe impl<T: Safecast> Safecast for [T; 245] { fn safecast(&self) { Safecast::safecast(&self[0]) }}
-unsafe impl<T: Safecast> Safecast for [T; 246] { fn safecast(&self) { Safecast::safecast(&self[0]) }}
-unsafe impl<T: Safecast> Safecast for [T; 247] { fn safecast(&self) { Safecast::safecast(&self[0]) }}
-unsafe impl<T: Safecast> Safecast for [T; 248] { fn safecast(&self) { Safecast::safecast(&self[0]) }}
-unsafe impl<T: Safecast> Safecast for [T; 249] { fn safecast(&self) { Safecast::safecast(&self[0]) }}
-unsafe impl<T: Safecast> Safecast for [T; 250] { fn safecast(&self) { Safecast::safecast(&self[0]) }}
-unsafe impl<T: Safecast> Safecast for [T; 251] { fn safecast(&self) { Safecast::safecast(&self[0]) }}
-unsafe impl<T: Safecast> Safecast for [T; 252] { fn safecast(&self) { Safecast::safecast(&self[0]) }}
-unsafe impl<T: Safecast> Safecast for [T; 253] { fn safecast(&self) { Safecast::safecast(&self[0]) }}
-unsafe impl<T: Safecast> Safecast for [T; 254] { fn safecast(&self) { Safecast::safecast(&self[0]) }}
-unsafe impl<T: Safecast> Safecast for [T; 255] { fn safecast(&self) { Safecast::safecast(&self[0]) }}
-unsafe impl<T: Safecast> Safecast for [T; 256] { fn safecast(&self) { Safecast::safecast(&self[0]) }}
+// When T is `VALIDATED` its runtime checks only depend on its layout, thus
+// they are invoked on a zeroed probe rather than reading the slice. Otherwise
+// they may validate values, such as `validate` fields, thus every member of
+// the slice is checked. Empty slices have no members to check.
+unsafe impl<T: Safecast> Safecast for [T] {
+    fn safecast(&self) {
+        if T::VALIDATED {
+            probe::<T>();
+        } else {
+            self.iter().for_each(Safecast::safecast);
+        }
+    }
 
+    // Every member shares the same layout, thus checking one suffices
+    fn check_layout(&self) {
+        if let Some(first) = self.first() {
+            Safecast::check_layout(first);
+        }
+    }
+
+    const INTERIOR_MUTABLE: bool = T::INTERIOR_MUTABLE;
+    const VALIDATED: bool = T::VALIDATED;
+    const CHECKS_VALUES: bool = T::CHECKS_VALUES;
+}
+
+// Generic fixed-sized array impl
+// Like slices, the runtime checks of T are invoked on a zeroed probe when T
+// is `VALIDATED`, and on every member otherwise
+unsafe impl<T: Safecast, const N: usize> Safecast for [T; N] {
+    fn safecast(&self) {
+        if T::VALIDATED {
+            probe::<T>();
+        } else {
+            self.iter().for_each(Safecast::safecast);
+        }
+    }
+
+    // Every member shares the same layout, thus checking one suffices
+    fn check_layout(&self) {
+        if let Some(first) = self.first() {
+            Safecast::check_layout(first);
+        }
+    }
+
+    const INTERIOR_MUTABLE: bool = T::INTERIOR_MUTABLE;
+    const VALIDATED: bool = T::VALIDATED;
+    const CHECKS_VALUES: bool = T::CHECKS_VALUES;
+}
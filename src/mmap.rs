@@ -0,0 +1,130 @@
+//! Casting memory-mapped regions into typed slices
+//!
+//! Memory-mapped files, such as snapshots of arrays of records, are handed
+//! out by the OS as a raw pointer and length. `slice_from_raw` and
+//! `slice_from_raw_mut` turn such a region into a validated `&[T]` or
+//! `&mut [T]`, checking the length, the alignment, and the bit pattern of
+//! every element, thus the only unsafe part left to the caller is vouching
+//! for the mapping itself.
+//!
+//! Mappings which are already exposed as bytes, eg. by a crate implementing
+//! `Deref<Target = [u8]>`, can use the safe `try_cast_slice` and
+//! `try_cast_slice_mut` instead.
+//!
+//! Shared views of interior mutable types, such as atomics in memory shared
+//! with other processes, are only available through `slice_from_raw`. The
+//! bytes given to `try_cast_slice` may be aliased by other shared references
+//! which assume they never change, thus it refuses such types.
+
+use core::any::type_name;
+use crate::{CheckedSafecast, SafecastError};
+
+/// Make sure `bytes` holds a whole number of suitably aligned, valid `T`s
+fn check_slice<T: CheckedSafecast>(bytes: &[u8]) -> Result<(), SafecastError> {
+    // Make sure we're not working with zero-size-types
+    cast_assert!(core::mem::size_of::<T>() > 0,
+            "ZST not allowed: {}", type_name::<T>());
+
+    // Validate that the bytes are evenly divisible by T
+    if !bytes.len().is_multiple_of(core::mem::size_of::<T>()) {
+        return Err(SafecastError::slice_size_mismatch(bytes.len(),
+            core::mem::size_of::<T>()));
+    }
+
+    // Validate alignment
+    if !bytes.as_ptr().cast::<T>().is_aligned() {
+        return Err(SafecastError::AlignmentMismatch);
+    }
+
+    // Validate the bit pattern of every element
+    if !bytes.chunks_exact(core::mem::size_of::<T>()).all(T::is_valid_bytes) {
+        return Err(SafecastError::InvalidValue);
+    }
+
+    Ok(())
+}
+
+/// Interpret `bytes` as a slice of `T`s without copying
+///
+/// Returns an error if the length of `bytes` is not a multiple of the size of
+/// `T`, if `bytes` is not suitably aligned for `T`, or if any element does
+/// not hold a valid `T`. Interior mutable `T`s are refused with an
+/// `InteriorMutable` error.
+pub fn try_cast_slice<T: CheckedSafecast>(bytes: &[u8])
+        -> Result<&[T], SafecastError> {
+    // Shared references must not be able to mutate each other
+    if T::INTERIOR_MUTABLE {
+        return Err(SafecastError::InteriorMutable);
+    }
+
+    check_slice::<T>(bytes)?;
+
+    // Bytes have been validated to hold `T`s
+    Ok(unsafe {
+        core::slice::from_raw_parts(bytes.as_ptr() as *const T,
+            bytes.len() / core::mem::size_of::<T>())
+    })
+}
+
+/// Interpret `bytes` as a mutable slice of `T`s without copying
+///
+/// Only valid values of `T` can be written through the returned slice, thus
+/// `bytes` remains valid. Returns the same errors as `try_cast_slice`.
+pub fn try_cast_slice_mut<T: CheckedSafecast>(bytes: &mut [u8])
+        -> Result<&mut [T], SafecastError> {
+    check_slice::<T>(bytes)?;
+
+    // Bytes have been validated to hold `T`s
+    Ok(unsafe {
+        core::slice::from_raw_parts_mut(bytes.as_mut_ptr() as *mut T,
+            bytes.len() / core::mem::size_of::<T>())
+    })
+}
+
+/// Interpret the `len` mapped bytes at `ptr` as a slice of `T`s
+///
+/// Returns an `OutOfBounds` error if `len` is larger than `isize::MAX`, and
+/// otherwise the same errors as `try_cast_slice`. Unlike `try_cast_slice`,
+/// interior mutable `T`s such as atomics are allowed.
+///
+/// # Safety
+///
+/// `ptr` must be non-null and point to `len` initialized bytes which stay
+/// mapped, and are not mutated other than through interior mutable `T`s, for
+/// the lifetime `'a`.
+pub unsafe fn slice_from_raw<'a, T: CheckedSafecast>(ptr: *const u8,
+                                                     len: usize)
+        -> Result<&'a [T], SafecastError> {
+    assert!(!ptr.is_null(), "Null mapping");
+    if len > isize::MAX as usize {
+        return Err(SafecastError::OutOfBounds);
+    }
+
+    // The caller vouches that the mapping is only mutated through the `T`s,
+    // thus interior mutable `T`s need not be refused
+    check_slice::<T>(unsafe { core::slice::from_raw_parts(ptr, len) })?;
+    Ok(unsafe {
+        core::slice::from_raw_parts(ptr as *const T,
+            len / core::mem::size_of::<T>())
+    })
+}
+
+/// Interpret the `len` mapped bytes at `ptr` as a mutable slice of `T`s
+///
+/// Returns the same errors as `slice_from_raw`.
+///
+/// # Safety
+///
+/// `ptr` must be non-null and point to `len` initialized, writable bytes
+/// which stay mapped for the lifetime `'a`, and which are not accessed
+/// through any other pointer during `'a`.
+pub unsafe fn slice_from_raw_mut<'a, T: CheckedSafecast>(ptr: *mut u8,
+                                                         len: usize)
+        -> Result<&'a mut [T], SafecastError> {
+    assert!(!ptr.is_null(), "Null mapping");
+    if len > isize::MAX as usize {
+        return Err(SafecastError::OutOfBounds);
+    }
+
+    try_cast_slice_mut(unsafe { core::slice::from_raw_parts_mut(ptr, len) })
+}
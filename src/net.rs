@@ -0,0 +1,201 @@
+//! Network byte order types for writing protocol headers
+//!
+//! Everything in this module implements `Safecast` and `Endian`, has an
+//! alignment of 1, and stores its bytes in network (big-endian) order. This
+//! is intended to be glob imported with `use safecast::net::*;` when writing
+//! `#[derive(Safecast)]` packet headers.
+
+use crate::Safecast;
+use crate::endian::Endian;
+
+pub use crate::endian::{U16Be, U32Be, U64Be, U128Be};
+pub use crate::endian::{I16Be, I32Be, I64Be, I128Be};
+
+/// A 24-bit unsigned integer stored in network byte order, as used for
+/// length fields in protocols like TLS
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Default)]
+#[cfg_attr(feature = "zerocopy", derive(::zerocopy::FromBytes,
+    ::zerocopy::IntoBytes, ::zerocopy::Immutable, ::zerocopy::KnownLayout,
+    ::zerocopy::Unaligned))]
+#[repr(transparent)]
+pub struct U24Be([u8; 3]);
+
+impl U24Be {
+    /// Create a new value from the low 24 bits of `val`
+    pub const fn new(val: u32) -> Self {
+        let bytes = val.to_be_bytes();
+        U24Be([bytes[1], bytes[2], bytes[3]])
+    }
+
+    /// Get the value in native byte order
+    pub const fn get(self) -> u32 {
+        u32::from_be_bytes([0, self.0[0], self.0[1], self.0[2]])
+    }
+
+    /// Set the value from the low 24 bits of `val`
+    pub fn set(&mut self, val: u32) {
+        *self = U24Be::new(val);
+    }
+}
+
+impl core::fmt::Debug for U24Be {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+        core::fmt::Debug::fmt(&self.get(), f)
+    }
+}
+
+unsafe impl Safecast for U24Be {
+    fn safecast(&self) {}
+    const VALIDATED: bool = true;
+}
+
+impl Endian for U24Be {
+    fn swap_bytes(&self) -> Self {
+        let mut ret = *self;
+        ret.0.reverse();
+        ret
+    }
+
+    fn to_le(&self) -> Self { *self }
+    fn to_be(&self) -> Self { *self }
+}
+
+/// A 16-bit ones' complement Internet checksum (RFC 1071) stored in network
+/// byte order, as used by IPv4, TCP, UDP, and ICMP
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Default, Debug)]
+#[cfg_attr(feature = "zerocopy", derive(::zerocopy::FromBytes,
+    ::zerocopy::IntoBytes, ::zerocopy::Immutable, ::zerocopy::KnownLayout,
+    ::zerocopy::Unaligned))]
+#[repr(transparent)]
+pub struct Checksum16(U16Be);
+
+impl Checksum16 {
+    /// Create a new checksum from `val` in native byte order
+    pub const fn new(val: u16) -> Self {
+        Checksum16(U16Be::new(val))
+    }
+
+    /// Get the checksum in native byte order
+    pub const fn get(self) -> u16 {
+        self.0.get()
+    }
+
+    /// Set the checksum from `val` in native byte order
+    pub fn set(&mut self, val: u16) {
+        self.0.set(val);
+    }
+
+    /// Compute the checksum of `bytes`
+    ///
+    /// When computing the checksum of a header the checksum field itself
+    /// should be zero.
+    pub fn compute(bytes: &[u8]) -> Self {
+        // Sum up all the 16-bit big-endian words, padding an odd trailing
+        // byte with a zero
+        let mut chunks = bytes.chunks_exact(2);
+        let mut sum = (&mut chunks)
+            .map(|x| u16::from_be_bytes([x[0], x[1]]) as u64)
+            .sum::<u64>();
+        if let [last] = chunks.remainder() {
+            sum += (*last as u64) << 8;
+        }
+
+        // Fold the carries back into the low 16 bits
+        while sum > 0xffff {
+            sum = (sum & 0xffff) + (sum >> 16);
+        }
+
+        Checksum16::new(!(sum as u16))
+    }
+
+    /// Verify the checksum of `bytes`, which includes the checksum field
+    pub fn verify(bytes: &[u8]) -> bool {
+        Self::compute(bytes).get() == 0
+    }
+}
+
+unsafe impl Safecast for Checksum16 {
+    fn safecast(&self) {}
+    const VALIDATED: bool = true;
+}
+
+impl Endian for Checksum16 {
+    fn swap_bytes(&self) -> Self { Checksum16(self.0.swap_bytes()) }
+    fn to_le(&self) -> Self { *self }
+    fn to_be(&self) -> Self { *self }
+}
+
+/// A 48-bit Ethernet MAC address
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Default, Debug)]
+#[cfg_attr(feature = "zerocopy", derive(::zerocopy::FromBytes,
+    ::zerocopy::IntoBytes, ::zerocopy::Immutable, ::zerocopy::KnownLayout,
+    ::zerocopy::Unaligned))]
+#[repr(transparent)]
+pub struct MacAddr(pub [u8; 6]);
+
+impl core::fmt::Display for MacAddr {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+        write!(f, "{:02x}:{:02x}:{:02x}:{:02x}:{:02x}:{:02x}",
+               self.0[0], self.0[1], self.0[2],
+               self.0[3], self.0[4], self.0[5])
+    }
+}
+
+/// An IPv4 address stored in network byte order
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Default, Debug)]
+#[cfg_attr(feature = "zerocopy", derive(::zerocopy::FromBytes,
+    ::zerocopy::IntoBytes, ::zerocopy::Immutable, ::zerocopy::KnownLayout,
+    ::zerocopy::Unaligned))]
+#[repr(transparent)]
+pub struct Ipv4Addr(pub [u8; 4]);
+
+impl From<core::net::Ipv4Addr> for Ipv4Addr {
+    fn from(val: core::net::Ipv4Addr) -> Self {
+        Ipv4Addr(val.octets())
+    }
+}
+
+impl From<Ipv4Addr> for core::net::Ipv4Addr {
+    fn from(val: Ipv4Addr) -> Self {
+        val.0.into()
+    }
+}
+
+/// An IPv6 address stored in network byte order
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Default, Debug)]
+#[cfg_attr(feature = "zerocopy", derive(::zerocopy::FromBytes,
+    ::zerocopy::IntoBytes, ::zerocopy::Immutable, ::zerocopy::KnownLayout,
+    ::zerocopy::Unaligned))]
+#[repr(transparent)]
+pub struct Ipv6Addr(pub [u8; 16]);
+
+impl From<core::net::Ipv6Addr> for Ipv6Addr {
+    fn from(val: core::net::Ipv6Addr) -> Self {
+        Ipv6Addr(val.octets())
+    }
+}
+
+impl From<Ipv6Addr> for core::net::Ipv6Addr {
+    fn from(val: Ipv6Addr) -> Self {
+        val.0.into()
+    }
+}
+
+// Addresses are just byte arrays, so there is nothing to check and nothing
+// to swap
+macro_rules! byte_array_type {
+    ($($name:ident),*) => {
+        $(
+            unsafe impl Safecast for $name {
+                fn safecast(&self) {}
+                const VALIDATED: bool = true;
+            }
+
+            impl Endian for $name {
+                fn swap_bytes(&self) -> Self { *self }
+            }
+        )*
+    }
+}
+
+byte_array_type!(MacAddr, Ipv4Addr, Ipv6Addr);
@@ -0,0 +1,273 @@
+//! Cursor for sequentially parsing typed values out of bytes
+//!
+//! `ByteReader` wraps a `&[u8]` and reads values one after another, as is
+//! done when parsing packets or file formats. Values are copied out, thus
+//! there are no alignment requirements on the bytes. Reads which run past the
+//! end of the bytes, or which find an invalid bit pattern, return an error
+//! and leave the reader where it was.
+//!
+//! For parsers written as chains of functions, `peek` and `take` do the same
+//! on a plain `&[u8]`, with `take` also returning the bytes after the value.
+//!
+//! When values arrive in fragments, `Deframer` buffers the fragments until a
+//! full value has been received.
+//!
+//! Records which carry a checksum of their own bytes implement `Checksummed`,
+//! and can be read with `ByteReader::read_verified` which rejects corrupt
+//! records.
+
+use core::any::type_name;
+use crate::{Safecast, CheckedSafecast, IntoBytes, SafecastError};
+
+/// Read a `T` from the start of `bytes`
+///
+/// There are no alignment requirements on `bytes`. Returns an error if
+/// `bytes` is too short to hold a `T`, or if the bytes do not hold a valid
+/// `T`.
+pub fn peek<T: CheckedSafecast>(bytes: &[u8]) -> Result<T, SafecastError> {
+    take(bytes).map(|(ret, _)| ret)
+}
+
+/// Read a `T` from the start of `bytes`, returning it along with the bytes
+/// following it
+///
+/// There are no alignment requirements on `bytes`. Returns an error if
+/// `bytes` is too short to hold a `T`, or if the bytes do not hold a valid
+/// `T`.
+pub fn take<T: CheckedSafecast>(bytes: &[u8])
+        -> Result<(T, &[u8]), SafecastError> {
+    if bytes.len() < core::mem::size_of::<T>() {
+        return Err(SafecastError::OutOfBounds);
+    }
+
+    let (val, rest) = bytes.split_at(core::mem::size_of::<T>());
+    Ok((T::try_read_from(val)?, rest))
+}
+
+/// Read `N` values of type `T` from the start of `bytes`
+///
+/// This allows arrays of types which are only `CheckedSafecast`, such as
+/// `[bool; N]`, which cannot be read with `peek`. There are no alignment
+/// requirements on `bytes`. Returns an error if `bytes` is too short to hold
+/// the values, or if the bytes do not hold valid `T`s.
+pub fn read_array<T: CheckedSafecast, const N: usize>(bytes: &[u8])
+        -> Result<[T; N], SafecastError> {
+    // Make sure we're not working with zero-size-types
+    cast_assert!(core::mem::size_of::<T>() > 0,
+            "ZST not allowed: {}", type_name::<T>());
+
+    let bytes = bytes.get(..core::mem::size_of::<[T; N]>())
+        .ok_or(SafecastError::OutOfBounds)?;
+
+    // Copy into aligned storage so each element can be validated in place
+    let mut ret = core::mem::MaybeUninit::<[T; N]>::uninit();
+    let copied = unsafe {
+        core::ptr::copy_nonoverlapping(
+            bytes.as_ptr(), ret.as_mut_ptr() as *mut u8, bytes.len());
+        core::slice::from_raw_parts(ret.as_ptr() as *const u8, bytes.len())
+    };
+
+    if !copied.chunks_exact(core::mem::size_of::<T>())
+            .all(T::is_valid_bytes) {
+        return Err(SafecastError::InvalidValue);
+    }
+
+    // Safe to assume initialized here because we filled in _all_ the
+    // bytes, and every element was validated to hold a `T`
+    Ok(unsafe { ret.assume_init() })
+}
+
+/// Trait for records containing a checksum of their own bytes
+///
+/// This is implemented by the derives for structures with a field annotated
+/// with `#[safecast(checksum = path)]`. The function `path` is given the
+/// bytes of the record with the checksum field zeroed, and returns the
+/// checksum as the type of the field.
+pub trait Checksummed: CheckedSafecast {
+    /// Returns `true` if the checksum field matches the rest of the record
+    fn verify_checksum(&self) -> bool;
+
+    /// Compute the checksum of the record and store it in the checksum field
+    fn update_checksum(&mut self);
+}
+
+/// Call `f` with the bytes of `val`, where the `len` bytes at `offset` are
+/// zeroed. Used by the derives to compute checksums of records, where the
+/// checksum field itself is zero.
+#[doc(hidden)]
+pub fn with_zeroed_field<T: IntoBytes, R>(val: &T, offset: usize, len: usize,
+                                          f: impl FnOnce(&[u8]) -> R) -> R {
+    let mut copy = core::mem::MaybeUninit::<T>::uninit();
+    let bytes = val.as_bytes();
+    let copy = unsafe {
+        let ptr = copy.as_mut_ptr() as *mut u8;
+        core::ptr::copy_nonoverlapping(bytes.as_ptr(), ptr, bytes.len());
+        core::slice::from_raw_parts_mut(ptr, bytes.len())
+    };
+
+    copy[offset..][..len].fill(0);
+    f(copy)
+}
+
+/// A cursor reading typed values sequentially out of a byte slice
+#[derive(Debug, Clone)]
+pub struct ByteReader<'a> {
+    /// Bytes being read
+    bytes: &'a [u8],
+
+    /// Offset of the next byte to read
+    offset: usize,
+}
+
+impl<'a> ByteReader<'a> {
+    /// Create a new reader starting at the beginning of `bytes`
+    pub const fn new(bytes: &'a [u8]) -> Self {
+        ByteReader { bytes, offset: 0 }
+    }
+
+    /// Get the offset of the next byte to read
+    pub fn position(&self) -> usize {
+        self.offset
+    }
+
+    /// Get the number of bytes left to read
+    pub fn remaining(&self) -> usize {
+        self.bytes.len() - self.offset
+    }
+
+    /// Returns `true` if there are no bytes left to read
+    pub fn is_empty(&self) -> bool {
+        self.remaining() == 0
+    }
+
+    /// Get the bytes left to read, without consuming them
+    pub fn remaining_bytes(&self) -> &'a [u8] {
+        &self.bytes[self.offset..]
+    }
+
+    /// Read the next `len` bytes
+    ///
+    /// Returns an error if fewer than `len` bytes remain.
+    pub fn read_bytes(&mut self, len: usize)
+            -> Result<&'a [u8], SafecastError> {
+        let ret = self.remaining_bytes().get(..len)
+            .ok_or(SafecastError::OutOfBounds)?;
+        self.offset += len;
+        Ok(ret)
+    }
+
+    /// Skip over the next `len` bytes
+    ///
+    /// Returns an error if fewer than `len` bytes remain.
+    pub fn skip(&mut self, len: usize) -> Result<(), SafecastError> {
+        self.read_bytes(len).map(|_| ())
+    }
+
+    /// Read the next `T`
+    ///
+    /// Returns an error if there are not enough bytes left to hold a `T`, or
+    /// if the bytes do not hold a valid `T`.
+    pub fn read<T: CheckedSafecast>(&mut self) -> Result<T, SafecastError> {
+        let ret = peek(self.remaining_bytes())?;
+        self.offset += core::mem::size_of::<T>();
+        Ok(ret)
+    }
+
+    /// Read the next `T` and verify its checksum
+    ///
+    /// Returns the same errors as `read`, or a `ChecksumMismatch` error if
+    /// the checksum of the `T` does not match, in which case the reader is
+    /// left where it was.
+    pub fn read_verified<T: Checksummed>(&mut self)
+            -> Result<T, SafecastError> {
+        let ret: T = peek(self.remaining_bytes())?;
+        if !ret.verify_checksum() {
+            return Err(SafecastError::ChecksumMismatch);
+        }
+        self.offset += core::mem::size_of::<T>();
+        Ok(ret)
+    }
+
+    /// Read the next `N` values of type `T`
+    ///
+    /// Returns an error if there are not enough bytes left to hold the
+    /// values, or if the bytes do not hold valid `T`s.
+    pub fn read_array<T: CheckedSafecast, const N: usize>(&mut self)
+            -> Result<[T; N], SafecastError> {
+        let ret = read_array(self.remaining_bytes())?;
+        self.offset += core::mem::size_of::<[T; N]>();
+        Ok(ret)
+    }
+}
+
+/// Accumulates bytes received in fragments, eg. from a nonblocking socket or
+/// a UART, until a full `T` is available
+///
+/// The bytes are buffered in storage for a single `T` inside of the
+/// deframer, thus nothing is allocated.
+pub struct Deframer<T: Safecast> {
+    /// Storage for the `T` being received
+    buf: core::mem::MaybeUninit<T>,
+
+    /// Number of bytes of `buf` received so far
+    len: usize,
+}
+
+impl<T: Safecast> Deframer<T> {
+    /// Create a new deframer with no bytes buffered
+    pub const fn new() -> Self {
+        Deframer { buf: core::mem::MaybeUninit::uninit(), len: 0 }
+    }
+
+    /// Get the number of bytes of the next `T` buffered so far
+    pub fn buffered(&self) -> usize {
+        self.len
+    }
+
+    /// Discard any buffered bytes
+    pub fn reset(&mut self) {
+        self.len = 0;
+    }
+
+    /// Buffer bytes from the start of `bytes`, returning the next `T` once
+    /// all of its bytes have been received
+    ///
+    /// `bytes` is advanced past the bytes consumed, which stops at the end
+    /// of the returned `T`. When multiple values arrive at once, call this
+    /// in a loop until it returns `None`, by which point `bytes` is empty.
+    pub fn push(&mut self, bytes: &mut &[u8]) -> Option<T> {
+        // Make sure we're not working with zero-size-types
+        cast_assert!(core::mem::size_of::<T>() > 0,
+                "ZST not allowed: {}", type_name::<T>());
+
+        // Copy as much of the rest of the `T` as we have
+        let size = core::mem::size_of::<T>();
+        let take = core::cmp::min(size - self.len, bytes.len());
+        unsafe {
+            core::ptr::copy_nonoverlapping(bytes.as_ptr(),
+                (self.buf.as_mut_ptr() as *mut u8).add(self.len), take);
+        }
+        self.len += take;
+        *bytes = &bytes[take..];
+
+        if self.len < size {
+            return None;
+        }
+        self.len = 0;
+
+        // Safe to assume initialized here because we filled in _all_ the
+        // bytes, and every bit pattern is valid for a `Safecast` type
+        let ret = unsafe { self.buf.assume_init_read() };
+
+        // Validate runtime checks on output
+        crate::run_checks(&ret);
+
+        Some(ret)
+    }
+}
+
+impl<T: Safecast> Default for Deframer<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,226 @@
+//! Iterators over length-prefixed records
+//!
+//! Many log files and RPC framings are a sequence of records, each a header
+//! giving the length of the payload which follows it. `Records` walks such a
+//! buffer, checking that every header and payload is in bounds, and yields
+//! each header along with its payload.
+//!
+//! Type-length-value (TLV) formats, such as BLE advertising data or ACPI
+//! tables, additionally tag each record with its type and may nest records
+//! inside of others. `Tlvs` walks these, yielding `Tlv` elements whose values
+//! can be viewed as typed structures or walked as nested TLVs.
+
+use core::convert::TryFrom;
+use crate::{Safecast, SafecastError};
+use crate::endian::{U16Le, U32Le, U64Le, U16Be, U32Be, U64Be};
+use crate::net::U24Be;
+
+/// Trait for the header of a length-prefixed record
+///
+/// This is implemented for `u8` and the unsigned fixed-endianness integers,
+/// for records prefixed only by their length, eg. `[len: U32Le][payload]`.
+pub trait RecordHeader: Safecast {
+    /// Get the length in bytes of the payload following the header
+    fn payload_len(&self) -> usize;
+}
+
+impl RecordHeader for u8 {
+    fn payload_len(&self) -> usize { *self as usize }
+}
+
+macro_rules! record_header {
+    ($($ty:ty),*) => {
+        $(
+            impl RecordHeader for $ty {
+                // Lengths which do not fit in a `usize` are always out of
+                // bounds
+                fn payload_len(&self) -> usize {
+                    usize::try_from(self.get()).unwrap_or(usize::MAX)
+                }
+            }
+        )*
+    }
+}
+
+record_header!(U16Le, U32Le, U64Le, U16Be, U24Be, U32Be, U64Be);
+
+/// Iterate over the records in `bytes`, each a `H` followed by its payload
+pub fn records<H: RecordHeader>(bytes: &[u8]) -> Records<'_, H> {
+    Records { bytes, _header: core::marker::PhantomData }
+}
+
+/// Iterator over length-prefixed records, created with `records`
+///
+/// Yields each header along with its payload. A record which does not fit in
+/// the remaining bytes yields an `OutOfBounds` error, and a header which is
+/// not suitably aligned for `H` yields an `AlignmentMismatch` error. After an
+/// error the iterator ends.
+#[derive(Debug, Clone)]
+pub struct Records<'a, H> {
+    /// Bytes of the records which have not been parsed yet
+    bytes: &'a [u8],
+
+    /// Marker for the type of the header
+    _header: core::marker::PhantomData<H>,
+}
+
+impl<'a, H> Records<'a, H> {
+    /// Get the bytes which have not been parsed yet. After an error this is
+    /// empty.
+    pub fn remaining(&self) -> &'a [u8] {
+        self.bytes
+    }
+}
+
+/// Split the record at the start of `*bytes` into its header and payload,
+/// where `payload_len` gets the length of the payload from the header. On
+/// success `*bytes` is advanced past the record, on error it is emptied such
+/// that iteration ends. Returns `None` once `*bytes` is empty.
+fn next_record<'a, H: Safecast + 'a>(bytes: &mut &'a [u8],
+        payload_len: impl FnOnce(&H) -> Result<usize, SafecastError>)
+        -> Option<Result<(&'a H, &'a [u8]), SafecastError>> {
+    if bytes.is_empty() {
+        return None;
+    }
+
+    let parse = || {
+        if bytes.len() < core::mem::size_of::<H>() {
+            return Err(SafecastError::OutOfBounds);
+        }
+
+        let (header, rest) = bytes.cast_prefix::<H>()?;
+        let len = payload_len(header)?;
+        if len > rest.len() {
+            return Err(SafecastError::OutOfBounds);
+        }
+
+        let (payload, rest) = rest.split_at(len);
+        Ok((header, payload, rest))
+    };
+
+    Some(match parse() {
+        Ok((header, payload, rest)) => {
+            *bytes = rest;
+            Ok((header, payload))
+        }
+        Err(err) => {
+            *bytes = &[];
+            Err(err)
+        }
+    })
+}
+
+impl<'a, H: RecordHeader + 'a> Iterator for Records<'a, H> {
+    type Item = Result<(&'a H, &'a [u8]), SafecastError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        next_record(&mut self.bytes, |header: &H| Ok(header.payload_len()))
+    }
+}
+
+impl<'a, H: RecordHeader + 'a> core::iter::FusedIterator
+    for Records<'a, H> {}
+
+/// Trait for the header of a type-length-value element
+#[allow(clippy::len_without_is_empty)]
+pub trait TlvHeader: Safecast {
+    /// Type of the tags of the elements, eg. `u8` or an enum
+    type Tag;
+
+    /// Set if `len()` includes the size of the header itself, as is the case
+    /// for ACPI tables, rather than only the size of the value
+    const LEN_INCLUDES_HEADER: bool = false;
+
+    /// Get the tag giving the type of the element
+    fn tag(&self) -> Self::Tag;
+
+    /// Get the length of the element in bytes
+    fn len(&self) -> usize;
+}
+
+/// Iterate over the TLV elements in `bytes`, each a `H` followed by its
+/// value
+pub fn tlvs<H: TlvHeader>(bytes: &[u8]) -> Tlvs<'_, H> {
+    Tlvs { bytes, _header: core::marker::PhantomData }
+}
+
+/// A single type-length-value element
+#[derive(Debug)]
+pub struct Tlv<'a, H> {
+    /// Header of the element
+    pub header: &'a H,
+
+    /// Value of the element following the header
+    pub value: &'a [u8],
+}
+
+impl<H> Clone for Tlv<'_, H> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<H> Copy for Tlv<'_, H> {}
+
+impl<'a, H: TlvHeader> Tlv<'a, H> {
+    /// Get the tag giving the type of the element
+    pub fn tag(&self) -> H::Tag {
+        self.header.tag()
+    }
+
+    /// View the value as a `T`
+    ///
+    /// Returns an error if the length of the value does not exactly match
+    /// the size of `T`, or if the value is not suitably aligned for `T`.
+    pub fn view<T: Safecast>(&self) -> Result<&'a T, SafecastError> {
+        T::from_bytes(self.value)
+    }
+
+    /// Iterate over the TLV elements nested inside of the value. Nested
+    /// elements are bounds checked against the value, thus they can never
+    /// overflow into the elements following this one.
+    pub fn children<C: TlvHeader>(&self) -> Tlvs<'a, C> {
+        tlvs(self.value)
+    }
+}
+
+/// Iterator over type-length-value elements, created with `tlvs`
+///
+/// Yields each element, or an error like `Records`. A header whose length
+/// does not cover the header itself when `LEN_INCLUDES_HEADER` is set yields
+/// an `InvalidValue` error.
+#[derive(Debug, Clone)]
+pub struct Tlvs<'a, H> {
+    /// Bytes of the elements which have not been parsed yet
+    bytes: &'a [u8],
+
+    /// Marker for the type of the header
+    _header: core::marker::PhantomData<H>,
+}
+
+impl<'a, H> Tlvs<'a, H> {
+    /// Get the bytes which have not been parsed yet. After an error this is
+    /// empty.
+    pub fn remaining(&self) -> &'a [u8] {
+        self.bytes
+    }
+}
+
+impl<'a, H: TlvHeader + 'a> Iterator for Tlvs<'a, H> {
+    type Item = Result<Tlv<'a, H>, SafecastError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let value_len = |header: &H| if H::LEN_INCLUDES_HEADER {
+            header.len().checked_sub(core::mem::size_of::<H>())
+                .ok_or(SafecastError::InvalidValue)
+        } else {
+            Ok(header.len())
+        };
+
+        next_record(&mut self.bytes, value_len).map(|x| {
+            x.map(|(header, value)| Tlv { header, value })
+        })
+    }
+}
+
+impl<'a, H: TlvHeader + 'a> core::iter::FusedIterator for Tlvs<'a, H> {}
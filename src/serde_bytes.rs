@@ -0,0 +1,146 @@
+//! Serde adapters for embedding values as their raw bytes
+//!
+//! Use with `#[serde(with = "safecast::serde_bytes")]` on a field of a
+//! `Serialize`/`Deserialize` structure to store the field as its exact bytes,
+//! rather than field by field. Human-readable formats such as JSON get the
+//! bytes as a base64 string, while binary formats such as CBOR get them as a
+//! byte string. Deserializing accepts either, along with a sequence of
+//! integers, and validates the bytes like any other read of a `T`. Requires
+//! the `serde` feature.
+
+use core::fmt;
+use ::serde::{Serializer, Deserializer};
+use ::serde::de::{self, Visitor, SeqAccess, Unexpected};
+use crate::{CheckedSafecast, IntoBytes};
+
+/// Alphabet of standard, padded base64
+const BASE64: &[u8; 64] =
+    b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+/// Bytes formatted as padded base64
+struct Base64<'a>(&'a [u8]);
+
+impl fmt::Display for Base64<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for chunk in self.0.chunks(3) {
+            let mut group = [0u8; 3];
+            group[..chunk.len()].copy_from_slice(chunk);
+            let bits = u32::from_be_bytes([0, group[0], group[1], group[2]]);
+
+            let mut out = [b'='; 4];
+            for (ii, out) in out.iter_mut().take(chunk.len() + 1).enumerate() {
+                *out = BASE64[(bits >> (18 - ii * 6)) as usize & 0x3f];
+            }
+            f.write_str(core::str::from_utf8(&out).unwrap())?;
+        }
+        Ok(())
+    }
+}
+
+/// Decode the padded base64 in `s` into `out`, returning `None` if `s` is not
+/// valid base64 or does not decode to exactly `out.len()` bytes
+fn decode_base64(s: &str, out: &mut [u8]) -> Option<()> {
+    let s = s.as_bytes();
+    if s.len() != out.len().div_ceil(3) * 4 {
+        return None;
+    }
+
+    for (chunk, out) in s.chunks(4).zip(out.chunks_mut(3)) {
+        let mut bits = 0u32;
+        for (ii, &x) in chunk.iter().enumerate() {
+            // Only the characters past the end of the bytes are padding
+            let val = if ii > out.len() {
+                (x == b'=').then_some(0)?
+            } else {
+                BASE64.iter().position(|&y| y == x)? as u32
+            };
+            bits |= val << (18 - ii * 6);
+        }
+        out.copy_from_slice(&bits.to_be_bytes()[1..][..out.len()]);
+    }
+    Some(())
+}
+
+/// Serialize the bytes of `val`
+pub fn serialize<T: IntoBytes, S: Serializer>(val: &T, serializer: S)
+        -> Result<S::Ok, S::Error> {
+    if serializer.is_human_readable() {
+        serializer.collect_str(&Base64(val.as_bytes()))
+    } else {
+        serializer.serialize_bytes(val.as_bytes())
+    }
+}
+
+/// Visitor filling in the bytes of a `T`
+struct BytesVisitor<T>(core::marker::PhantomData<T>);
+
+impl<T: CheckedSafecast> BytesVisitor<T> {
+    /// Validate the bytes of a `T` filled in by `fill`
+    fn build<E: de::Error>(&self,
+            fill: impl FnOnce(&mut [u8]) -> Result<(), E>) -> Result<T, E> {
+        // Zero the storage first, such that `fill` is only ever given
+        // initialized bytes
+        let mut bytes = core::mem::MaybeUninit::<T>::zeroed();
+        let bytes = unsafe {
+            core::slice::from_raw_parts_mut(bytes.as_mut_ptr() as *mut u8,
+                                            core::mem::size_of::<T>())
+        };
+
+        fill(bytes)?;
+        T::try_read_from(bytes).map_err(E::custom)
+    }
+}
+
+impl<'de, T: CheckedSafecast> Visitor<'de> for BytesVisitor<T> {
+    type Value = T;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} bytes as base64 or a byte string",
+               core::mem::size_of::<T>())
+    }
+
+    fn visit_bytes<E: de::Error>(self, v: &[u8]) -> Result<T, E> {
+        self.build(|bytes| {
+            if v.len() != bytes.len() {
+                return Err(E::invalid_length(v.len(), &self));
+            }
+            bytes.copy_from_slice(v);
+            Ok(())
+        })
+    }
+
+    fn visit_str<E: de::Error>(self, v: &str) -> Result<T, E> {
+        self.build(|bytes| {
+            decode_base64(v, bytes)
+                .ok_or_else(|| E::invalid_value(Unexpected::Str(v), &self))
+        })
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A)
+            -> Result<T, A::Error> {
+        self.build(|bytes| {
+            let len = bytes.len();
+            for (ii, byte) in bytes.iter_mut().enumerate() {
+                *byte = seq.next_element()?
+                    .ok_or_else(|| de::Error::invalid_length(ii, &self))?;
+            }
+
+            // Make sure there are no bytes past the end of the `T`
+            if seq.next_element::<de::IgnoredAny>()?.is_some() {
+                return Err(de::Error::invalid_length(len + 1, &self));
+            }
+            Ok(())
+        })
+    }
+}
+
+/// Deserialize a `T` from its bytes
+pub fn deserialize<'de, T: CheckedSafecast, D: Deserializer<'de>>(
+        deserializer: D) -> Result<T, D::Error> {
+    let visitor = BytesVisitor(core::marker::PhantomData);
+    if deserializer.is_human_readable() {
+        deserializer.deserialize_any(visitor)
+    } else {
+        deserializer.deserialize_bytes(visitor)
+    }
+}
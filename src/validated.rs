@@ -0,0 +1,149 @@
+//! Proof that the runtime checks of a type have passed
+//!
+//! Most types are checked entirely at compile time, see
+//! `Safecast::VALIDATED`, but types with hand written `Safecast` impls may
+//! run their checks every time they are cast. `Validated<T>` runs them once
+//! up front, and its cast routines skip them, which moves the cost out of
+//! loops casting millions of records.
+//!
+//! The routines of `Validated<T>` skip all of `safecast`, thus it is only
+//! available for types whose checks depend on the layout alone. Types with
+//! value-dependent checks, such as `#[safecast(validate = ...)]` fields, see
+//! `Safecast::CHECKS_VALUES`, are rejected at compile time.
+
+use core::any::type_name;
+use crate::{Safecast, SafecastError};
+
+/// A zero-sized proof that the runtime checks of `T` have passed
+pub struct Validated<T: Safecast> {
+    /// Marker for the validated type
+    _marker: core::marker::PhantomData<fn() -> T>,
+}
+
+impl<T: Safecast> Clone for Validated<T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T: Safecast> Copy for Validated<T> {}
+
+impl<T: Safecast> core::fmt::Debug for Validated<T> {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+        write!(f, "Validated<{}>", core::any::type_name::<T>())
+    }
+}
+
+/// Make sure `bytes` is exactly the size of a `T` and is suitably aligned
+/// for a `T`
+fn check_bytes<T>(bytes: &[u8]) -> Result<(), SafecastError> {
+    if bytes.len() != core::mem::size_of::<T>() {
+        return Err(SafecastError::size_mismatch(bytes.len(),
+            core::mem::size_of::<T>(), core::mem::size_of::<T>()));
+    }
+    if !bytes.as_ptr().cast::<T>().is_aligned() {
+        return Err(SafecastError::AlignmentMismatch);
+    }
+    Ok(())
+}
+
+/// Make sure `bytes` holds a whole number of `T`s and is suitably aligned for
+/// a `T`, returning the number of `T`s
+fn check_slice<T>(bytes: &[u8]) -> Result<usize, SafecastError> {
+    if !bytes.len().is_multiple_of(core::mem::size_of::<T>()) {
+        return Err(SafecastError::slice_size_mismatch(bytes.len(),
+            core::mem::size_of::<T>()));
+    }
+    if !bytes.as_ptr().cast::<T>().is_aligned() {
+        return Err(SafecastError::AlignmentMismatch);
+    }
+    Ok(bytes.len() / core::mem::size_of::<T>())
+}
+
+impl<T: Safecast> Validated<T> {
+    /// Rejects `T`s whose values are checked, as the routines skip the checks
+    const LAYOUT_ONLY: () = assert!(!T::CHECKS_VALUES,
+        "Validated<T> is not allowed on types whose values are checked");
+
+    /// Run the layout checks of `T` on a zeroed `T`, returning the proof that
+    /// they passed
+    ///
+    /// This panics if the checks fail, just like any other cast of a `T`.
+    pub fn check() -> Self {
+        let () = Self::LAYOUT_ONLY;
+
+        // Make sure we're not working with zero-size-types
+        cast_assert!(core::mem::size_of::<T>() > 0,
+                "ZST not allowed: {}", type_name::<T>());
+
+        let probe = core::mem::MaybeUninit::<T>::zeroed();
+        Safecast::check_layout(unsafe { &*probe.as_ptr() });
+        Validated { _marker: core::marker::PhantomData }
+    }
+
+    /// Interpret `bytes` as a reference to a `T` without copying or running
+    /// the runtime checks of `T`
+    ///
+    /// Returns an error if the length of `bytes` does not exactly match the
+    /// size of `T` or if `bytes` is not suitably aligned for `T`.
+    pub fn from_bytes(self, bytes: &[u8]) -> Result<&T, SafecastError> {
+        // Shared references must not be able to mutate each other
+        cast_assert!(!T::INTERIOR_MUTABLE,
+                "Shared cast of interior mutable type: {}",
+                type_name::<T>());
+
+        check_bytes::<T>(bytes)?;
+        Ok(unsafe { &*(bytes.as_ptr() as *const T) })
+    }
+
+    /// Interpret `bytes` as a mutable reference to a `T` without copying or
+    /// running the runtime checks of `T`
+    ///
+    /// Returns the same errors as `from_bytes`.
+    pub fn from_bytes_mut(self, bytes: &mut [u8])
+            -> Result<&mut T, SafecastError> {
+        check_bytes::<T>(bytes)?;
+        Ok(unsafe { &mut *(bytes.as_mut_ptr() as *mut T) })
+    }
+
+    /// Interpret `bytes` as a slice of `T`s without copying or running the
+    /// runtime checks of `T`
+    ///
+    /// Returns an error if the length of `bytes` is not a multiple of the
+    /// size of `T` or if `bytes` is not suitably aligned for `T`.
+    pub fn cast_slice(self, bytes: &[u8]) -> Result<&[T], SafecastError> {
+        // Shared references must not be able to mutate each other
+        cast_assert!(!T::INTERIOR_MUTABLE,
+                "Shared cast of interior mutable type: {}",
+                type_name::<T>());
+
+        let len = check_slice::<T>(bytes)?;
+        Ok(unsafe {
+            core::slice::from_raw_parts(bytes.as_ptr() as *const T, len)
+        })
+    }
+
+    /// Interpret `bytes` as a mutable slice of `T`s without copying or
+    /// running the runtime checks of `T`
+    ///
+    /// Returns the same errors as `cast_slice`.
+    pub fn cast_slice_mut(self, bytes: &mut [u8])
+            -> Result<&mut [T], SafecastError> {
+        let len = check_slice::<T>(bytes)?;
+        Ok(unsafe {
+            core::slice::from_raw_parts_mut(bytes.as_mut_ptr() as *mut T, len)
+        })
+    }
+
+    /// Read a `T` out of `bytes` without running the runtime checks of `T`
+    ///
+    /// There are no alignment requirements on `bytes`. Returns an error if
+    /// the length of `bytes` does not exactly match the size of `T`.
+    pub fn read(self, bytes: &[u8]) -> Result<T, SafecastError> {
+        if bytes.len() != core::mem::size_of::<T>() {
+            return Err(SafecastError::size_mismatch(bytes.len(),
+                core::mem::size_of::<T>(), core::mem::size_of::<T>()));
+        }
+        Ok(unsafe { core::ptr::read_unaligned(bytes.as_ptr() as *const T) })
+    }
+}
@@ -0,0 +1,151 @@
+//! Bounds-checked windows into byte buffers
+//!
+//! `PodView` and `PodViewMut` wrap a range of a larger byte buffer and read
+//! or write typed values at offsets relative to the start of the window.
+//! Every access is bounds checked against the window, thus nothing can read
+//! or write outside of it. Windows can be narrowed further with `window`,
+//! which makes parsing nested containers, such as an IP packet inside of an
+//! Ethernet frame, composable.
+
+use crate::{CheckedSafecast, IntoBytes, SafecastError};
+use crate::reader::peek;
+
+/// Get the `len` bytes at `offset` in `bytes`, or an error if they are not
+/// entirely in bounds
+fn range(bytes: &[u8], offset: usize, len: usize)
+        -> Result<&[u8], SafecastError> {
+    let end = offset.checked_add(len).ok_or(SafecastError::OutOfBounds)?;
+    bytes.get(offset..end).ok_or(SafecastError::OutOfBounds)
+}
+
+/// Get the `len` bytes at `offset` in `bytes` mutably, or an error if they
+/// are not entirely in bounds
+fn range_mut(bytes: &mut [u8], offset: usize, len: usize)
+        -> Result<&mut [u8], SafecastError> {
+    let end = offset.checked_add(len).ok_or(SafecastError::OutOfBounds)?;
+    bytes.get_mut(offset..end).ok_or(SafecastError::OutOfBounds)
+}
+
+/// A bounds-checked window into a shared byte buffer
+#[derive(Debug, Clone, Copy)]
+pub struct PodView<'a> {
+    /// Bytes of the window
+    bytes: &'a [u8],
+}
+
+impl<'a> PodView<'a> {
+    /// Create a new window covering all of `bytes`
+    pub const fn new(bytes: &'a [u8]) -> Self {
+        PodView { bytes }
+    }
+
+    /// Get the length of the window in bytes
+    pub fn len(&self) -> usize {
+        self.bytes.len()
+    }
+
+    /// Returns `true` if the window is empty
+    pub fn is_empty(&self) -> bool {
+        self.bytes.is_empty()
+    }
+
+    /// Get the bytes of the window
+    pub fn bytes(&self) -> &'a [u8] {
+        self.bytes
+    }
+
+    /// Read the `T` at `offset` in the window
+    ///
+    /// There are no alignment requirements on `offset`. Returns an error if
+    /// the `T` is not entirely inside of the window, or if the bytes do not
+    /// hold a valid `T`.
+    pub fn get<T: CheckedSafecast>(&self, offset: usize)
+            -> Result<T, SafecastError> {
+        peek(range(self.bytes, offset, core::mem::size_of::<T>())?)
+    }
+
+    /// Get the window of `len` bytes at `offset` in this window
+    ///
+    /// Returns an error if the range is not entirely inside of this window.
+    pub fn window(&self, offset: usize, len: usize)
+            -> Result<PodView<'a>, SafecastError> {
+        Ok(PodView::new(range(self.bytes, offset, len)?))
+    }
+}
+
+/// A bounds-checked window into a mutable byte buffer
+#[derive(Debug)]
+pub struct PodViewMut<'a> {
+    /// Bytes of the window
+    bytes: &'a mut [u8],
+}
+
+impl<'a> PodViewMut<'a> {
+    /// Create a new window covering all of `bytes`
+    pub fn new(bytes: &'a mut [u8]) -> Self {
+        PodViewMut { bytes }
+    }
+
+    /// Get the length of the window in bytes
+    pub fn len(&self) -> usize {
+        self.bytes.len()
+    }
+
+    /// Returns `true` if the window is empty
+    pub fn is_empty(&self) -> bool {
+        self.bytes.is_empty()
+    }
+
+    /// Get the bytes of the window
+    pub fn bytes(&self) -> &[u8] {
+        self.bytes
+    }
+
+    /// Get the bytes of the window mutably
+    pub fn bytes_mut(&mut self) -> &mut [u8] {
+        self.bytes
+    }
+
+    /// Read the `T` at `offset` in the window
+    ///
+    /// There are no alignment requirements on `offset`. Returns an error if
+    /// the `T` is not entirely inside of the window, or if the bytes do not
+    /// hold a valid `T`.
+    pub fn get<T: CheckedSafecast>(&self, offset: usize)
+            -> Result<T, SafecastError> {
+        self.as_view().get(offset)
+    }
+
+    /// Write the bytes of `val` at `offset` in the window
+    ///
+    /// There are no alignment requirements on `offset`. Returns an error if
+    /// the `T` is not entirely inside of the window, in which case nothing
+    /// is written.
+    pub fn set<T: IntoBytes>(&mut self, offset: usize, val: &T)
+            -> Result<(), SafecastError> {
+        let bytes = val.as_bytes();
+        range_mut(self.bytes, offset, bytes.len())?.copy_from_slice(bytes);
+        Ok(())
+    }
+
+    /// Get a shared view of this window
+    pub fn as_view(&self) -> PodView<'_> {
+        PodView::new(self.bytes)
+    }
+
+    /// Get the window of `len` bytes at `offset` in this window
+    ///
+    /// Returns an error if the range is not entirely inside of this window.
+    pub fn window(&self, offset: usize, len: usize)
+            -> Result<PodView<'_>, SafecastError> {
+        self.as_view().window(offset, len)
+    }
+
+    /// Get the window of `len` bytes at `offset` in this window mutably
+    ///
+    /// Returns an error if the range is not entirely inside of this window.
+    pub fn window_mut(&mut self, offset: usize, len: usize)
+            -> Result<PodViewMut<'_>, SafecastError> {
+        Ok(PodViewMut::new(range_mut(self.bytes, offset, len)?))
+    }
+}
@@ -0,0 +1,103 @@
+//! Cursor for sequentially serializing typed values into bytes
+//!
+//! `ByteWriter` wraps a `&mut [u8]` and writes values one after another, as
+//! is done when building packets out of several headers. There are no
+//! alignment requirements on the bytes. Writes which do not fit in the
+//! remaining bytes return an error and leave the writer where it was.
+
+use crate::{IntoBytes, SafecastError};
+
+/// Write the bytes of every value in `vals` to the start of `bytes`
+///
+/// There are no alignment requirements on `bytes`. Returns an error if
+/// `bytes` is too short to hold all of `vals`, in which case nothing is
+/// written.
+pub fn write_array<T: IntoBytes, const N: usize>(bytes: &mut [u8],
+                                                 vals: &[T; N])
+        -> Result<(), SafecastError> {
+    ByteWriter::new(bytes).write_slice(vals)
+}
+
+/// A cursor writing typed values sequentially into a byte slice
+#[derive(Debug)]
+pub struct ByteWriter<'a> {
+    /// Bytes being written
+    bytes: &'a mut [u8],
+
+    /// Offset of the next byte to write
+    offset: usize,
+}
+
+impl<'a> ByteWriter<'a> {
+    /// Create a new writer starting at the beginning of `bytes`
+    pub fn new(bytes: &'a mut [u8]) -> Self {
+        ByteWriter { bytes, offset: 0 }
+    }
+
+    /// Get the offset of the next byte to write, which is also the number of
+    /// bytes written so far
+    pub fn position(&self) -> usize {
+        self.offset
+    }
+
+    /// Get the number of bytes left to write
+    pub fn remaining(&self) -> usize {
+        self.bytes.len() - self.offset
+    }
+
+    /// Get the bytes written so far
+    pub fn written(&self) -> &[u8] {
+        &self.bytes[..self.offset]
+    }
+
+    /// Consume the writer, getting the bytes written
+    pub fn into_written(self) -> &'a mut [u8] {
+        &mut self.bytes[..self.offset]
+    }
+
+    /// Write `bytes`
+    ///
+    /// Returns an error if fewer than `bytes.len()` bytes remain.
+    pub fn write_bytes(&mut self, bytes: &[u8]) -> Result<(), SafecastError> {
+        self.bytes[self.offset..].get_mut(..bytes.len())
+            .ok_or(SafecastError::OutOfBounds)?
+            .copy_from_slice(bytes);
+        self.offset += bytes.len();
+        Ok(())
+    }
+
+    /// Write `len` zero bytes, eg. for reserved fields or alignment
+    ///
+    /// Returns an error if fewer than `len` bytes remain.
+    pub fn write_zeros(&mut self, len: usize) -> Result<(), SafecastError> {
+        self.bytes[self.offset..].get_mut(..len)
+            .ok_or(SafecastError::OutOfBounds)?
+            .fill(0);
+        self.offset += len;
+        Ok(())
+    }
+
+    /// Write the bytes of `val`
+    ///
+    /// Returns an error if there are not enough bytes left to hold a `T`.
+    pub fn write<T: IntoBytes>(&mut self, val: &T)
+            -> Result<(), SafecastError> {
+        self.write_bytes(val.as_bytes())
+    }
+
+    /// Write the bytes of every value in `vals`
+    ///
+    /// Returns an error if there are not enough bytes left to hold all of
+    /// `vals`, in which case nothing is written.
+    pub fn write_slice<T: IntoBytes>(&mut self, vals: &[T])
+            -> Result<(), SafecastError> {
+        if core::mem::size_of_val(vals) > self.remaining() {
+            return Err(SafecastError::OutOfBounds);
+        }
+
+        for val in vals {
+            self.write(val)?;
+        }
+        Ok(())
+    }
+}
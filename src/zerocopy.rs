@@ -0,0 +1,44 @@
+//! Interoperability with `zerocopy`
+//!
+//! `Zerocopy<T>` wraps a type implementing zerocopy's `FromBytes`,
+//! `IntoBytes`, and `Immutable` such that it can be used anywhere a
+//! `Safecast` type is expected, eg. `bytes.cast::<Zerocopy<Header>>()`.
+//!
+//! In the other direction, the fixed-endianness integers and network types
+//! of this crate implement zerocopy's traits, thus structures built out of
+//! them can derive both `Safecast` and zerocopy's traits and be passed to
+//! either crate. zerocopy does not allow its traits to be implemented by
+//! other derives, thus they must be derived with zerocopy itself. Requires
+//! the `zerocopy` feature.
+
+use ::zerocopy::{FromBytes, IntoBytes, Immutable};
+use crate::Safecast;
+
+/// A zerocopy type usable as a `Safecast` type
+///
+/// `FromBytes` guarantees every bit pattern is valid, `IntoBytes` guarantees
+/// there is no padding, and `Immutable` guarantees there is no interior
+/// mutability, which is everything `Safecast` requires.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Default, Debug)]
+#[repr(transparent)]
+pub struct Zerocopy<T: FromBytes + IntoBytes + Immutable>(pub T);
+
+unsafe impl<T: FromBytes + IntoBytes + Immutable> Safecast for Zerocopy<T> {
+    fn safecast(&self) {}
+    const VALIDATED: bool = true;
+}
+
+impl<T: FromBytes + IntoBytes + Immutable> core::ops::Deref for Zerocopy<T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.0
+    }
+}
+
+impl<T: FromBytes + IntoBytes + Immutable> core::ops::DerefMut
+        for Zerocopy<T> {
+    fn deref_mut(&mut self) -> &mut T {
+        &mut self.0
+    }
+}
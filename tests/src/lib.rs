@@ -1,19 +1,336 @@
+//! Layouts with padding are rejected by the derives at compile time
+//!
+//! ```compile_fail
+//! #[derive(safecast::Safecast)]
+//! #[repr(C)]
+//! struct Padded(u32, u8);
+//! ```
+//!
+//! Raising the alignment adds trailing padding
+//!
+//! ```compile_fail
+//! #[derive(safecast::Safecast)]
+//! #[repr(C, align(16))]
+//! struct Overaligned { a: u32, b: u32 }
+//! ```
+//!
+//! Unless it is declared as an explicit field
+//!
+//! ```
+//! #[derive(safecast::Safecast)]
+//! #[repr(C, align(16))]
+//! struct Overaligned { a: u32, b: u32, _pad: [u8; 8] }
+//! ```
+//!
+//! The same applies to structures with fields which are only
+//! `CheckedSafecast`
+//!
+//! ```compile_fail
+//! #[derive(safecast::CheckedSafecast)]
+//! #[repr(C)]
+//! struct Padded(bool, u16);
+//! ```
+//!
+//! Every variant of a union must cover the entire union
+//!
+//! ```compile_fail
+//! #[derive(safecast::Safecast, Clone, Copy)]
+//! #[repr(C)]
+//! union Uneven { small: u32, large: u64 }
+//! ```
+//!
+//! Every variant of an enum must cover the entire enum
+//!
+//! ```compile_fail
+//! #[derive(safecast::CheckedSafecast)]
+//! #[repr(C, u8)]
+//! enum Padded { Small(u8), Big(u32) }
+//! ```
+//!
+//! Alignments given with `#[safecast(assert_align = N)]` are also checked
+//!
+//! ```compile_fail
+//! #[derive(safecast::Safecast)]
+//! #[repr(C)]
+//! #[safecast(assert_align = 8)]
+//! struct Descriptor { addr: u32, len: u32 }
+//! ```
+//!
+//! Portable structures may not contain fields which change size between
+//! targets
+//!
+//! ```compile_fail
+//! #[derive(safecast::Safecast)]
+//! #[repr(C)]
+//! #[safecast(portable)]
+//! struct Entry { offset: u64, len: usize }
+//! ```
+//!
+//! ```compile_fail
+//! #[derive(safecast::Safecast)]
+//! #[repr(C)]
+//! #[safecast(portable)]
+//! struct Entry { offset: u64, next: Option<core::num::NonZeroUsize> }
+//! ```
+//!
+//! ```compile_fail
+//! #[derive(safecast::Safecast)]
+//! #[repr(C)]
+//! #[safecast(portable)]
+//! struct Entry { offset: u64, next: Option<core::ptr::NonNull<Entry>> }
+//! ```
+//!
+//! Wire structures may not contain fields with a host-dependent byte order,
+//! including through wrappers
+//!
+//! ```compile_fail
+//! #[derive(safecast::Safecast)]
+//! #[repr(C)]
+//! #[safecast(wire)]
+//! struct Header { magic: [u8; 4], len: u32 }
+//! ```
+//!
+//! ```compile_fail
+//! #[derive(safecast::Safecast)]
+//! #[repr(C)]
+//! #[safecast(wire)]
+//! struct Header { magic: [u8; 4], len: [i16; 4] }
+//! ```
+//!
+//! ```compile_fail
+//! #[derive(safecast::Safecast)]
+//! #[repr(C)]
+//! #[safecast(wire)]
+//! struct Header { magic: [u8; 4], len: ::core::num::Wrapping<u32> }
+//! ```
+//!
+//! ```compile_fail
+//! #[derive(safecast::Safecast)]
+//! #[repr(C)]
+//! #[safecast(wire)]
+//! struct Header { magic: [u8; 4], len: Option<core::num::NonZeroU32> }
+//! ```
+//!
+//! ```compile_fail
+//! #[derive(safecast::Safecast)]
+//! #[repr(C)]
+//! #[safecast(wire)]
+//! struct Header { magic: [u8; 4], len: core::sync::atomic::AtomicU32 }
+//! ```
+//!
+//! ```compile_fail
+//! #[derive(safecast::CheckedSafecast)]
+//! #[repr(C)]
+//! #[safecast(wire)]
+//! struct Header { magic: [u8; 8], len: core::num::NonZeroU64 }
+//! ```
+//!
+//! Generic structures are checked separately for every instantiation
+//!
+//! ```compile_fail
+//! use safecast::Safecast;
+//!
+//! #[derive(Safecast, Clone, Copy)]
+//! #[repr(C)]
+//! struct Frame<const N: usize> { hdr: u32, payload: [u8; N] }
+//!
+//! Frame::<3> { hdr: 0, payload: [0; 3] }.cast::<u8>();
+//! ```
+//!
+//! ```
+//! use safecast::Safecast;
+//!
+//! #[derive(Safecast, Clone, Copy)]
+//! #[repr(C)]
+//! struct Frame<const N: usize> { hdr: u32, payload: [u8; N] }
+//!
+//! Frame::<4> { hdr: 0, payload: [0; 4] }.cast::<u8>();
+//! ```
+//!
+//! Static assertions reject types which are not `Safecast`
+//!
+//! ```compile_fail
+//! safecast::assert_pod!(u32, &'static u8);
+//! ```
+//!
+//! ```compile_fail
+//! struct Plain { magic: u32 }
+//!
+//! safecast::assert_pod!(Plain);
+//! ```
+//!
+//! Or which have padding, given the types of their fields
+//!
+//! ```compile_fail
+//! #[repr(C)]
+//! struct Header { magic: u32, len: u16 }
+//!
+//! safecast::assert_no_padding!(Header: u32, u16);
+//! ```
+//!
+//! ```
+//! #[repr(C)]
+//! struct Header { magic: u32, len: u16, kind: u16 }
+//!
+//! safecast::assert_no_padding!(Header: u32, u16, u16);
+//! ```
+//!
+//! Or given a hand written `Layout`
+//!
+//! ```compile_fail
+//! use safecast::layout::{Layout, FieldLayout};
+//!
+//! #[repr(C)]
+//! struct Header { magic: u32, len: u16 }
+//!
+//! impl Layout for Header {
+//!     const FIELDS: &'static [FieldLayout] = &[
+//!         FieldLayout { name: "magic", offset: 0, size: 4 },
+//!         FieldLayout { name: "len", offset: 4, size: 2 },
+//!     ];
+//! }
+//!
+//! safecast::assert_no_padding!(Header);
+//! ```
+//!
+//! Mirrors of structures must match in size, alignment, and optionally fields
+//!
+//! ```compile_fail
+//! safecast::assert_layout_eq!(u32, [u8; 4]);
+//! ```
+//!
+//! ```compile_fail
+//! #[derive(safecast::Safecast)]
+//! #[repr(C)]
+//! struct Header { magic: u32, len: u16, kind: u16 }
+//!
+//! #[derive(safecast::Safecast)]
+//! #[repr(C)]
+//! struct Mirror { magic: u16, kind: u16, len: u32 }
+//!
+//! safecast::assert_layout_eq!(Header, Mirror, fields);
+//! ```
+//!
+//! Generic types need the instantiation to round-trip test
+//!
+//! ```compile_fail
+//! #[safecast::safecast_roundtrip_test]
+//! #[derive(safecast::Safecast)]
+//! #[repr(C)]
+//! struct Frame<const N: usize> { hdr: u32, payload: [u8; N] }
+//! ```
+//!
+//! Validation witnesses skip every check, thus types whose values are checked
+//! are rejected
+//!
+//! ```compile_fail
+//! fn is_magic(magic: &u32) -> bool { *magic == 0x464c457f }
+//!
+//! #[derive(safecast::Safecast)]
+//! #[repr(C)]
+//! struct Header { #[safecast(validate = is_magic)] magic: u32, len: u32 }
+//!
+//! safecast::Validated::<Header>::check();
+//! ```
+
 #[cfg(test)]
+#[allow(clippy::useless_vec)]
 mod tests {
-    use safecast::Safecast;
+    use safecast::{Safecast, SafecastError, CheckedSafecast};
+    use safecast::safecast_roundtrip_test;
+    use safecast::{IntoBytes, FromBytes, SafecastLayout, Validated};
+    use safecast::endian::{Endian, U16Le, U32Le, U32Be, I64Be};
+    use safecast::net::{Checksum16, Ipv4Addr, U16Be};
+    use safecast::layout::{Layout, FieldLayout, explain_layout};
+    use safecast::layout::compare_layouts;
+    use safecast::hex::Dump;
+    use safecast::diff::{diff, diff_fields};
+    use safecast::reader::{ByteReader, Deframer, Checksummed, peek, take};
+    use safecast::reader::read_array;
+    use safecast::writer::{ByteWriter, write_array};
+    use safecast::records::{RecordHeader, records, TlvHeader, tlvs};
+    use safecast::view::{PodView, PodViewMut};
+    use safecast::mmap;
+    use safecast::io::{ReadPodExt, WritePodExt, StructFile};
+    use safecast::io::{SocketPodExt, UdpPodExt};
+    use safecast::align::{Unaligned, Aligned, AlignedBytes, Align8, Align4096};
+    use std::rc::Rc;
+    use std::sync::Arc;
         
     #[derive(Safecast, Debug, Clone, Copy, PartialEq)]
     #[repr(C)]
     struct Au32(u32);
     
-    #[derive(Safecast, Debug, Clone, Copy, PartialEq)]
+    // The derive rejects padding at compile time, thus this is implemented by
+    // hand to exercise the runtime checks
+    #[derive(Debug, Clone, Copy, PartialEq)]
     #[repr(C)]
     struct Au32Pad(u32, u8);
 
+    unsafe impl Safecast for Au32Pad {
+        fn safecast(&self) {
+            assert!(core::mem::size_of::<Self>() == 5,
+                "Safecast not allowed on structures with padding bytes");
+        }
+    }
+
     #[allow(dead_code)]
     #[derive(Safecast)]
     #[repr(C)]
     struct Moosestruct { a: u32 }
+    
+    #[derive(Safecast, Debug, Clone, Copy, PartialEq)]
+    #[repr(C)]
+    struct Rgba { r: u8, g: u8, b: u8, a: u8 }
+    
+    #[derive(Safecast, Debug, Clone, Copy, PartialEq)]
+    #[repr(C)]
+    struct WireHeader { magic: U32Be, len: U16Le, offset: I64Be }
+    
+    #[derive(Safecast, Debug, Clone, Copy, PartialEq)]
+    #[repr(C)]
+    #[safecast(byteswap)]
+    struct SwapHeader { magic: u32, len: u16, kind: [u8; 2], ts: [u32; 2] }
+    
+    #[derive(Safecast, Debug, Clone, Copy, PartialEq)]
+    #[repr(C)]
+    #[safecast(byteswap)]
+    struct SwapNested(SwapHeader, U32Be);
+    
+    #[derive(Safecast, Debug, Clone, Copy, PartialEq)]
+    #[repr(C)]
+    struct Packed { kind: u16, value: Unaligned<u32>, more: Unaligned<u64> }
+    
+    #[derive(Safecast, Debug, Clone, Copy, PartialEq)]
+    #[repr(C)]
+    #[safecast(bytes)]
+    struct Version { major: u16, minor: u16, build: u32 }
+    
+    #[derive(Safecast, Debug, Clone, Copy, PartialEq)]
+    #[repr(C)]
+    #[safecast(wire)]
+    struct Ipv4Header {
+        ver_ihl:  u8,
+        tos:      u8,
+        length:   U16Be,
+        ident:    U16Be,
+        frag:     U16Be,
+        ttl:      u8,
+        proto:    u8,
+        checksum: Checksum16,
+        src:      Ipv4Addr,
+        dst:      Ipv4Addr,
+    }
+    
+    #[derive(Safecast, Debug, Clone, Copy, PartialEq)]
+    #[repr(C)]
+    struct MixedHeader {
+        #[safecast(endian = "big")]
+        magic: u32,
+        #[safecast(endian = "little")]
+        size: u16,
+        flags: u16,
+    }
 
     #[test]
     fn check_cast_copy() {
@@ -36,12 +353,12 @@ mod tests {
     
     #[test]
     fn check_cast() {
-        assert!([0x41u8; 4].cast::<Au32>() == &[Au32(0x41414141)]);
+        assert!([0x41u8; 4].cast::<Au32>() == [Au32(0x41414141)]);
     }
     
     #[test]
     fn check_cast_multiple() {
-        assert!([0x41u8; 8].cast::<Au32>() == &[Au32(0x41414141); 2]);
+        assert!([0x41u8; 8].cast::<Au32>() == [Au32(0x41414141); 2]);
     }
     
     #[test]
@@ -49,16 +366,18 @@ mod tests {
     fn check_cast_align() {
         let bytes = vec![0x41u8; 32];
         let mut ptr = &bytes[..];
-        while ((ptr.as_ptr() as usize) & 3) == 0 {
+        while (ptr.as_ptr().addr() & 3) == 0 {
             ptr = &ptr[1..];
         }
-        assert!(ptr[..4].cast::<Au32>() == &[Au32(0x41414141)]);
+        assert!(ptr[..4].cast::<Au32>() == [Au32(0x41414141)]);
     }
     
     #[test]
     #[should_panic="cast src cannot be evenly divided by T"]
     fn check_cast_mismatch() {
-        assert!([0x41u8; 3].cast::<Au32>() == &[Au32(0x41414141); 2]);
+        // Source from a `u32` so the cast can't fail on alignment first
+        let val = 0x41414141u32;
+        assert!(val.cast::<u8>()[..3].cast::<Au32>() == [Au32(0x41414141); 2]);
     }
     
     #[test]
@@ -75,5 +394,2556 @@ mod tests {
         val.cast_copy_into(&mut output[..]);
         assert!(output == [0x90; 8]);
     }
-}
+    
+    #[test]
+    fn check_from_bytes() {
+        let val = 0x41414141u32;
+        assert!(Au32::from_bytes(val.cast::<u8>()) == Ok(&Au32(0x41414141)));
+    }
+    
+    #[test]
+    fn check_from_bytes_mut() {
+        let mut val = 0x41414141u32;
+        Au32::from_bytes_mut(val.cast_mut::<u8>()).unwrap().0 = 0x90909090;
+        assert!(val == 0x90909090);
+    }
+    
+    #[test]
+    fn check_from_bytes_size() {
+        let val = 0x41414141u64;
+        assert!(Au32::from_bytes(val.cast::<u8>()) ==
+                Err(SafecastError::SizeMismatch {
+                    src: 8, dest: 4, elem: 4, remainder: 0 }));
+    
+        // Slices of elements report how far they are from a whole element
+        let err = mmap::try_cast_slice::<u32>(&val.cast::<u8>()[..7]);
+        assert!(err.unwrap_err().to_string() == "Size mismatch in cast: 7 \
+            bytes given, 8 bytes required (4 byte elements, 3 bytes remaining)");
+    }
+    
+    #[test]
+    fn check_from_bytes_align() {
+        let val = [0x41414141u32; 2];
+        let bytes = &val.cast::<u8>()[1..5];
+        assert!(Au32::from_bytes(bytes) ==
+                Err(SafecastError::AlignmentMismatch));
+    }
+    
+    #[test]
+    fn check_cast_copy_unaligned() {
+        let bytes = [0x41u8, 0x42, 0x42, 0x42, 0x42, 0x41];
+        assert!(bytes[1..5].cast_copy_unaligned::<Au32>() == Au32(0x42424242));
+    }
+    
+    #[test]
+    #[should_panic="Size mismatch in cast_copy_unaligned"]
+    fn check_cast_copy_unaligned_size() {
+        let bytes = [0x41u8; 6];
+        assert!(bytes[1..].cast_copy_unaligned::<Au32>() == Au32(0x41414141));
+    }
+    
+    #[test]
+    fn check_read_unaligned_from() {
+        let bytes = [0x41u8, 0x42, 0x42, 0x42, 0x42, 0x41];
+        assert!(Au32::read_unaligned_from(&bytes, 1) == Au32(0x42424242));
+        assert!(Au32::read_unaligned_from(&bytes, 2) == Au32(0x41424242));
+    }
+    
+    #[test]
+    #[should_panic="Out of bounds read_unaligned_from"]
+    fn check_read_unaligned_from_bounds() {
+        let bytes = [0x41u8; 6];
+        Au32::read_unaligned_from(&bytes, 3);
+    }
+    
+    #[test]
+    fn check_cast_prefix() {
+        let val = [0x41414141u32, 0x42424242];
+        let (hdr, rest) = val.cast_prefix::<Au32>().unwrap();
+        assert!(hdr == &Au32(0x41414141));
+        assert!(rest == [0x42; 4]);
+    }
+    
+    #[test]
+    fn check_cast_suffix() {
+        let val = [0x41414141u32, 0x42424242];
+        let (rest, tail) = val.cast_suffix::<Au32>().unwrap();
+        assert!(rest == [0x41; 4]);
+        assert!(tail == &Au32(0x42424242));
+    }
+    
+    #[test]
+    fn check_cast_prefix_size() {
+        let val = 0x4141u16;
+        assert!(val.cast_prefix::<Au32>() ==
+                Err(SafecastError::size_mismatch(2, 4, 4)));
+        assert!(val.cast_suffix::<Au32>() ==
+                Err(SafecastError::size_mismatch(2, 4, 4)));
+    }
+    
+    #[test]
+    fn check_cast_ref() {
+        let val = 0x41414141u32;
+        assert!(val.cast::<u8>().cast_ref::<Au32>() == &Au32(0x41414141));
+    }
+    
+    #[test]
+    #[should_panic="Size mismatch in cast_ref"]
+    fn check_cast_ref_size() {
+        let val = 0x41414141_41414141u64;
+        assert!(val.cast_ref::<Au32>() == &Au32(0x41414141));
+    }
+    
+    #[test]
+    fn check_cast_mut_ref() {
+        let mut val = 0x41414141u32;
+        val.cast_mut::<u8>().cast_mut_ref::<Au32>().0 = 0x90909090;
+        assert!(val == 0x90909090);
+    }
+    
+    #[test]
+    #[should_panic="Size mismatch in cast_mut_ref"]
+    fn check_cast_mut_ref_size() {
+        let mut val = 0x41414141_41414141u64;
+        val.cast_mut_ref::<Au32>().0 = 0x90909090;
+    }
+    
+    #[test]
+    fn check_cast_aligned() {
+        let val = [0x41414141u32; 4];
+        let (head, body, tail) = val.cast::<u8>()[1..15].cast_aligned::<Au32>();
+        assert!(head == [0x41; 3]);
+        assert!(body == [Au32(0x41414141); 2]);
+        assert!(tail == [0x41; 3]);
+    }
+    
+    #[test]
+    fn check_cast_aligned_short() {
+        let val = [0x41414141u32; 2];
+        let (head, body, tail) = val.cast::<u8>()[1..6].cast_aligned::<Au32>();
+        assert!(head == [0x41; 3]);
+        assert!(body.is_empty());
+        assert!(tail == [0x41; 2]);
+    }
+    
+    #[test]
+    fn check_read_at() {
+        let bytes = [0x41u8, 0x42, 0x42, 0x42, 0x42, 0x41];
+        assert!(safecast::read_at::<Au32>(&bytes, 1) == Ok(Au32(0x42424242)));
+    }
+    
+    #[test]
+    fn check_read_at_bounds() {
+        let bytes = [0x41u8; 6];
+        assert!(safecast::read_at::<Au32>(&bytes, 3) ==
+                Err(SafecastError::OutOfBounds));
+        assert!(safecast::read_at::<Au32>(&bytes, usize::MAX) ==
+                Err(SafecastError::OutOfBounds));
+    }
+    
+    #[test]
+    fn check_write_at() {
+        let mut bytes = [0x41u8; 6];
+        assert!(safecast::write_at(&mut bytes, 1, &Au32(0x42424242)) == Ok(()));
+        assert!(bytes == [0x41, 0x42, 0x42, 0x42, 0x42, 0x41]);
+    }
+    
+    #[test]
+    fn check_write_at_bounds() {
+        let mut bytes = [0x41u8; 6];
+        assert!(safecast::write_at(&mut bytes, 3, &Au32(0x42424242)) ==
+                Err(SafecastError::OutOfBounds));
+        assert!(bytes == [0x41; 6]);
+    }
+    
+    #[test]
+    fn check_cast_copy_to_vec() {
+        assert!(Au32(0x41414141).cast_copy_to_vec() == vec![0x41u8; 4]);
+    }
+    
+    #[test]
+    fn check_to_boxed_bytes() {
+        assert!(*Au32(0x41414141).to_boxed_bytes() == [0x41u8; 4]);
+    }
+    
+    #[test]
+    fn check_cast_vec() {
+        let mut bytes = Vec::with_capacity(8);
+        bytes.extend_from_slice(&[1u8, 2, 3, 4, 5, 6, 7, 8]);
+        let ptr = bytes.as_ptr();
+
+        let pixels = safecast::cast_vec::<Rgba>(bytes).unwrap();
+        assert!(pixels.as_ptr() as *const u8 == ptr);
+        assert!(pixels == [Rgba { r: 1, g: 2, b: 3, a: 4 },
+                           Rgba { r: 5, g: 6, b: 7, a: 8 }]);
+
+        let bytes = safecast::vec_to_bytes(pixels).unwrap();
+        assert!(bytes.as_ptr() == ptr);
+        assert!(bytes == [1, 2, 3, 4, 5, 6, 7, 8]);
+    }
+    
+    #[test]
+    fn check_cast_vec_errors() {
+        let mut bytes = Vec::with_capacity(8);
+        bytes.extend_from_slice(&[1u8, 2, 3, 4, 5, 6]);
+        let (err, bytes) = safecast::cast_vec::<Rgba>(bytes).unwrap_err();
+        assert!(err == SafecastError::SizeMismatch {
+            src: 6, dest: 8, elem: 4, remainder: 2 });
+
+        let (err, _) = safecast::cast_vec::<Au32>(bytes).unwrap_err();
+        assert!(err == SafecastError::AlignmentMismatch);
+    }
+    
+    fn is_versioned(version: &u8) -> bool {
+        *version != 0
+    }
+    
+    #[derive(Safecast)]
+    #[repr(C)]
+    struct Versioned {
+        #[safecast(validate = is_versioned)]
+        version: u8,
+        data:    [u8; 3],
+    }
+    
+    #[test]
+    #[should_panic="Validation of field `version` failed"]
+    fn check_cast_vec_validates_all() {
+        // Only the second record is invalid
+        let bytes = vec![1u8, 2, 3, 4, 0, 6, 7, 8];
+        let _ = safecast::cast_vec::<Versioned>(bytes);
+    }
+    
+    #[test]
+    #[should_panic="Validation of field `version` failed"]
+    fn check_vec_to_bytes_validates_all() {
+        let records = vec![Versioned { version: 1, data: [2, 3, 4] },
+                             Versioned { version: 0, data: [6, 7, 8] }];
+        let _ = safecast::vec_to_bytes(records);
+    }
+    
+    #[test]
+    fn check_cast_box() {
+        let bytes: Box<[u8]> = vec![1u8, 2, 3, 4].into_boxed_slice();
+        let ptr = bytes.as_ptr();
+
+        let pixel = safecast::cast_box::<Rgba>(bytes).unwrap();
+        assert!(&*pixel as *const Rgba as *const u8 == ptr);
+        assert!(*pixel == Rgba { r: 1, g: 2, b: 3, a: 4 });
+
+        let bytes = safecast::box_to_bytes(pixel).unwrap();
+        assert!(bytes.as_ptr() == ptr);
+        assert!(*bytes == [1, 2, 3, 4]);
+    }
+    
+    #[test]
+    fn check_cast_box_slice() {
+        let bytes: Box<[u8]> = vec![1u8, 2, 3, 4, 5, 6, 7, 8].into_boxed_slice();
+        let ptr = bytes.as_ptr();
+
+        let pixels = safecast::cast_box_slice::<Rgba>(bytes).unwrap();
+        assert!(pixels.as_ptr() as *const u8 == ptr);
+        assert!(*pixels == [Rgba { r: 1, g: 2, b: 3, a: 4 },
+                            Rgba { r: 5, g: 6, b: 7, a: 8 }]);
+
+        let bytes = safecast::box_slice_to_bytes(pixels).unwrap();
+        assert!(bytes.as_ptr() == ptr);
+        assert!(*bytes == [1, 2, 3, 4, 5, 6, 7, 8]);
+    }
+    
+    #[test]
+    #[should_panic="Validation of field `version` failed"]
+    fn check_cast_box_slice_validates_all() {
+        // Only the last record is invalid
+        let bytes: Box<[u8]> =
+            vec![1u8, 2, 3, 4, 5, 6, 7, 8, 0, 1, 2, 3].into_boxed_slice();
+        let _ = safecast::cast_box_slice::<Versioned>(bytes);
+    }
+    
+    #[test]
+    #[should_panic="Validation of field `version` failed"]
+    fn check_box_slice_to_bytes_validates_all() {
+        let records: Box<[Versioned]> =
+            vec![Versioned { version: 1, data: [2, 3, 4] },
+                 Versioned { version: 0, data: [6, 7, 8] }].into_boxed_slice();
+        let _ = safecast::box_slice_to_bytes(records);
+    }
+    
+    #[test]
+    fn check_cast_box_errors() {
+        let bytes: Box<[u8]> = vec![1u8, 2, 3, 4, 5, 6].into_boxed_slice();
+        let (err, bytes) = safecast::cast_box::<Rgba>(bytes).unwrap_err();
+        assert!(err == SafecastError::size_mismatch(6, 4, 4));
+        let (err, bytes) = safecast::cast_box_slice::<Rgba>(bytes).unwrap_err();
+        assert!(err == SafecastError::slice_size_mismatch(6, 4));
+        let (err, _) = safecast::cast_box_slice::<Au32>(bytes).unwrap_err();
+        assert!(err == SafecastError::AlignmentMismatch);
+    }
+    
+    #[test]
+    fn check_rc_bytes() {
+        let pixel = Rc::new(Rgba { r: 1, g: 2, b: 3, a: 4 });
+        let bytes = safecast::rc_as_bytes(&pixel);
+        assert!(bytes.as_ptr() == &*pixel as *const Rgba as *const u8);
+        assert!(*bytes == [1, 2, 3, 4]);
+        assert!(Rc::strong_count(&pixel) == 2);
+
+        let back = safecast::rc_from_bytes::<Rgba>(&bytes).unwrap();
+        assert!(Rc::ptr_eq(&back, &pixel));
+        assert!(Rc::strong_count(&pixel) == 3);
+
+        drop(bytes);
+        drop(back);
+        assert!(Rc::strong_count(&pixel) == 1);
+    }
+    
+    #[test]
+    fn check_arc_bytes() {
+        let pixel = Arc::new(Rgba { r: 1, g: 2, b: 3, a: 4 });
+        let bytes = safecast::arc_as_bytes(&pixel);
+        assert!(bytes.as_ptr() == &*pixel as *const Rgba as *const u8);
+        assert!(Arc::strong_count(&pixel) == 2);
+
+        let back = safecast::arc_from_bytes::<Rgba>(&bytes).unwrap();
+        assert!(Arc::ptr_eq(&back, &pixel));
+    }
+    
+    #[test]
+    fn check_arc_bytes_copy() {
+        let val = Arc::new(Au32(0x41414141));
+        let bytes = safecast::arc_as_bytes(&val);
+        assert!(*bytes == [0x41; 4]);
+        assert!(Arc::strong_count(&val) == 1);
+
+        assert!(*safecast::arc_from_bytes::<Au32>(&bytes).unwrap() ==
+                Au32(0x41414141));
+        assert!(safecast::arc_from_bytes::<u64>(&bytes) ==
+                Err(SafecastError::size_mismatch(4, 8, 8)));
+    }
+    
+    #[test]
+    fn check_zeroed() {
+        assert!(Au32::zeroed() == Au32(0));
+        assert!(<[Rgba; 2]>::zeroed() == [Rgba { r: 0, g: 0, b: 0, a: 0 }; 2]);
+    }
+    
+    #[test]
+    #[should_panic="Safecast not allowed on structures with padding bytes"]
+    fn check_zeroed_padding() {
+        Au32Pad::zeroed();
+    }
+    
+    #[test]
+    fn check_fill_bytes() {
+        let mut val = Au32(0);
+        val.fill_bytes(0x41);
+        assert!(val == Au32(0x41414141));
+
+        let mut vals = vec![0u32; 4];
+        vals[..].fill_bytes(0xcc);
+        assert!(vals == [0xcccccccc; 4]);
+    }
+    
+    #[test]
+    fn check_endian() {
+        let bytes = [0x11u8, 0x22, 0x33, 0x44, 0x04, 0x00,
+                     0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xfe];
+        let mut hdr = bytes.cast_copy::<WireHeader>();
+        assert!(hdr.magic.get() == 0x11223344);
+        assert!(hdr.len.get() == 4);
+        assert!(hdr.offset.get() == -2);
+
+        hdr.magic.set(0xaabbccdd);
+        hdr.len = U16Le::new(0x0102);
+        assert!(hdr.cast::<u8>()[..6] == [0xaa, 0xbb, 0xcc, 0xdd, 0x02, 0x01]);
+        assert!(core::mem::align_of::<WireHeader>() == 1);
+    }
+    
+    #[test]
+    fn check_byteswap() {
+        let hdr = SwapHeader {
+            magic: 0x11223344, len: 0x5566, kind: [1, 2], ts: [0x778899aa, 4],
+        };
+        let swapped = SwapHeader {
+            magic: 0x44332211, len: 0x6655, kind: [1, 2],
+            ts: [0xaa998877, 0x04000000],
+        };
+        assert!(Endian::swap_bytes(&hdr) == swapped);
+
+        let be = hdr.to_be();
+        assert!(be.cast::<u8>()[..8] ==
+                [0x11, 0x22, 0x33, 0x44, 0x55, 0x66, 1, 2]);
+        assert!(Endian::from_be(&be) == hdr);
+
+        let le = hdr.to_le();
+        assert!(le.cast::<u8>()[..8] ==
+                [0x44, 0x33, 0x22, 0x11, 0x66, 0x55, 1, 2]);
+        assert!(Endian::from_le(&le) == hdr);
+    }
+    
+    #[test]
+    fn check_byteswap_nested() {
+        let val = SwapNested(SwapHeader {
+            magic: 0x11223344, len: 0x5566, kind: [1, 2], ts: [3, 4],
+        }, U32Be::new(0xaabbccdd));
+
+        // Fixed endianness fields are left untouched
+        let be = val.to_be();
+        assert!(be.0 == val.0.to_be());
+        assert!(be.1 == val.1);
+    }
+    
+    #[test]
+    fn check_cast_copy_endian() {
+        let bytes = [0x11u8, 0x22, 0x33, 0x44, 0x00, 0x10, 1, 2,
+                     0, 0, 0, 3, 0, 0, 0, 4];
+        let hdr = bytes.cast_copy_be::<SwapHeader>();
+        assert!(hdr == SwapHeader {
+            magic: 0x11223344, len: 0x10, kind: [1, 2], ts: [3, 4],
+        });
+
+        let hdr = bytes.cast_copy_le::<SwapHeader>();
+        assert!(hdr == SwapHeader {
+            magic: 0x44332211, len: 0x1000, kind: [1, 2],
+            ts: [0x03000000, 0x04000000],
+        });
+    }
+    
+    #[test]
+    fn check_field_endian() {
+        let bytes = [0x11u8, 0x22, 0x33, 0x44, 0x10, 0x00, 0x01, 0x02];
+        let mut hdr = bytes.cast_copy::<MixedHeader>();
+        assert!(hdr.magic() == 0x11223344);
+        assert!(hdr.size() == 0x10);
+
+        hdr.set_magic(0xaabbccdd);
+        hdr.set_size(0x1234);
+        assert!(hdr.cast::<u8>() ==
+                [0xaa, 0xbb, 0xcc, 0xdd, 0x34, 0x12, 0x01, 0x02]);
+    }
+    
+    #[test]
+    fn check_net_header() {
+        let bytes = [0x45u8, 0x00, 0x00, 0x73, 0x00, 0x00, 0x40, 0x00,
+                     0x40, 0x11, 0xb8, 0x61, 0xc0, 0xa8, 0x00, 0x01,
+                     0xc0, 0xa8, 0x00, 0xc7];
+        let mut hdr = bytes.cast_copy::<Ipv4Header>();
+        assert!(hdr.length.get() == 0x73);
+        assert!(hdr.checksum.get() == 0xb861);
+        assert!(core::net::Ipv4Addr::from(hdr.src) ==
+                core::net::Ipv4Addr::new(192, 168, 0, 1));
+        assert!(Checksum16::verify(&bytes));
+
+        hdr.checksum = Checksum16::new(0);
+        assert!(Checksum16::compute(hdr.cast::<u8>()).get() == 0xb861);
+    }
+    
+    #[test]
+    fn check_checksum_odd() {
+        assert!(Checksum16::compute(&[0x01, 0x02, 0x03]).get() == !0x0402);
+    }
+    
+    #[test]
+    fn check_swap_bytes_in_place() {
+        let mut samples = vec![0x1122u16, 0x3344, 0x5566];
+        safecast::endian::swap_bytes_in_place(&mut samples);
+        assert!(samples == [0x2211, 0x4433, 0x6655]);
+
+        let mut wire = [U32Be::new(0x11223344); 2];
+        safecast::endian::swap_bytes_in_place(&mut wire);
+        assert!(wire == [U32Be::new(0x44332211); 2]);
+    }
+    
+    #[test]
+    fn check_struct_bytes() {
+        let ver = Version { major: 1, minor: 2, build: 0x11223344 };
+        let be = [0u8, 1, 0, 2, 0x11, 0x22, 0x33, 0x44];
+        let le = [1u8, 0, 2, 0, 0x44, 0x33, 0x22, 0x11];
+
+        assert!(ver.to_be_bytes() == be);
+        assert!(ver.to_le_bytes() == le);
+        assert!(Version::from_be_bytes(be) == ver);
+        assert!(Version::from_le_bytes(le) == ver);
+    }
+    
+    #[test]
+    fn check_unaligned() {
+        assert!(core::mem::size_of::<Packed>() == 14);
+        assert!(core::mem::align_of::<Packed>() == 2);
+
+        let mut bytes = [0x41u8; 14];
+        bytes[2..6].copy_from_slice(&0x11223344u32.to_ne_bytes());
+        let mut pkt = bytes.cast_copy::<Packed>();
+        assert!(pkt.value.get() == 0x11223344);
+
+        pkt.more.set(0x90);
+        assert!(pkt.more == Unaligned::new(0x90));
+    }
+    
+    #[test]
+    #[should_panic="Safecast not allowed on structures with padding bytes"]
+    fn check_unaligned_padding() {
+        Unaligned::<Au32Pad>::zeroed();
+    }
+    
+    #[test]
+    fn check_aligned_bytes() {
+        let mut page = AlignedBytes::<4096, Align4096>::new();
+        assert!(page.as_ptr().addr().is_multiple_of(4096));
+        page[..4].copy_from_slice(&[0x41; 4]);
+        assert!(page.cast::<Au32>()[0] == Au32(0x41414141));
 
+        let buf = AlignedBytes::<32>::new();
+        assert!(core::mem::align_of_val(&buf) == 16);
+        assert!(buf.cast::<u128>() == [0, 0]);
+    }
+    
+    #[test]
+    fn check_aligned() {
+        let val = Aligned::<Align8, [u8; 8]>::new([0x41; 8]);
+        assert!((&*val as *const [u8; 8]).addr().is_multiple_of(8));
+        assert!(val.cast::<u64>() == [0x4141414141414141]);
+    }
+    
+    #[test]
+    #[should_panic="Safecast not allowed on structures with padding bytes"]
+    fn check_aligned_padding() {
+        let val = Aligned::<Align8, [u8; 4]>::new([0x41; 4]);
+        val.cast::<u8>();
+    }
+    
+    #[test]
+    fn check_large_array() {
+        // Arrays are no longer limited to 256 elements
+        let mut page = AlignedBytes::<4096, Align4096>::new();
+        page[4092..].copy_from_slice(&[0x41; 4]);
+        let arr: &[u8; 4096] = &page;
+        assert!(arr.cast_copy::<[u32; 1024]>()[1023] == 0x41414141);
+    }
+    
+    #[derive(Safecast, Clone, Copy, Default, PartialEq, Debug)]
+    #[repr(C)]
+    #[safecast(byteswap)]
+    struct Lfsr {
+        state: std::num::Wrapping<u32>,
+        taps:  std::num::Wrapping<u32>,
+    }
+    
+    #[test]
+    fn check_wrapping() {
+        let lfsr = Lfsr {
+            state: std::num::Wrapping(0x11223344),
+            taps:  std::num::Wrapping(0x80200003),
+        };
+        let bytes = lfsr.cast_copy_to_vec();
+        assert!(bytes.cast_copy::<Lfsr>() == lfsr);
+        assert!(lfsr.swap_bytes().state == std::num::Wrapping(0x44332211));
+    }
+    
+    #[derive(Safecast, Clone, Copy, PartialEq, Debug)]
+    #[repr(C)]
+    #[safecast(byteswap)]
+    struct HandleEntry {
+        handle: Option<std::num::NonZeroU32>,
+        parent: Option<std::num::NonZeroU32>,
+    }
+    
+    #[test]
+    fn check_option_nonzero() {
+        let entries = [1u32, 0];
+        let entry = entries.cast_copy::<HandleEntry>();
+        assert!(entry.handle == std::num::NonZeroU32::new(1));
+        assert!(entry.parent.is_none());
+    
+        let swapped = entry.swap_bytes();
+        assert!(swapped.handle == std::num::NonZeroU32::new(0x01000000));
+        assert!(swapped.parent.is_none());
+        assert!(swapped.cast_copy::<[u32; 2]>() == [0x01000000, 0]);
+    }
+    
+    #[derive(Safecast)]
+    #[repr(C)]
+    struct PointerEntry {
+        object: Option<std::ptr::NonNull<u32>>,
+        parent: Option<std::ptr::NonNull<u32>>,
+    }
+    
+    #[test]
+    fn check_option_nonnull() {
+        let entries = [0x1000usize, 0];
+        let entry = entries.cast_copy::<PointerEntry>();
+        assert!(entry.object.unwrap().as_ptr().addr() == 0x1000);
+        assert!(entry.parent.is_none());
+        assert!(entry.cast_copy::<[usize; 2]>() == entries);
+    
+        // The pointers themselves must be non-null
+        let ptr = std::ptr::NonNull::<u32>::try_read_from(
+            &0x1000usize.to_ne_bytes()).unwrap();
+        assert!(ptr.as_ptr().addr() == 0x1000);
+        assert!(matches!(std::ptr::NonNull::<u32>::try_read_from(
+            &0usize.to_ne_bytes()), Err(SafecastError::InvalidValue)));
+    }
+    
+    #[derive(Safecast, Default)]
+    #[repr(C)]
+    struct ControlBlock {
+        head: std::sync::atomic::AtomicU32,
+        tail: std::sync::atomic::AtomicU32,
+    }
+    
+    #[test]
+    fn check_atomic() {
+        use std::sync::atomic::Ordering;
+    
+        let mut region = [0u32; 2];
+        let block = ControlBlock::from_bytes_mut(region.cast_mut()).unwrap();
+        block.head.fetch_add(5, Ordering::SeqCst);
+        block.tail.store(3, Ordering::SeqCst);
+        assert!(region == [5, 3]);
+    
+        let mut block = ControlBlock::default();
+        block.cast_mut::<u32>()[1] = 7;
+        assert!(block.tail.load(Ordering::SeqCst) == 7);
+    }
+    
+    #[test]
+    #[should_panic="Shared cast of interior mutable type"]
+    fn check_atomic_shared() {
+        let region = [0u32; 2];
+        let _ = ControlBlock::from_bytes(region.cast());
+    }
+    
+    #[test]
+    fn check_cast_copy_into_uninit() {
+        let bytes = [0x41u8, 0x41, 0x41, 0x41, 0x42, 0x42, 0x42, 0x42];
+        let mut dest = std::mem::MaybeUninit::<[Au32; 2]>::uninit();
+        let casted = bytes.cast_copy_into_uninit(&mut dest);
+        assert!(casted == &[Au32(0x41414141), Au32(0x42424242)]);
+    }
+    
+    #[test]
+    #[should_panic="Size mismatch in cast_copy_into_uninit"]
+    fn check_cast_copy_into_uninit_mismatch() {
+        let mut dest = std::mem::MaybeUninit::<u64>::uninit();
+        [0u8; 4].cast_copy_into_uninit(&mut dest);
+    }
+    
+    #[derive(Safecast)]
+    #[repr(C)]
+    struct Intrusive {
+        next:  u32,
+        inner: std::mem::ManuallyDrop<Au32>,
+    }
+    
+    #[test]
+    fn check_manually_drop() {
+        let bytes = [1u32, 0x41414141];
+        let node = Intrusive::from_bytes(bytes.cast()).unwrap();
+        assert!(node.next == 1);
+        assert!(*node.inner == Au32(0x41414141));
+    }
+    
+    #[test]
+    #[should_panic="Safecast not allowed on structures with padding bytes"]
+    fn check_manually_drop_padding() {
+        let val = std::mem::ManuallyDrop::new(Au32Pad(0, 0));
+        val.cast::<u8>();
+    }
+    
+    #[derive(Safecast, Clone, Copy, PartialEq, Debug)]
+    #[repr(C)]
+    #[safecast(byteswap)]
+    struct Handle<T> {
+        id:      U32Be,
+        _marker: std::marker::PhantomData<T>,
+    }
+    
+    #[derive(Safecast)]
+    #[repr(C)]
+    struct Table<'a, T: Copy, const N: usize> {
+        entries: [T; N],
+        _marker: std::marker::PhantomData<&'a T>,
+    }
+    
+    #[test]
+    fn check_phantom_data() {
+        let bytes = [0u8, 0, 0, 5];
+        let handle = Handle::<String>::from_bytes(&bytes).unwrap();
+        assert!(handle.id.get() == 5);
+        assert!(handle.swap_bytes().id.get() == 0x05000000);
+    
+        let entries = [1u32, 2, 3];
+        let table = Table::<u32, 3>::from_bytes(entries.cast()).unwrap();
+        assert!(table.entries == [1, 2, 3]);
+    }
+    
+    #[test]
+    #[cfg(target_arch = "x86_64")]
+    fn check_simd() {
+        use std::arch::x86_64::{__m128i, _mm_cmpeq_epi8, _mm_movemask_epi8};
+        use safecast::align::Align16;
+    
+        let mut buf = AlignedBytes::<32, Align16>::new();
+        buf[3] = 0x41;
+        buf[16 + 9] = 0x41;
+    
+        // Scan for bytes equal to 0x41
+        let needle = [0x41u8; 16].cast_copy::<__m128i>();
+        let masks: Vec<i32> = buf.cast::<__m128i>().iter().map(|&x| unsafe {
+            _mm_movemask_epi8(_mm_cmpeq_epi8(x, needle))
+        }).collect();
+        assert!(masks == [1 << 3, 1 << 9]);
+    }
+    
+    #[derive(Debug, PartialEq)]
+    #[repr(transparent)]
+    struct Flag(u8);
+    
+    unsafe impl CheckedSafecast for Flag {
+        fn is_valid_bytes(bytes: &[u8]) -> bool {
+            assert!(bytes.len() == 1);
+            bytes[0] <= 1
+        }
+    }
+    
+    #[test]
+    fn check_checked_safecast() {
+        // Custom validation
+        assert!(Flag::try_from_bytes(&[1]) == Ok(&Flag(1)));
+        assert!(Flag::try_from_bytes(&[2]) == Err(SafecastError::InvalidValue));
+        assert!(Flag::try_read_from(&[0]) == Ok(Flag(0)));
+        assert!(Flag::try_read_from(&[0, 0]) ==
+                Err(SafecastError::size_mismatch(2, 1, 1)));
+    
+        let mut byte = [0u8];
+        *Flag::try_from_bytes_mut(&mut byte).unwrap() = Flag(1);
+        assert!(byte == [1]);
+    
+        // Every `Safecast` type is valid
+        let val = 0x41414141u32;
+        assert!(<u32 as CheckedSafecast>::try_from_bytes(val.cast()) ==
+                Ok(&0x41414141));
+        assert!(Au32::try_read_from(&[0x41; 4][..]) == Ok(Au32(0x41414141)));
+        assert!(<u32 as CheckedSafecast>::try_from_bytes(&val.cast()[1..]) ==
+                Err(SafecastError::size_mismatch(3, 4, 4)));
+    }
+    
+    #[test]
+    #[should_panic="Safecast not allowed on structures with padding bytes"]
+    fn check_checked_safecast_padding() {
+        let _ = Au32Pad::try_read_from(&[0; 8]);
+    }
+    
+    #[test]
+    fn check_into_from_bytes() {
+        // Checked types can be converted to bytes, but not created from them
+        // without validation
+        assert!(Flag(1).as_bytes() == [1]);
+        let mut dest = [0u8; 1];
+        assert!(Flag(1).write_to(&mut dest) == Ok(()));
+        assert!(dest == [1]);
+    
+        // `Safecast` types can go both ways
+        assert!(Au32::read_from_bytes(&[0x41; 4]) == Ok(Au32(0x41414141)));
+        assert!(Au32::read_from_bytes(&[0x41; 5]) ==
+                Err(SafecastError::size_mismatch(5, 4, 4)));
+        assert!(Au32::new_zeroed() == Au32(0));
+    
+        let mut val = Au32(0);
+        val.as_bytes_mut()[0] = 0x41;
+        assert!(val.as_bytes() == [0x41, 0, 0, 0]);
+    }
+    
+    #[test]
+    #[should_panic="Safecast not allowed on structures with padding bytes"]
+    fn check_into_bytes_padding() {
+        Au32Pad(0, 0).as_bytes();
+    }
+    
+    #[derive(CheckedSafecast, Debug, PartialEq)]
+    #[repr(C)]
+    struct Options {
+        flags: [[Flag; 2]; 2],
+        value: U16Le,
+    }
+    
+    #[test]
+    fn check_derive_checked_safecast() {
+        let bytes = [1u8, 0, 0, 1, 0x34, 0x12];
+        let opts = Options::try_read_from(&bytes).unwrap();
+        assert!(opts.flags == [[Flag(1), Flag(0)], [Flag(0), Flag(1)]]);
+        assert!(opts.value.get() == 0x1234);
+        assert!(opts.as_bytes() == bytes);
+    
+        // Any invalid element of the flags is rejected
+        for ii in 0..4 {
+            let mut bad = bytes;
+            bad[ii] = 2;
+            assert!(Options::try_read_from(&bad) ==
+                    Err(SafecastError::InvalidValue));
+        }
+    }
+    
+    #[derive(CheckedSafecast, Debug, PartialEq)]
+    #[repr(C)]
+    struct ProtocolFlags {
+        syn:   bool,
+        ack:   bool,
+        other: [bool; 2],
+    }
+    
+    #[test]
+    fn check_bool() {
+        assert!(bool::try_read_from(&[0]) == Ok(false));
+        assert!(bool::try_read_from(&[1]) == Ok(true));
+        assert!(bool::try_read_from(&[2]) == Err(SafecastError::InvalidValue));
+        assert!(true.as_bytes() == [1]);
+    
+        let flags = ProtocolFlags::try_from_bytes(&[1, 0, 0, 1]).unwrap();
+        assert!(flags.syn && !flags.ack && flags.other == [false, true]);
+        assert!(ProtocolFlags::try_from_bytes(&[1, 0, 0xff, 1]).unwrap_err() ==
+                SafecastError::InvalidValue);
+    }
+    
+    #[derive(CheckedSafecast)]
+    #[repr(C)]
+    struct TextRecord {
+        id:   u32,
+        name: [char; 4],
+    }
+    
+    #[test]
+    fn check_char() {
+        assert!(char::try_read_from(&0x41u32.to_ne_bytes()) == Ok('A'));
+        assert!(char::try_read_from(&0x1f980u32.to_ne_bytes()) == Ok('\u{1f980}'));
+        assert!(char::try_read_from(&0xd800u32.to_ne_bytes()) ==
+                Err(SafecastError::InvalidValue));
+        assert!(char::try_read_from(&0x110000u32.to_ne_bytes()) ==
+                Err(SafecastError::InvalidValue));
+        assert!('A'.as_bytes() == 0x41u32.to_ne_bytes());
+    
+        let mut words = [7u32, 'm' as u32, 'o' as u32, 'o' as u32, 's' as u32];
+        let record = TextRecord::try_from_bytes(words.cast()).unwrap();
+        assert!(record.id == 7 && record.name == ['m', 'o', 'o', 's']);
+    
+        words[4] = 0xdfff;
+        assert!(TextRecord::try_from_bytes(words.cast()).is_err());
+    }
+    
+    #[derive(CheckedSafecast, Clone, Copy, Debug, PartialEq)]
+    #[repr(u8)]
+    enum MessageType {
+        /// Keepalive request
+        Ping = 1,
+        Pong,
+        #[allow(dead_code)]
+        Data = 0x10,
+    }
+    
+    #[derive(CheckedSafecast, Debug, PartialEq)]
+    #[repr(u32)]
+    #[allow(dead_code)]
+    enum Status {
+        Ok,
+        Error = 0xdeadbeef,
+    }
+    
+    #[derive(CheckedSafecast)]
+    #[repr(C)]
+    struct Message {
+        kind: MessageType,
+        len:  U16Le,
+    }
+    
+    #[test]
+    fn check_fieldless_enum() {
+        assert!(MessageType::try_read_from(&[1]) == Ok(MessageType::Ping));
+        assert!(MessageType::try_read_from(&[2]) == Ok(MessageType::Pong));
+        assert!(MessageType::try_read_from(&[3]) ==
+                Err(SafecastError::InvalidValue));
+        assert!(MessageType::Pong.as_bytes() == [2]);
+    
+        assert!(Status::try_read_from(&0xdeadbeefu32.to_ne_bytes()) ==
+                Ok(Status::Error));
+        assert!(Status::try_read_from(&1u32.to_ne_bytes()) ==
+                Err(SafecastError::InvalidValue));
+    
+        let msg = Message::try_read_from(&[2, 5, 0]).unwrap();
+        assert!(msg.kind == MessageType::Pong && msg.len.get() == 5);
+        assert!(Message::try_read_from(&[0, 5, 0]).is_err());
+    }
+    
+    #[derive(CheckedSafecast, Debug, PartialEq)]
+    #[repr(C, u8)]
+    enum Packet {
+        Ping([u8; 3]),
+        Data { kind: MessageType, len: U16Le },
+        Ack(bool, [u8; 2]) = 7,
+    }
+    
+    #[test]
+    fn check_tagged_union() {
+        assert!(Packet::try_read_from(&[0, 1, 2, 3]) ==
+                Ok(Packet::Ping([1, 2, 3])));
+        assert!(Packet::try_read_from(&[1, 2, 0x34, 0x12]) ==
+                Ok(Packet::Data { kind: MessageType::Pong,
+                                  len: U16Le::new(0x1234) }));
+        assert!(Packet::try_read_from(&[7, 1, 0, 0]) ==
+                Ok(Packet::Ack(true, [0, 0])));
+        assert!(Packet::Ack(false, [9, 9]).as_bytes() == [7, 0, 9, 9]);
+    
+        // Invalid tags
+        assert!(Packet::try_read_from(&[2, 0, 0, 0]) ==
+                Err(SafecastError::InvalidValue));
+        assert!(Packet::try_read_from(&[8, 0, 0, 0]) ==
+                Err(SafecastError::InvalidValue));
+    
+        // Invalid payloads for the active variant
+        assert!(Packet::try_read_from(&[1, 0, 0, 0]) ==
+                Err(SafecastError::InvalidValue));
+        assert!(Packet::try_read_from(&[7, 2, 0, 0]) ==
+                Err(SafecastError::InvalidValue));
+    }
+    
+    #[derive(Safecast, Clone, Copy)]
+    #[repr(C)]
+    #[safecast(size = 8, assert_align = 8)]
+    union Descriptor {
+        raw:   u64,
+        words: [u32; 2],
+        halves: [U16Be; 4],
+    }
+    
+    #[test]
+    fn check_union() {
+        let bytes = [0x41u8; 8];
+        let desc = bytes.cast_copy::<Descriptor>();
+        assert!(unsafe { desc.raw } == 0x4141414141414141);
+        assert!(unsafe { desc.words } == [0x41414141; 2]);
+        assert!(unsafe { desc.halves }[3].get() == 0x4141);
+        assert!(desc.cast::<u8>() == bytes);
+    }
+    
+    fn is_elf_magic(magic: &[u8; 4]) -> bool {
+        magic == b"\x7fELF"
+    }
+    
+    fn is_small_len(len: &U16Le) -> bool {
+        len.get() <= 0x100
+    }
+    
+    fn is_small_raw_len(len: &u16) -> bool {
+        u16::from_le(*len) <= 0x100
+    }
+    
+    #[derive(Safecast)]
+    #[repr(C)]
+    struct ValidatedHeader {
+        #[safecast(validate = is_elf_magic)]
+        magic: [u8; 4],
+        #[safecast(endian = "little", validate = is_small_raw_len)]
+        len:   u16,
+        kind:  u16,
+    }
+    
+    #[derive(CheckedSafecast)]
+    #[repr(C)]
+    struct CheckedHeader {
+        #[safecast(validate = is_elf_magic)]
+        magic: [u8; 4],
+        present: bool,
+        pad:     [u8; 1],
+        #[safecast(validate = is_small_len)]
+        len:     U16Le,
+    }
+    
+    #[test]
+    fn check_validate() {
+        let bytes = *b"\x7fELF\x00\x01\x02\x00";
+        let hdr = ValidatedHeader::from_bytes(&bytes).unwrap();
+        assert!(hdr.len() == 0x100);
+    
+        let bytes = *b"\x7fELF\x01\x00\x00\x01";
+        let hdr = CheckedHeader::try_read_from(&bytes).unwrap();
+        assert!(hdr.present && hdr.len.get() == 0x100);
+    
+        // Fails the length check
+        let bytes = *b"\x7fELF\x01\x00\x01\x01";
+        assert!(matches!(CheckedHeader::try_read_from(&bytes),
+                         Err(SafecastError::InvalidValue)));
+    
+        // Fails the magic check
+        let bytes = *b"\x7fELG\x01\x00\x00\x01";
+        assert!(CheckedHeader::try_read_from(&bytes).is_err());
+    }
+    
+    #[test]
+    #[should_panic="Validation of field `len` failed"]
+    fn check_validate_safecast() {
+        let bytes = *b"\x7fELF\x01\x01\x02\x00";
+        let _ = ValidatedHeader::from_bytes(&bytes);
+    }
+    
+    #[test]
+    fn check_validate_elements() {
+        // The magic rejects zero, thus the real elements must be validated
+        // rather than a zeroed one
+        let words = [u64::from_le_bytes(*b"\x7fELF\x00\x01\x02\x00"); 2];
+        let hdr = words[0].cast_ref::<ValidatedHeader>();
+        assert!(hdr.len() == 0x100);
+        assert!(words.cast::<ValidatedHeader>().len() == 2);
+        let hdrs = words.cast_copy::<[ValidatedHeader; 2]>();
+        assert!(hdrs[1].len() == 0x100);
+    }
+    
+    #[test]
+    #[should_panic="Validation of field `magic` failed"]
+    fn check_validate_elements_invalid() {
+        // Invalid data in any element of a slice is rejected
+        let words = [u64::from_le_bytes(*b"\x7fELF\x00\x01\x02\x00"),
+                     u64::from_le_bytes(*b"\x7fELG\x00\x01\x02\x00")];
+        let _ = words.cast::<ValidatedHeader>();
+    }
+    
+    #[derive(CheckedSafecast, Debug)]
+    #[repr(C)]
+    struct KernelObject {
+        handle: std::num::NonZeroU32,
+        parent: Option<std::num::NonZeroU32>,
+        owner:  [Option<std::num::NonZeroU16>; 2],
+    }
+    
+    #[test]
+    fn check_nonzero() {
+        use std::num::{NonZeroU16, NonZeroU32, NonZeroU64};
+    
+        assert!(NonZeroU64::try_read_from(&[0, 0, 0, 0, 0, 0, 0, 1]).is_ok());
+        assert!(NonZeroU64::try_read_from(&[0; 8]) ==
+                Err(SafecastError::InvalidValue));
+    
+        let words = [7u32, 0, 0x00050000];
+        let obj = KernelObject::try_from_bytes(words.cast()).unwrap();
+        assert!(obj.handle == NonZeroU32::new(7).unwrap());
+        assert!(obj.parent.is_none());
+        assert!(obj.owner.contains(&NonZeroU16::new(5)));
+        assert!(obj.owner.contains(&None));
+    
+        // The handle must be present
+        let words = [0u32, 1, 1];
+        assert!(KernelObject::try_from_bytes(words.cast()).is_err());
+    }
+    
+    #[derive(Safecast, Clone, Copy)]
+    #[repr(C)]
+    pub struct EthernetHeader {
+        pub dst:   [u8; 6],
+        pub(crate) src: [u8; 6],
+        pub(super) ethertype: U16Be,
+        pub payload: [[u8; 2]; 2],
+        pub aligned: safecast::align::Aligned<safecast::align::Align1, [u8; 4]>,
+    }
+    
+    #[derive(Safecast, Clone, Copy)]
+    #[repr(C)]
+    pub struct MacPair(pub [u8; 6], pub(crate) [u8; 6], [U16Be; 2]);
+    
+    #[test]
+    fn check_array_fields() {
+        let bytes: Vec<u8> = (0..22).collect();
+        let hdr = EthernetHeader::from_bytes(&bytes[..]).unwrap();
+        assert!(hdr.dst == [0, 1, 2, 3, 4, 5]);
+        assert!(hdr.src == [6, 7, 8, 9, 10, 11]);
+        assert!(hdr.ethertype.get() == 0x0c0d);
+        assert!(hdr.payload == [[14, 15], [16, 17]]);
+        assert!(*hdr.aligned == [18, 19, 20, 21]);
+    
+        let pair = MacPair::from_bytes(&bytes[..16]).unwrap();
+        assert!(pair.0 == [0, 1, 2, 3, 4, 5] && pair.1[5] == 11);
+        assert!(pair.2[1].get() == 0x0e0f);
+    }
+    
+    /* Attribute order, comments, and formatting must not matter */
+    #[derive(Clone, Copy)]
+    #[repr(C)]
+    #[derive(Safecast)]
+    pub struct Unusual
+    {
+        #[allow(dead_code)] /* before */ #[safecast(endian = "big")]
+        r#type : u32,
+        nested
+            : Aligned<safecast::align::Align1,
+                      Aligned<safecast::align::Align4, [u8; 4]>>,
+        pub(in crate::tests) tail: [
+            U16Be; 2 // trailing comment
+        ],
+    }
+    
+    #[derive(CheckedSafecast, Clone, Copy, PartialEq, Debug)]
+    #[repr(u8)]
+    pub enum Spaced {
+        /// Documented
+        A = 1 << 1,
+        #[allow(dead_code)]
+        B /* comment */ = 0x20 >> 1,
+        C,
+    }
+    
+    #[test]
+    fn check_token_parsing() {
+        let mut val = Unusual {
+            r#type: 0,
+            nested: Aligned::new(Aligned::new([1; 4])),
+            tail:   [U16Be::new(5); 2],
+        };
+        val.set_type(0x11223344);
+        assert!(val.r#type() == 0x11223344);
+        assert!(val.cast::<u8>()[..4] == [0x11, 0x22, 0x33, 0x44]);
+        assert!(core::mem::size_of::<Unusual>() == 12);
+    
+        assert!(Spaced::try_from_bytes(&[2]) == Ok(&Spaced::A));
+        assert!(Spaced::try_from_bytes(&[0x10]) == Ok(&Spaced::B));
+        assert!(Spaced::try_from_bytes(&[0x11]) == Ok(&Spaced::C));
+        assert!(Spaced::try_from_bytes(&[1]).is_err());
+    }
+    
+    #[derive(Safecast, Clone, Copy, PartialEq, Debug)]
+    #[repr(C)]
+    #[safecast(byteswap)]
+    struct Pair<T: Safecast> {
+        a: T,
+        b: T,
+    }
+    
+    #[derive(Safecast, Clone, Copy)]
+    #[repr(C)]
+    struct Tagged<T, const N: usize>(T, [T; N]) where T: Copy + Default;
+    
+    #[derive(CheckedSafecast, Clone, Copy)]
+    #[repr(C)]
+    struct Checked<T>
+    where
+        T: Copy,
+    {
+        flag:  bool,
+        value: T,
+    }
+    
+    #[test]
+    fn check_generic_structs() {
+        let pair = Pair { a: 0x1122u16, b: 0x3344u16 };
+        assert!(pair.swap_bytes() == Pair { a: 0x2211, b: 0x4433 });
+        assert!(Pair::<u32>::from_bytes(&[0u8; 8]).is_ok());
+    
+        let tagged = Tagged::<u8, 3>::from_bytes(&[1, 2, 3, 4]).unwrap();
+        assert!(tagged.0 == 1 && tagged.1 == [2, 3, 4]);
+        assert!(Tagged::<u8, 3>::is_valid_bytes(&[1, 2, 3, 4]));
+    
+        assert!(Checked::<u8>::try_from_bytes(&[1, 7]).unwrap().value == 7);
+        assert!(Checked::<u8>::try_from_bytes(&[2, 7]).is_err());
+    }
+    
+    #[derive(Safecast, Clone, Copy)]
+    #[repr(C)]
+    struct Frame<const N: usize> {
+        hdr:     u32,
+        payload: [u8; N],
+    }
+    
+    #[derive(CheckedSafecast, Clone, Copy)]
+    #[repr(C)]
+    struct FlagFrame<const N: usize = 4> {
+        len:   u8,
+        flags: [bool; N],
+    }
+    
+    #[test]
+    fn check_const_generic_structs() {
+        let frame = Frame::<4> { hdr: 0, payload: [0x41; 4] };
+        assert!(frame.cast::<u8>()[4..] == [0x41; 4]);
+        assert!(Frame::<12>::from_bytes([0u32; 4].cast()).is_ok());
+    
+        assert!(FlagFrame::<3>::try_from_bytes(&[3, 1, 0, 1]).is_ok());
+        assert!(FlagFrame::<3>::try_from_bytes(&[3, 1, 2, 1]).is_err());
+        assert!(<FlagFrame>::try_from_bytes(&[4, 0, 0, 0, 1]).is_ok());
+    }
+    
+    #[derive(Safecast, Clone, Copy)]
+    #[repr(C)]
+    #[safecast(byteswap)]
+    struct Configured {
+        /// Documented
+        #[doc = "Also documented"]
+        a: u32,
+    
+        // Never compiled in, this would add padding
+        #[cfg(any())]
+        b: u64,
+    
+        #[cfg(test)]
+        #[safecast(endian = "big")]
+        c: u32,
+    }
+    
+    #[derive(CheckedSafecast, Clone, Copy)]
+    #[repr(C)]
+    struct ConfiguredFlags {
+        #[cfg(test)]
+        a: bool,
+        #[cfg(any())]
+        b: u32,
+        #[cfg_attr(any(), allow(dead_code))]
+        c: bool,
+    }
+    
+    #[test]
+    fn check_cfg_fields() {
+        let mut val = Configured { a: 1, c: 0 };
+        val.set_c(0x11223344);
+        assert!(val.c() == 0x11223344);
+        assert!(val.cast::<u8>()[4..] == [0x11, 0x22, 0x33, 0x44]);
+        assert!(val.swap_bytes().a == 0x01000000);
+    
+        assert!(ConfiguredFlags::try_from_bytes(&[1, 0]).is_ok());
+        assert!(ConfiguredFlags::try_from_bytes(&[1, 2]).is_err());
+    }
+    
+    mod wire {
+        pub type BigU32 = safecast::endian::U32Be;
+    }
+    
+    pub trait Identity { type Type; }
+    impl<T> Identity for T { type Type = T; }
+    
+    #[derive(Safecast, Clone, Copy)]
+    #[repr(C)]
+    #[safecast(byteswap)]
+    struct Paths {
+        a: crate::tests::wire::BigU32,
+        b: ::core::num::Wrapping<u32>,
+        c: <u32 as Identity>::Type,
+        d: <<u32 as Identity>::Type as Identity>::Type,
+        e: [std::num::Wrapping<u16>; 2],
+        f: Option<core::num::NonZeroU32>,
+        g: core::primitive::u32,
+    }
+    
+    #[test]
+    fn check_type_paths() {
+        let mut bytes = [0u32; 7];
+        bytes[0] = 5u32.to_be();
+        bytes[1] = 6;
+        bytes[5] = 7;
+        let val = Paths::from_bytes(bytes.cast()).unwrap();
+        assert!(val.a.get() == 5 && val.b.0 == 6 && val.c == 0 &&
+                val.d == 0 && val.e[0].0 == 0);
+        assert!(val.f.map(|x| x.get()) == Some(7) && val.g == 0);
+        assert!(val.swap_bytes().a.get() == 0x05000000);
+        assert!(val.swap_bytes().b.0 == 0x06000000);
+    }
+    
+    // Raw identifiers as produced by bindings generators
+    #[allow(non_camel_case_types)]
+    #[derive(Safecast, Clone, Copy)]
+    #[repr(C)]
+    #[safecast(byteswap)]
+    struct r#struct {
+        r#ref: u16,
+        #[safecast(endian = "little")]
+        r#type: u16,
+    }
+    
+    #[allow(non_camel_case_types)]
+    #[derive(CheckedSafecast, Clone, Copy)]
+    #[repr(C)]
+    struct r#enum {
+        r#match: bool,
+        r#in:    u8,
+    }
+    
+    #[allow(non_camel_case_types)]
+    #[derive(CheckedSafecast, Clone, Copy, PartialEq, Debug)]
+    #[repr(u8)]
+    enum r#kind {
+        r#fn = 1,
+        r#loop,
+    }
+    
+    #[test]
+    fn check_raw_identifiers() {
+        let mut val = r#struct { r#ref: 0x1122, r#type: 0 };
+        val.set_type(0x3344);
+        assert!(val.r#type() == 0x3344);
+        assert!(val.swap_bytes().r#ref == 0x2211);
+    
+        assert!(r#enum::try_from_bytes(&[1, 5]).unwrap().r#in == 5);
+        assert!(r#enum::try_from_bytes(&[5, 5]).is_err());
+    
+        assert!(r#kind::try_from_bytes(&[2]) == Ok(&r#kind::r#loop));
+        assert!(r#kind::try_from_bytes(&[0]).is_err());
+    }
+    
+    #[derive(Safecast, Clone, Copy)]
+    #[repr(C, align(8))]
+    struct AlignedHeader {
+        a: u32,
+        b: u32,
+    }
+    
+    #[derive(Safecast, Clone, Copy)]
+    #[repr(align(4))] #[derive(Debug)] #[repr(C)]
+    struct SplitRepr {
+        a: [u8; 4],
+    }
+    
+    #[derive(CheckedSafecast, Clone, Copy, PartialEq, Debug)]
+    #[repr(u16, C)]
+    enum ReversedRepr {
+        A(u16),
+    }
+    
+    #[test]
+    fn check_combined_reprs() {
+        let bytes = [0u64; 2];
+        let hdrs = bytes.cast::<AlignedHeader>();
+        assert!(hdrs.len() == 2 && hdrs[1].b == 0);
+        assert!(SplitRepr::from_bytes(bytes.cast()).is_err());
+        assert!(SplitRepr::from_bytes(&bytes.cast()[..4]).is_ok());
+        assert!(ReversedRepr::try_from_bytes(&bytes.cast()[..4]) ==
+                Ok(&ReversedRepr::A(0)));
+    }
+    
+    #[derive(Safecast, Clone, Copy, Default)]
+    #[repr(C, align(16))]
+    #[safecast(assert_align = 16)]
+    struct DmaDescriptor {
+        addr:  u64,
+        len:   u32,
+        flags: u32,
+    }
+    
+    #[derive(CheckedSafecast, Clone, Copy)]
+    #[repr(C)]
+    #[safecast(assert_align = 4)]
+    struct PageTableEntry {
+        present: bool,
+        _rsvd:   [u8; 3],
+        frame:   u32,
+    }
+    
+    #[test]
+    fn check_assert_align() {
+        let descs = [DmaDescriptor::default(); 2];
+        assert!(descs.cast::<u8>().len() == 32);
+        assert!(AlignedBytes::<32>::new().cast::<DmaDescriptor>().len() == 2);
+    
+        let bytes = [1u32.to_le(), 0x1000];
+        let pte = PageTableEntry::try_from_bytes(bytes.cast()).unwrap();
+        assert!(pte.present && pte.frame == 0x1000);
+    }
+    
+    // Sized using the layout constants, rather than repeating `size_of`
+    static DESCRIPTOR_RING: [u8; 4 * DmaDescriptor::SIZE] =
+        [0; 4 * DmaDescriptor::SIZE];
+    
+    #[test]
+    fn check_layout_constants() {
+        assert_eq!((u8::SIZE, u8::ALIGN), (1, 1));
+        assert_eq!((<[U32Be; 3]>::SIZE, <[U32Be; 3]>::ALIGN), (12, 1));
+        assert_eq!((DmaDescriptor::SIZE, DmaDescriptor::ALIGN), (16, 16));
+        assert_eq!((PageTableEntry::SIZE, PageTableEntry::ALIGN), (8, 4));
+        assert!(DESCRIPTOR_RING.len() == 64);
+    }
+    
+    #[test]
+    fn check_field_layout() {
+        let field = |name, offset, size| FieldLayout { name, offset, size };
+        assert!(DmaDescriptor::FIELDS == [
+            field("addr", 0, 8), field("len", 8, 4), field("flags", 12, 4),
+        ]);
+        assert!(PageTableEntry::FIELDS == [
+            field("present", 0, 1), field("_rsvd", 1, 3), field("frame", 4, 4),
+        ]);
+        assert!(Descriptor::FIELDS.iter().all(|x| x.offset == 0));
+    
+        // Tuple fields are named by index and raw identifiers are unprefixed
+        assert!(SwapNested::FIELDS[0].name == "0");
+        assert!(r#struct::FIELDS[1] == field("type", 2, 2));
+    
+        // Fields which are configured out are not listed
+        assert!(Configured::FIELDS == [field("a", 0, 4), field("c", 4, 4)]);
+    
+        assert!(DmaDescriptor::field_at(11) == Some(&field("len", 8, 4)));
+        assert!(DmaDescriptor::field_at(16).is_none());
+    }
+    
+    fn nonzero_sectors(sectors: &u32) -> bool {
+        *sectors != 0
+    }
+    
+    #[derive(Safecast, Clone, Copy)]
+    #[repr(C, packed)]
+    #[safecast(byteswap)]
+    struct LegacyRecord {
+        kind: u8,
+        #[safecast(endian = "big")]
+        len: u32,
+        #[safecast(validate = nonzero_sectors)]
+        sectors: u32,
+        inner: SwapHeader,
+    }
+    
+    #[derive(CheckedSafecast)]
+    #[repr(C, packed(2))]
+    struct LegacyEntry {
+        used:    bool,
+        _rsvd:   u8,
+        #[safecast(validate = nonzero_sectors)]
+        sectors: u32,
+        status:  Status,
+    }
+    
+    #[test]
+    fn check_packed_structs() {
+        assert_eq!((LegacyRecord::SIZE, LegacyRecord::ALIGN), (25, 1));
+        assert!(LegacyRecord::FIELDS[2].offset == 5);
+    
+        let mut bytes = [0u8; 25];
+        bytes[1..5].copy_from_slice(&0x1234u32.to_be_bytes());
+        bytes[5..9].copy_from_slice(&3u32.to_ne_bytes());
+        bytes[9..13].copy_from_slice(&0xcafeu32.to_ne_bytes());
+        let record: LegacyRecord = bytes.cast_copy();
+        assert!(record.len() == 0x1234);
+        assert!({ record.inner }.magic == 0xcafe);
+        assert!({ record.swap_bytes().sectors } == 3u32.swap_bytes());
+    
+        // Validation runs on an unaligned copy of the field
+        bytes[5..9].copy_from_slice(&[0; 4]);
+        assert!(std::panic::catch_unwind(|| {
+            bytes.cast_copy::<LegacyRecord>()
+        }).is_err());
+    
+        // Checked fields are validated even when misaligned in the input
+        let mut bytes = AlignedBytes::<10>::new();
+        bytes[0] = 1;
+        bytes[2..6].copy_from_slice(&7u32.to_ne_bytes());
+        bytes[6..10].copy_from_slice(&0xdeadbeefu32.to_ne_bytes());
+        let entry = LegacyEntry::try_from_bytes(&bytes[..]).unwrap();
+        assert!(entry.used && { entry.sectors } == 7);
+        assert!(LegacyEntry::FIELDS[3].offset == 6);
+        bytes[6] ^= 1;
+        assert!(LegacyEntry::try_from_bytes(&bytes[..]).is_err());
+        bytes[6] ^= 1;
+        bytes[2..6].copy_from_slice(&[0; 4]);
+        assert!(LegacyEntry::try_from_bytes(&bytes[..]).is_err());
+    }
+    
+    #[derive(Safecast, Debug, Clone, Copy, PartialEq)]
+    #[repr(transparent)]
+    #[safecast(byteswap)]
+    struct InodeId(U32Be);
+    
+    #[derive(CheckedSafecast, Clone, Copy)]
+    #[repr(transparent)]
+    struct Present(bool);
+    
+    #[derive(Safecast, Clone, Copy)]
+    #[repr(transparent)]
+    struct Meters<T> {
+        val:   T,
+        _unit: std::marker::PhantomData<T>,
+    }
+    
+    #[test]
+    fn check_transparent_newtypes() {
+        let ids: &[InodeId] = [0u8, 0, 0, 2, 0, 0, 1, 0].cast();
+        assert!(ids[1] == InodeId(U32Be::new(0x100)));
+        assert!(ids[0].swap_bytes().0.get() == 0x02000000);
+    
+        assert!(Present::try_from_bytes(&[1]).unwrap().0);
+        assert!(Present::try_from_bytes(&[2]).is_err());
+    
+        let dist = Meters { val: 5u16, _unit: std::marker::PhantomData };
+        assert!(dist.cast::<u8>() == 5u16.to_ne_bytes());
+        assert!(Meters::<u64>::FIELDS[0] == FieldLayout {
+            name: "val", offset: 0, size: 8,
+        });
+    }
+    
+    #[derive(Safecast, Clone, Copy)]
+    #[repr(C)]
+    #[safecast(debug)]
+    struct DebugHeader {
+        magic: U32Be,
+        len:   U16Le,
+        kind:  [u8; 2],
+    }
+    
+    #[derive(CheckedSafecast)]
+    #[repr(C)]
+    #[safecast(debug)]
+    struct DebugChecked<T>(bool, T);
+    
+    #[test]
+    fn check_debug_option() {
+        let hdr = DebugHeader {
+            magic: U32Be::new(0xdeadbeef),
+            len:   U16Le::new(0x10),
+            kind:  *b"ab",
+        };
+        assert!(format!("{:?}", hdr) == "DebugHeader { \
+            magic: @0x0 de ad be ef, len: @0x4 10 00, kind: @0x6 61 62 }");
+        assert!(format!("{:#?}", hdr).contains("\n    len: @0x4 10 00,\n"));
+    
+        let val = DebugChecked(true, 0u8);
+        assert!(format!("{:?}", val) ==
+                "DebugChecked { 0: @0x0 01, 1: @0x1 00 }");
+    }
+    
+    #[derive(Safecast, Clone, Copy)]
+    #[repr(C)]
+    #[safecast(portable)]
+    struct PortableEntry {
+        offset: u64,
+        len:    u32,
+        next:   Option<core::num::NonZeroU32>,
+        name:   [u8; 16],
+    }
+    
+    #[derive(Safecast, Clone, Copy)]
+    #[repr(C)]
+    #[safecast(portable, size = 8)]
+    union PortableWord {
+        word:  u64,
+        bytes: [u8; 8],
+    }
+    
+    #[test]
+    fn check_portable_option() {
+        assert_eq!((PortableEntry::SIZE, PortableWord::SIZE), (32, 8));
+        let entry: PortableEntry = [0u8; 32].cast_copy();
+        assert!(entry.next.is_none());
+    }
+    
+    #[test]
+    fn check_byte_reader() {
+        let mut bytes = vec![0u8];
+        bytes.extend_from_slice(&0xdeadbeefu32.to_be_bytes());
+        bytes.extend_from_slice(&[1, 0, 2, 3, 4, 5, 6]);
+    
+        // Reads are unaligned and advance the reader
+        let mut reader = ByteReader::new(&bytes[1..]);
+        assert!(reader.read::<U32Be>().unwrap().get() == 0xdeadbeef);
+        assert!(reader.position() == 4 && reader.remaining() == 7);
+    
+        // Failed reads leave the reader untouched
+        assert!(reader.read_array::<bool, 3>() ==
+                Err(SafecastError::InvalidValue));
+        assert!(reader.read_array::<bool, 2>() == Ok([true, false]));
+        assert!(reader.read::<u64>() == Err(SafecastError::OutOfBounds));
+        assert!(reader.position() == 6);
+    
+        reader.skip(2).unwrap();
+        assert!(reader.read_bytes(3) == Ok(&[4, 5, 6][..]));
+        assert!(reader.is_empty() && reader.remaining_bytes().is_empty());
+        assert!(reader.skip(1) == Err(SafecastError::OutOfBounds));
+    }
+    
+    #[test]
+    fn check_byte_writer() {
+        let mut buf = [0xffu8; 12];
+        let mut writer = ByteWriter::new(&mut buf[1..]);
+    
+        // Writes are unaligned and advance the writer
+        writer.write(&U32Be::new(0xdeadbeef)).unwrap();
+        writer.write(&true).unwrap();
+        writer.write_zeros(1).unwrap();
+        assert!(writer.position() == 6 && writer.remaining() == 5);
+        assert!(writer.written() == [0xde, 0xad, 0xbe, 0xef, 1, 0]);
+    
+        // Failed writes leave the writer untouched
+        assert!(writer.write_slice(&[U16Le::new(1); 3]) ==
+                Err(SafecastError::OutOfBounds));
+        assert!(writer.write(&0u64) == Err(SafecastError::OutOfBounds));
+        assert!(writer.position() == 6);
+    
+        writer.write_slice(&[U16Le::new(0x0201); 2]).unwrap();
+        writer.write_bytes(&[9]).unwrap();
+        assert!(writer.write_zeros(1) == Err(SafecastError::OutOfBounds));
+        assert!(writer.into_written().len() == 11);
+        assert!(buf == [0xff, 0xde, 0xad, 0xbe, 0xef, 1, 0, 1, 2, 1, 2, 9]);
+    }
+    
+    #[test]
+    fn check_peek_take() {
+        let bytes = [0u8, 0, 0, 1, 0, 0, 0, 7];
+        assert!(peek::<U32Be>(&bytes[1..]).unwrap().get() == 0x100);
+    
+        // Parsers can be written as chains
+        let (a, rest) = take::<U32Be>(&bytes).unwrap();
+        let (b, rest) = take::<U16Le>(rest).unwrap();
+        let (c, rest) = take::<bool>(rest).unwrap();
+        assert!(a.get() == 1 && b.get() == 0 && !c && rest == [7]);
+    
+        assert!(take::<bool>(rest).err() == Some(SafecastError::InvalidValue));
+        assert!(peek::<u16>(rest) == Err(SafecastError::OutOfBounds));
+        assert!(take::<u8>(rest) == Ok((7, &[][..])));
+    }
+    
+    #[derive(Safecast, Clone, Copy)]
+    #[repr(C)]
+    struct LogHeader {
+        kind: u8,
+        len:  U16Be,
+    }
+    
+    impl RecordHeader for LogHeader {
+        fn payload_len(&self) -> usize {
+            self.len.get() as usize
+        }
+    }
+    
+    #[test]
+    fn check_records() {
+        let bytes = [2u8, 0, 0, 0, 0xaa, 0xbb, 9, 0, 0, 0, 1, 2];
+        let mut iter = records::<U32Le>(&bytes);
+        let (hdr, payload) = iter.next().unwrap().unwrap();
+        assert!(hdr.get() == 2 && payload == [0xaa, 0xbb]);
+        assert!(iter.remaining().len() == 6);
+    
+        // The length of the second record runs past the end of the bytes
+        assert!(iter.next() == Some(Err(SafecastError::OutOfBounds)));
+        assert!(iter.next().is_none() && iter.remaining().is_empty());
+    
+        // Records with structure headers
+        let bytes = [1u8, 0, 2, 0xaa, 0xbb, 2, 0, 0, 3, 0, 1, 0xcc, 4, 0];
+        let logs: Vec<_> = records::<LogHeader>(&bytes).collect();
+        assert!(logs.len() == 4);
+        assert!(logs[0].unwrap().1 == [0xaa, 0xbb]);
+        assert!(logs[1].unwrap().0.kind == 2 && logs[1].unwrap().1.is_empty());
+        assert!(logs[2].unwrap().0.kind == 3 && logs[2].unwrap().1 == [0xcc]);
+        assert!(logs[3].err() == Some(SafecastError::OutOfBounds));
+    
+        assert!(records::<u8>(&[]).next().is_none());
+        assert!(records::<u8>(&[0, 0]).all(|x| x.unwrap().1.is_empty()));
+    }
+    
+    #[derive(Safecast, Clone, Copy)]
+    #[repr(C)]
+    struct TlvOption {
+        kind: u8,
+        len:  u8,
+    }
+    
+    impl TlvHeader for TlvOption {
+        type Tag = u8;
+        fn tag(&self) -> u8 { self.kind }
+        fn len(&self) -> usize { self.len as usize }
+    }
+    
+    #[derive(Safecast, Clone, Copy)]
+    #[repr(C)]
+    struct TableHeader {
+        signature: [u8; 4],
+        length:    U32Le,
+    }
+    
+    impl TlvHeader for TableHeader {
+        type Tag = [u8; 4];
+        const LEN_INCLUDES_HEADER: bool = true;
+        fn tag(&self) -> [u8; 4] { self.signature }
+        fn len(&self) -> usize { self.length.get() as usize }
+    }
+    
+    #[test]
+    fn check_tlvs() {
+        // The second option nests two more options
+        let bytes = [1u8, 2, 0xaa, 0xbb, 2, 5, 3, 0, 4, 1, 9, 5, 0];
+        let elems: Vec<_> = tlvs::<TlvOption>(&bytes)
+            .collect::<Result<_, _>>().unwrap();
+        assert!(elems.len() == 3);
+        assert!(elems[0].tag() == 1 && elems[0].view::<U16Be>().is_ok());
+        assert!(elems[0].view::<u32>() ==
+                Err(SafecastError::size_mismatch(2, 4, 4)));
+        let nested: Vec<_> = elems[1].children::<TlvOption>()
+            .map(|x| (x.unwrap().tag(), x.unwrap().value)).collect();
+        assert!(nested == [(3, &[][..]), (4, &[9][..])]);
+        assert!(elems[2].tag() == 5 && elems[2].value.is_empty());
+    
+        // Nested elements cannot overflow their parent
+        let bytes = [2u8, 2, 3, 2, 0xff, 0xff];
+        let parent = tlvs::<TlvOption>(&bytes).next().unwrap().unwrap();
+        let mut children = parent.children::<TlvOption>();
+        assert!(children.next().unwrap().err() ==
+                Some(SafecastError::OutOfBounds));
+        assert!(children.next().is_none());
+    
+        // Lengths which include the header
+        let mut bytes = Vec::new();
+        bytes.extend_from_slice(b"FACP\x0a\0\0\0\x11\x22");
+        bytes.extend_from_slice(b"SSDT\x08\0\0\0");
+        bytes.extend_from_slice(b"BAD!\x04\0\0\0");
+        let mut tables = tlvs::<TableHeader>(&bytes);
+        let facp = tables.next().unwrap().unwrap();
+        assert!(&facp.tag() == b"FACP" && facp.value == [0x11, 0x22]);
+        assert!(tables.next().unwrap().unwrap().value.is_empty());
+        assert!(tables.next().unwrap().err() ==
+                Some(SafecastError::InvalidValue));
+        assert!(tables.next().is_none() && tables.remaining().is_empty());
+    }
+    
+    #[test]
+    fn check_deframer() {
+        let mut deframer = Deframer::<TlvOption>::new();
+    
+        // A value split over multiple pushes
+        let mut bytes = &[1u8][..];
+        assert!(deframer.push(&mut bytes).is_none() && bytes.is_empty());
+        assert!(deframer.buffered() == 1);
+    
+        // The end of one value along with two more, and the start of another
+        let mut bytes = &[2u8, 3, 4, 5, 6, 7][..];
+        let mut received = Vec::new();
+        while let Some(opt) = deframer.push(&mut bytes) {
+            received.push((opt.kind, opt.len));
+        }
+        assert!(received == [(1, 2), (3, 4), (5, 6)] && bytes.is_empty());
+        assert!(deframer.buffered() == 1);
+    
+        deframer.reset();
+        let mut bytes = &[8u8, 9][..];
+        assert!(deframer.push(&mut bytes).map(|x| x.kind) == Some(8));
+    }
+    
+    #[test]
+    fn check_cast_iter() {
+        let bytes = [0u8, 0, 1, 0, 2, 0xff];
+        let (iter, rest) = safecast::cast_iter::<U16Be>(&bytes[1..]);
+        assert!(iter.len() == 2 && rest == [0xff]);
+        assert!(iter.clone().rev().map(|x| x.get()).eq([2, 1]));
+        assert!(iter.map(|x| x.get()).eq([1, 2]));
+    
+        let (mut iter, rest) = safecast::cast_iter::<u32>(&bytes[..3]);
+        assert!(iter.next().is_none() && rest.len() == 3);
+    }
+    
+    fn sum_bytes(bytes: &[u8]) -> u8 {
+        bytes.iter().fold(0u8, |acc, x| acc.wrapping_add(*x))
+    }
+    
+    #[derive(Safecast, Clone, Copy)]
+    #[repr(C)]
+    struct FlashEntry {
+        seq:  U16Le,
+        data: [u8; 4],
+        #[safecast(checksum = sum_bytes)]
+        sum:  u8,
+        kind: u8,
+    }
+    
+    #[derive(CheckedSafecast, Clone, Copy)]
+    #[repr(C)]
+    struct Telemetry {
+        valid: bool,
+        _rsvd: u8,
+        #[safecast(checksum = Checksum16::compute)]
+        csum:  Checksum16,
+        value: U32Be,
+    }
+    
+    #[test]
+    fn check_checksums() {
+        let mut entry = FlashEntry {
+            seq: U16Le::new(1), data: [1, 2, 3, 4], sum: 0, kind: 0x10,
+        };
+        entry.update_checksum();
+        assert!(entry.sum == 0x1b && entry.verify_checksum());
+    
+        let mut telemetry = Telemetry {
+            valid: true, _rsvd: 0, csum: Checksum16::new(0),
+            value: U32Be::new(0x12345678),
+        };
+        telemetry.update_checksum();
+        assert!(Checksum16::verify(telemetry.as_bytes()));
+    
+        // Corrupt records are rejected without advancing the reader
+        let mut bytes = entry.cast::<u8>().to_vec();
+        bytes.extend_from_slice(telemetry.as_bytes());
+        bytes[3] ^= 1;
+        let mut reader = ByteReader::new(&bytes);
+        assert!(reader.read_verified::<FlashEntry>().err() ==
+                Some(SafecastError::ChecksumMismatch));
+        assert!(reader.position() == 0);
+        reader.skip(FlashEntry::SIZE).unwrap();
+        assert!(reader.read_verified::<Telemetry>().unwrap().value.get() ==
+                0x12345678);
+    }
+    
+    #[test]
+    fn check_pod_views() {
+        // An IPv4 packet inside of an Ethernet frame, with a trailing byte
+        let mut frame = [0u8; 14 + 20 + 1];
+        frame[12..14].copy_from_slice(&0x0800u16.to_be_bytes());
+        frame[14] = 0x45;
+        frame[34] = 0xff;
+    
+        let mut view = PodViewMut::new(&mut frame);
+        assert!(view.get::<U16Be>(12).unwrap().get() == 0x0800);
+        let mut ip = view.window_mut(14, 20).unwrap();
+        assert!(ip.get::<u8>(0) == Ok(0x45));
+        ip.set(12, &Ipv4Addr([10, 0, 0, 1])).unwrap();
+    
+        // Accesses can never escape the window
+        assert!(ip.get::<u8>(20) == Err(SafecastError::OutOfBounds));
+        assert!(ip.set(18, &0u32) == Err(SafecastError::OutOfBounds));
+        assert!(ip.window(16, 5).err() == Some(SafecastError::OutOfBounds));
+        assert!(ip.window(usize::MAX, 2).is_err());
+        assert!(ip.bytes()[18..] == [0, 0]);
+    
+        let view = PodView::new(&frame);
+        let ip = view.window(14, 20).unwrap();
+        assert!(ip.len() == 20 && ip.get::<Ipv4Addr>(12) ==
+                Ok(Ipv4Addr([10, 0, 0, 1])));
+        assert!(ip.get::<bool>(0) == Err(SafecastError::InvalidValue));
+        assert!(view.get::<u8>(34) == Ok(0xff));
+    }
+    
+    #[derive(CheckedSafecast, Clone, Copy, PartialEq, Debug)]
+    #[repr(C)]
+    struct PartitionEntry {
+        bootable: bool,
+        kind:     u8,
+        start:    U32Le,
+        sectors:  U16Le,
+    }
+    
+    #[test]
+    fn check_array_reads() {
+        let entries = [
+            PartitionEntry {
+                bootable: true, kind: 0x83, start: U32Le::new(2048),
+                sectors: U16Le::new(100),
+            },
+            PartitionEntry {
+                bootable: false, kind: 0x82, start: U32Le::new(4096),
+                sectors: U16Le::new(8),
+            },
+        ];
+    
+        // Round trip through an unaligned table
+        let mut table = [0u8; 1 + 16];
+        write_array(&mut table[1..], &entries).unwrap();
+        assert!(read_array::<PartitionEntry, 2>(&table[1..]) == Ok(entries));
+        assert!(read_array::<U16Be, 3>(&table[1..]).is_ok());
+    
+        // Validation and bounds
+        table[1 + 8] = 2;
+        assert!(read_array::<PartitionEntry, 2>(&table[1..]) ==
+                Err(SafecastError::InvalidValue));
+        assert!(read_array::<PartitionEntry, 3>(&table[1..]) ==
+                Err(SafecastError::OutOfBounds));
+        assert!(write_array(&mut table[2..], &entries) ==
+                Err(SafecastError::OutOfBounds));
+        assert!(table[1 + 8] == 2);
+    }
+    
+    /// A reader which only ever returns a single byte at a time
+    struct Trickle<'a>(&'a [u8]);
+    
+    impl std::io::Read for Trickle<'_> {
+        fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+            let len = self.0.len().min(buf.len()).min(1);
+            buf[..len].copy_from_slice(&self.0[..len]);
+            self.0 = &self.0[len..];
+            Ok(len)
+        }
+    }
+    
+    #[test]
+    fn check_io_ext() {
+        let mut file = Vec::new();
+        file.write_pod(&U32Be::new(0xcafe)).unwrap();
+        file.write_pod(&true).unwrap();
+        file.write_pod(&2u8).unwrap();
+        assert!(file == [0, 0, 0xca, 0xfe, 1, 2]);
+    
+        // Short reads are retried
+        let mut reader = Trickle(&file);
+        assert!(reader.read_pod::<U32Be>().unwrap().get() == 0xcafe);
+        assert!(reader.read_pod::<bool>().unwrap());
+    
+        let err = reader.read_pod::<bool>().unwrap_err();
+        assert!(err.kind() == std::io::ErrorKind::InvalidData);
+        assert!(err.to_string() == "Invalid bit pattern");
+        let err = (&file[..3]).read_pod::<u32>().unwrap_err();
+        assert!(err.kind() == std::io::ErrorKind::UnexpectedEof);
+    }
+    
+    #[test]
+    fn check_struct_file() {
+        let mut file = StructFile::<PartitionEntry, _>::new(
+            std::io::Cursor::new(Vec::new())).unwrap();
+        assert!(file.is_empty());
+    
+        let mut entry = PartitionEntry {
+            bootable: false, kind: 0x83, start: U32Le::new(2048),
+            sectors: U16Le::new(100),
+        };
+        assert!(file.append(&entry).unwrap() == 0);
+        entry.kind = 0x82;
+        assert!(file.append(&entry).unwrap() == 1);
+        entry.bootable = true;
+        file.set(0, &entry).unwrap();
+        assert!(file.len() == 2 && file.get(0).unwrap() == entry);
+        assert!(file.get(1).unwrap().kind == 0x82);
+    
+        let err = file.get(2).unwrap_err();
+        assert!(err.kind() == std::io::ErrorKind::InvalidInput);
+        assert!(file.set(2, &entry).is_err());
+    
+        // Records are validated as they are read
+        let mut bytes = file.into_inner().into_inner();
+        bytes[8] = 2;
+        let mut file = StructFile::<PartitionEntry, _>::new(
+            std::io::Cursor::new(&mut bytes)).unwrap();
+        let err = file.get(1).unwrap_err();
+        assert!(err.kind() == std::io::ErrorKind::InvalidData);
+    
+        // Files must hold a whole number of records
+        bytes.push(0);
+        let err = StructFile::<PartitionEntry, _>::new(
+            std::io::Cursor::new(&mut bytes)).unwrap_err();
+        assert!(err.kind() == std::io::ErrorKind::InvalidData);
+    
+        // Backed by a real file
+        let path = std::env::temp_dir().join(
+            format!("safecast_struct_file_{}", std::process::id()));
+        let mut file = StructFile::<U32Be>::create(&path).unwrap();
+        file.append(&U32Be::new(5)).unwrap();
+        drop(file);
+        let mut file = StructFile::<U32Be>::open(&path).unwrap();
+        assert!(file.len() == 1 && file.get(0).unwrap().get() == 5);
+        std::fs::remove_file(&path).unwrap();
+    }
+    
+    #[test]
+    fn check_mmap() {
+        let mut region = AlignedBytes::<16>::new();
+        region[..4].copy_from_slice(&[1, 2, 0x10, 1]);
+    
+        let msgs: &[MessageType] = mmap::try_cast_slice(&region[..4]).unwrap();
+        assert!(msgs == [MessageType::Ping, MessageType::Pong,
+                         MessageType::Data, MessageType::Ping]);
+    
+        // Length, alignment, and every element are checked
+        assert!(mmap::try_cast_slice::<U16Le>(&region[..3]).unwrap_err() ==
+            SafecastError::slice_size_mismatch(3, 2));
+        assert!(mmap::try_cast_slice::<u32>(&region[1..5]).unwrap_err() ==
+            SafecastError::AlignmentMismatch);
+        assert!(mmap::try_cast_slice::<MessageType>(&region[..]).unwrap_err()
+            == SafecastError::InvalidValue);
+    
+        let ptr = region.as_mut_ptr();
+        let words = unsafe { mmap::slice_from_raw_mut::<u32>(ptr, 16) }
+            .unwrap();
+        words[3] = 0x11223344;
+        assert!(words.len() == 4);
+        let bytes = unsafe { mmap::slice_from_raw::<U32Be>(ptr, 16) }
+            .unwrap();
+        assert!(bytes[3].get() == u32::from_be(0x11223344));
+        assert!(unsafe { mmap::slice_from_raw::<U32Be>(ptr, usize::MAX) }
+            .unwrap_err() == SafecastError::OutOfBounds);
+    
+        // Shared views of atomics are only given out for raw mappings
+        use std::sync::atomic::{AtomicU32, Ordering};
+        assert!(mmap::try_cast_slice::<AtomicU32>(&region[..]).unwrap_err() ==
+            SafecastError::InteriorMutable);
+        let counters = unsafe { mmap::slice_from_raw::<AtomicU32>(ptr, 16) }
+            .unwrap();
+        counters[3].fetch_add(1, Ordering::SeqCst);
+        assert!(counters[3].load(Ordering::SeqCst) == 0x11223345);
+    }
+    
+    #[test]
+    fn check_sockets() {
+        use std::net::{UdpSocket, TcpListener, TcpStream};
+    
+        let a = UdpSocket::bind("127.0.0.1:0").unwrap();
+        let b = UdpSocket::bind("127.0.0.1:0").unwrap();
+        a.send_pod_to(&U32Be::new(0xcafe), b.local_addr().unwrap()).unwrap();
+        let (val, src) = b.recv_pod_from::<U32Be>().unwrap();
+        assert!(val.get() == 0xcafe && src == a.local_addr().unwrap());
+    
+        // Datagrams must be exactly the size of the value
+        b.connect(a.local_addr().unwrap()).unwrap();
+        a.connect(b.local_addr().unwrap()).unwrap();
+        b.send_pod(&U32Be::new(5)).unwrap();
+        assert!(a.recv_pod::<U16Le>().unwrap_err().kind() ==
+            std::io::ErrorKind::InvalidData);
+        b.send_pod(&2u8).unwrap();
+        assert!(a.recv_pod::<U16Le>().unwrap_err().kind() ==
+            std::io::ErrorKind::InvalidData);
+        b.send_pod(&2u8).unwrap();
+        assert!(a.recv_pod::<bool>().unwrap_err().kind() ==
+            std::io::ErrorKind::InvalidData);
+        b.send_pod(&true).unwrap();
+        assert!(a.recv_pod::<bool>().unwrap());
+    
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let client = TcpStream::connect(listener.local_addr().unwrap())
+            .unwrap();
+        let (server, _) = listener.accept().unwrap();
+        client.send_pod(&[U32Le::new(1), U32Le::new(2)]).unwrap();
+        assert!(server.recv_pod::<U32Le>().unwrap().get() == 1);
+        assert!(server.recv_pod::<U32Le>().unwrap().get() == 2);
+        drop(client);
+        assert!(server.recv_pod::<U32Le>().unwrap_err().kind() ==
+            std::io::ErrorKind::UnexpectedEof);
+    }
+    
+    #[derive(serde::Serialize, serde::Deserialize, Debug, PartialEq)]
+    struct Config {
+        name: String,
+    
+        #[serde(with = "safecast::serde_bytes")]
+        entry: PartitionEntry,
+    }
+    
+    #[test]
+    fn check_serde_bytes() {
+        let config = Config {
+            name:  "boot".into(),
+            entry: PartitionEntry {
+                bootable: true, kind: 0x83, start: U32Le::new(2048),
+                sectors: U16Le::new(100),
+            },
+        };
+    
+        // Human-readable formats get base64
+        let json = serde_json::to_string(&config).unwrap();
+        assert!(json == r#"{"name":"boot","entry":"AYMACAAAZAA="}"#);
+        assert!(serde_json::from_str::<Config>(&json).unwrap() == config);
+    
+        // Sequences of bytes are accepted too
+        let json = r#"{"name":"boot","entry":[1,131,0,8,0,0,100,0]}"#;
+        assert!(serde_json::from_str::<Config>(json).unwrap() == config);
+    
+        // Sizes and bit patterns are checked
+        for json in [r#"{"name":"","entry":"AYMACAAAZA=="}"#,
+                     r#"{"name":"","entry":"AoMACAAAZAA="}"#,
+                     r#"{"name":"","entry":"AYMACAAAZA!="}"#,
+                     r#"{"name":"","entry":[1,131,0,8,0,0,100]}"#,
+                     r#"{"name":"","entry":[1,131,0,8,0,0,100,0,0]}"#] {
+            assert!(serde_json::from_str::<Config>(json).is_err());
+        }
+    
+        // Binary formats get a byte string
+        let mut cbor = Vec::new();
+        ciborium::into_writer(&config, &mut cbor).unwrap();
+        assert!(cbor.windows(9)
+            .any(|x| x == [0x48, 1, 0x83, 0, 8, 0, 0, 100, 0]));
+        assert!(ciborium::from_reader::<Config, _>(&cbor[..]).unwrap() ==
+            config);
+    }
+    
+    #[derive(Clone, Copy, PartialEq, Debug)]
+    #[repr(C)]
+    struct Vertex {
+        pos:   [f32; 2],
+        color: u32,
+    }
+    
+    unsafe impl bytemuck::Zeroable for Vertex {}
+    unsafe impl bytemuck::Pod for Vertex {}
+    
+    #[derive(Safecast, Clone, Copy, PartialEq, Debug)]
+    #[safecast(bytemuck)]
+    #[repr(C)]
+    struct Record {
+        kind: U16Be,
+        len:  U16Le,
+        id:   u32,
+    }
+    
+    #[test]
+    fn check_bytemuck() {
+        use safecast::bytemuck::Bytemuck;
+    
+        // `Pod` types can be cast through the wrapper
+        let vert = Bytemuck(Vertex { pos: [1.0, 2.0], color: 0xff00ff });
+        let bytes = vert.as_bytes();
+        assert!(bytes[8..] == [0xff, 0, 0xff, 0]);
+        assert!(bytes.cast::<Bytemuck<Vertex>>()[0] == vert);
+        assert!(vert.color == 0xff00ff);
+    
+        // Derived types are `Pod` as well
+        let record = Record {
+            kind: U16Be::new(1), len: U16Le::new(2), id: 3,
+        };
+        assert!(bytemuck::bytes_of(&record) == record.as_bytes());
+        let pair = [record, bytemuck::Zeroable::zeroed()];
+        let words: &[u32] = bytemuck::cast_slice(&pair);
+        assert!(words.len() == 4 && words[1] == 3 && words[3] == 0);
+        let records: &[Record] = bytemuck::cast_slice(&words[..2]);
+        assert!(records == [record]);
+    }
+    
+    #[derive(zerocopy::FromBytes, zerocopy::IntoBytes, zerocopy::Immutable)]
+    #[derive(zerocopy::KnownLayout, Clone, Copy, PartialEq, Debug)]
+    #[repr(C)]
+    struct ZcHeader {
+        magic: [u8; 2],
+        len:   u16,
+    }
+    
+    #[derive(Safecast, zerocopy::FromBytes, zerocopy::IntoBytes)]
+    #[derive(zerocopy::Immutable, zerocopy::KnownLayout, zerocopy::Unaligned)]
+    #[repr(C)]
+    struct UdpHeader {
+        src: U16Be,
+        dst: U16Be,
+        len: U16Be,
+        sum: Checksum16,
+    }
+    
+    #[test]
+    fn check_zerocopy() {
+        use safecast::zerocopy::Zerocopy;
+        use zerocopy::FromBytes as _;
+    
+        // zerocopy types can be cast through the wrapper
+        let header = Zerocopy(ZcHeader { magic: *b"ZC", len: 4 });
+        let bytes = header.as_bytes();
+        assert!(bytes[..2] == *b"ZC");
+        assert!(bytes.cast::<Zerocopy<ZcHeader>>()[0] == header);
+        assert!(header.len == 4);
+    
+        // Types of this crate can be used in zerocopy structures
+        let bytes = [0, 53, 0x30, 0x39, 0, 8, 0, 0];
+        let udp = UdpHeader::ref_from_bytes(&bytes[..]).unwrap();
+        assert!(udp.src.get() == 53 && udp.len.get() == 8);
+        assert!(zerocopy::IntoBytes::as_bytes(udp) ==
+            safecast::IntoBytes::as_bytes(udp));
+    }
+    
+    #[test]
+    fn check_bytes_buf() {
+        use bytes::{Buf, BytesMut};
+        use safecast::buf::{BufPodExt, BufMutPodExt};
+    
+        let mut out = BytesMut::new();
+        out.put_pod(&U32Be::new(0xcafe));
+        out.put_pod(&true);
+        out.put_pod(&2u8);
+        assert!(out[..] == [0, 0, 0xca, 0xfe, 1, 2]);
+    
+        let mut buf = out.freeze();
+        assert!(buf.get_pod::<U32Be>().unwrap().get() == 0xcafe);
+        assert!(buf.get_pod::<bool>().unwrap());
+        assert!(buf.get_pod::<U16Le>() == Err(SafecastError::OutOfBounds));
+        assert!(buf.get_pod::<bool>() == Err(SafecastError::InvalidValue));
+        assert!(!buf.has_remaining());
+    
+        // Values spanning chunks are gathered
+        let mut buf = (&[0u8, 0, 0xca][..]).chain(&[0xfe, 1, 2][..]);
+        assert!(buf.get_pod::<U32Be>().unwrap().get() == 0xcafe);
+        assert!(buf.get_pod::<bool>().unwrap());
+        let mut buf = (&[3u8][..]).chain(&[0, 0, 9][..]);
+        assert!(buf.get_pod::<Message>().err() ==
+            Some(SafecastError::InvalidValue));
+        assert!(buf.remaining() == 1);
+    }
+    
+    #[test]
+    fn check_heapless() {
+        use safecast::heapless::HeaplessPodExt;
+    
+        let mut buf = heapless::Vec::<u8, 8>::new();
+        buf.push_pod(&U32Be::new(0xcafe)).unwrap();
+        buf.push_pod(&U16Le::new(7)).unwrap();
+        assert!(buf.push_pod(&U32Be::new(1)) ==
+            Err(SafecastError::OutOfBounds));
+        assert!(buf[..] == [0, 0, 0xca, 0xfe, 7, 0]);
+    
+        assert!(buf.view_pods::<U16Be>().unwrap() ==
+            [U16Be::new(0), U16Be::new(0xcafe), U16Be::new(0x700)]);
+        assert!(buf.view_pods::<U32Be>() ==
+                Err(SafecastError::slice_size_mismatch(6, 4)));
+    
+        assert!(buf.pop_pod::<U16Le>().unwrap().get() == 7);
+        assert!(buf.pop_pod::<bool>() == Err(SafecastError::InvalidValue));
+        assert!(buf.pop_pod::<U32Be>().unwrap().get() == 0xcafe);
+        assert!(buf.pop_pod::<u8>() == Err(SafecastError::OutOfBounds));
+    }
+    
+    #[test]
+    fn check_arrayvec() {
+        use safecast::arrayvec::ArrayVecPodExt;
+    
+        let mut buf = arrayvec::ArrayVec::<u8, 6>::new();
+        buf.push_pod(&U32Be::new(0xcafe)).unwrap();
+        buf.push_pod(&true).unwrap();
+        assert!(buf.push_pod(&U16Le::new(1)) ==
+            Err(SafecastError::OutOfBounds));
+        buf.push_pod(&2u8).unwrap();
+        assert!(buf.is_full() && buf[..] == [0, 0, 0xca, 0xfe, 1, 2]);
+        assert!(buf.view_pods::<U16Be>().unwrap()[1].get() == 0xcafe);
+    
+        assert!(buf.pop_pod::<bool>() == Err(SafecastError::InvalidValue));
+        assert!(buf.pop_pod::<u8>().unwrap() == 2);
+        assert!(buf.pop_pod::<bool>().unwrap());
+        assert!(buf.pop_pod::<U32Be>().unwrap().get() == 0xcafe);
+        assert!(buf.is_empty());
+    }
+    
+    #[test]
+    fn check_validated() {
+        // Types whose layout is checked at compile time skip the runtime
+        // checks
+        assert_eq!((u32::VALIDATED, <[U16Le; 4]>::VALIDATED, Au32::VALIDATED,
+                    Ipv4Addr::VALIDATED, UdpHeader::VALIDATED,
+                    <Aligned::<Align8, [u8; 8]>>::VALIDATED),
+                   (true, true, true, true, true, true));
+    
+        // Runtime checks are still run for user-supplied validation, hand
+        // written impls, and wrappers which may add padding
+        assert_eq!((ValidatedHeader::VALIDATED, Au32Pad::VALIDATED,
+                    <Aligned::<Align8, [u8; 4]>>::VALIDATED),
+                   (false, false, false));
+    
+        // Only user-supplied validation checks values, including that of
+        // fields
+        assert_eq!((ValidatedHeader::CHECKS_VALUES,
+                    <[ValidatedHeader; 2]>::CHECKS_VALUES,
+                    Au32Pad::CHECKS_VALUES, u32::CHECKS_VALUES),
+                   (true, true, false, false));
+    }
+    
+    static CHECKS: std::sync::atomic::AtomicUsize =
+        std::sync::atomic::AtomicUsize::new(0);
+    
+    fn count_checks(_: &u32) -> bool {
+        CHECKS.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+        true
+    }
+    
+    #[derive(Safecast, Default)]
+    #[repr(C)]
+    struct Counted {
+        #[safecast(validate = count_checks)]
+        val: u32,
+    }
+    
+    #[test]
+    fn check_copy_validates_once() {
+        let checks = || CHECKS.load(std::sync::atomic::Ordering::Relaxed);
+        let src = Counted { val: 5 };
+        let mut dest = Counted::default();
+    
+        // Copies check the source and the output once each, the values of
+        // the destination are not checked before the copy
+        let before = checks();
+        src.cast_copy_into(&mut dest);
+        let copied: Counted = src.cast_copy();
+        assert!(dest.val == 5 && copied.val == 5);
+        assert!(checks() - before == 4);
+    
+        // Bytes are never checked, only the output is
+        let before = checks();
+        [7u8, 0, 0, 0].cast_copy_into(&mut dest);
+        let _: Counted = 9u32.cast_copy();
+        assert!(checks() - before == 2);
+    }
+    
+    #[test]
+    fn check_cast_copy_into_nontemporal() {
+        let src: Vec<u32> = (0..4099).collect();
+        let mut dest = vec![0u8; 4 * 4099 + 5];
+    
+        // Misaligned destination, with a tail which is not a whole vector
+        src.cast_copy_into_nontemporal(&mut dest[5..]);
+        assert!(dest[..5] == [0; 5] && dest[5..] == *src.cast::<u8>());
+    
+        let mut small = [0u8; 3];
+        [1u8, 2, 3].cast_copy_into_nontemporal(&mut small[..]);
+        assert!(small == [1, 2, 3]);
+    }
+    
+    #[test]
+    fn check_copy_checks_dest_first() {
+        // The layout of the destination is rejected before it is written
+        let mut dest = Au32Pad(0x1337, 5);
+        let result = std::panic::catch_unwind(
+            std::panic::AssertUnwindSafe(|| {
+                [0x41u8; 8].cast_copy_into(&mut dest);
+            }));
+        assert!(result.is_err());
+        assert!(dest.0 == 0x1337 && dest.1 == 5);
+    
+        // The values of the destination are not checked before the copy
+        let mut dest = ValidatedHeader { magic: [0; 4], len: 0, kind: 0 };
+        b"\x7fELF\x00\x01\x02\x00".cast_copy_into(&mut dest);
+        assert!(dest.len() == 0x100);
+    }
+    
+    static LAYOUT_CHECKS: std::sync::atomic::AtomicUsize =
+        std::sync::atomic::AtomicUsize::new(0);
+    
+    // Hand written impl whose checks only depend on the layout
+    #[repr(C)]
+    struct LayoutCounted(u32);
+    
+    unsafe impl Safecast for LayoutCounted {
+        fn safecast(&self) {
+            LAYOUT_CHECKS.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+        }
+    }
+    
+    #[test]
+    fn check_validated_witness() {
+        let checks =
+            || LAYOUT_CHECKS.load(std::sync::atomic::Ordering::Relaxed);
+        let bytes: Vec<u32> = (0..64).collect();
+        let mut bytes = bytes.cast::<u8>().to_vec();
+    
+        // The checks are run once, to obtain the witness
+        let valid = Validated::<LayoutCounted>::check();
+        assert!(checks() == 1);
+    
+        // Then never again, no matter how many casts
+        let mut sum = 0;
+        for chunk in bytes.chunks(16) {
+            sum += valid.cast_slice(chunk).unwrap().iter()
+                .map(|x| x.0).sum::<u32>();
+            sum += valid.read(&chunk[..4]).unwrap().0;
+        }
+        assert!(sum == (0..64).sum::<u32>() + (0..64).step_by(4).sum::<u32>());
+        valid.from_bytes_mut(&mut bytes[4..8]).unwrap().0 = 77;
+        assert!(valid.from_bytes(&bytes[4..8]).unwrap().0 == 77);
+        assert!(valid.cast_slice_mut(&mut bytes[..8]).unwrap().len() == 2);
+        assert!(checks() == 1);
+    
+        // Sizes and alignment are still checked on every cast
+        assert_eq!(valid.from_bytes(&bytes[..8]).err(),
+                   Some(SafecastError::size_mismatch(8, 4, 4)));
+        assert_eq!(valid.cast_slice(&bytes[..6]).err(),
+                   Some(SafecastError::slice_size_mismatch(6, 4)));
+        assert_eq!(valid.cast_slice(&bytes[1..5]).err(),
+                   Some(SafecastError::AlignmentMismatch));
+        assert!(valid.read(&bytes[1..5]).is_ok());
+    }
+    
+    #[test]
+    #[should_panic]
+    fn check_validated_witness_padding() {
+        Validated::<Au32Pad>::check();
+    }
+    
+    #[test]
+    fn check_empty_slice_copy() {
+        let src: &[u32] = &[];
+        let mut dest: [u8; 0] = [];
+    
+        // Zero-length copies are no-ops rather than panics
+        src.cast_copy_into(&mut dest[..]);
+        src.cast_copy_into_nontemporal(&mut dest[..]);
+        Safecast::safecast(src);
+    }
+    
+    #[test]
+    fn check_empty_slice_skips_checks() {
+        // Only the elements of a slice are checked, of which there are none
+        let src: &[Au32Pad] = &[];
+        let mut dest: [u8; 0] = [];
+        src.cast_copy_into(&mut dest[..]);
+    }
+    
+    #[test]
+    fn check_cast_copy_into_sized() {
+        let mut dest = [0u8; 4];
+        0x11223344u32.cast_copy_into_sized(&mut dest);
+        assert!(dest == 0x11223344u32.to_ne_bytes());
+    
+        // The types only need to match in size, not in alignment
+        let mut dest = Au32(0);
+        [1u16, 2].cast_copy_into_sized(&mut dest);
+        assert!(dest.0.to_ne_bytes()[..2] == 1u16.to_ne_bytes());
+    }
+    
+    #[test]
+    #[should_panic]
+    fn check_cast_copy_into_sized_size() {
+        let mut dest = [0u8; 3];
+        0u32.cast_copy_into_sized(&mut dest);
+    }
+    
+    #[test]
+    fn check_cast_copy_slice() {
+        let src: Vec<u32> = (0..1000).collect();
+        let mut dest = vec![Au32(0); 1000];
+        u32::cast_copy_slice(&src, &mut dest);
+        assert!(dest.iter().zip(&src).all(|(x, y)| x.0 == *y));
+    
+        // Empty slices copy nothing
+        u32::cast_copy_slice(&[], &mut dest[..0]);
+    }
+    
+    #[test]
+    #[should_panic]
+    fn check_cast_copy_slice_elements() {
+        // Same byte length, but the elements don't match up
+        let mut dest = [0u16; 4];
+        u32::cast_copy_slice(&[1, 2], &mut dest);
+    }
+    
+    #[test]
+    fn check_cast_empty_bytes() {
+        let mut word = [0u32; 1];
+        assert!(word.cast::<u8>()[..0].cast::<u32>().is_empty());
+        assert!(word.cast_mut::<u8>()[..0].cast_mut::<U32Le>().is_empty());
+    
+        // Empty slices hold nothing to misalign, thus they may be anywhere
+        assert!(word.cast::<u8>()[1..1].cast::<u32>().is_empty());
+        assert!(word.cast_mut::<u8>()[3..3].cast_mut::<U32Le>().is_empty());
+        assert!(word.cast::<u8>()[1..3].cast_aligned::<u32>() ==
+                (&[0u8, 0][..], &[][..], &[][..]));
+    }
+    
+    #[test]
+    #[should_panic="Size mismatch in cast_copy: [u8; 3] (3 bytes) to"]
+    fn check_panic_type_names() {
+        let _: Au32 = [0u8; 3].cast_copy();
+    }
+    
+    impl Layout for Au32Pad {
+        const FIELDS: &'static [FieldLayout] = &[
+            FieldLayout { name: "0", offset: 0, size: 4 },
+            FieldLayout { name: "1", offset: 4, size: 1 },
+        ];
+    }
+    
+    #[test]
+    fn check_explain_layout() {
+        let explained = explain_layout::<Au32Pad>().to_string();
+        assert_eq!(explained.lines().skip(1).collect::<Vec<_>>(), [
+            "  0x0000..0x0004 0 (4 bytes)",
+            "  0x0004..0x0005 1 (1 bytes)",
+            "  0x0005..0x0008 padding (3 bytes)",
+        ]);
+        assert!(explained.lines().next().unwrap()
+            .ends_with("Au32Pad: 8 bytes, align 4"));
+    
+        // Unions overlap, without any padding between the variants
+        let explained = explain_layout::<Descriptor>().to_string();
+        assert!(!explained.contains("padding"));
+    }
+    
+    #[derive(Safecast)]
+    #[repr(C)]
+    struct RustHeader { magic: u32, len: u16, kind: u16 }
+    
+    #[derive(Safecast)]
+    #[repr(C)]
+    struct CHeader { magic: u32, flags: u32, len: u16, kind: u16 }
+    
+    #[derive(Safecast)]
+    #[repr(C)]
+    struct Renamed { id: u32, size: u16, ty: u16 }
+    
+    #[test]
+    fn check_compare_layouts() {
+        let diff = compare_layouts::<RustHeader, CHeader>();
+        assert!(!diff.is_identical() && diff.size_delta() == 4);
+        assert!(diff.offset == Some(4));
+        assert!(diff.fields.0.unwrap().name == "len" &&
+                diff.fields.1.unwrap().name == "flags");
+        assert!(diff.to_string().ends_with("size differs by +4 bytes, \
+            diverging at 0x4: `len` (0x4..0x6) vs `flags` (0x4..0x8)"));
+    
+        // Names are not part of the layout
+        assert!(compare_layouts::<RustHeader, Renamed>().is_identical());
+    
+        // Extra trailing fields diverge where the shorter type ends
+        let diff = compare_layouts::<Au32, CHeader>();
+        assert!(diff.offset == Some(4) && diff.fields.0.is_none());
+        assert!(diff.fields.1.unwrap().name == "flags");
+    }
+    
+    #[test]
+    fn check_hex_dump() {
+        #[derive(Safecast)]
+        #[repr(C)]
+        struct Greeting { text: [u8; 16], magic: u32 }
+    
+        let mut text = [0u8; 16];
+        text[..13].copy_from_slice(b"Hello, world!");
+        let val = Greeting { text, magic: 0xdeadbeefu32.to_le() };
+        assert_eq!(Dump(&val).to_string(), "\
+00000000  48 65 6c 6c 6f 2c 20 77  6f 72 6c 64 21 00 00 00  |Hello, world!...|
+00000010  ef be ad de                                       |....|");
+    
+        // Byte slices and `Debug` work the same way
+        assert!(format!("{:?}", Dump(&b"abc"[..])) == "00000000  \
+            61 62 63                                          |abc|");
+        assert!(Dump(&[0u8; 0][..]).to_string().is_empty());
+    }
+    
+    #[test]
+    fn check_diff() {
+        let a = [1u8, 2, 3, 4, 5, 6, 7, 8];
+        let b = [1u8, 0, 0, 4, 5, 6, 7, 0];
+        assert!(diff(&a, &b).collect::<Vec<_>>() == [1..3, 7..8]);
+        assert!(diff(&a, &a).next().is_none());
+    
+        // Bytes past the end of the smaller value differ
+        assert!(diff(&a, &b[..4]).collect::<Vec<_>>() == [1..3, 4..8]);
+        let word = u32::from_ne_bytes([1, 2, 3, 4]);
+        assert!(diff(&word, &a).eq(Some(4..8)));
+    
+        // Ranges are split up by field
+        let a = CHeader { magic: 0x41414141, flags: 0, len: 5, kind: 1 };
+        let b = CHeader { magic: 0x41414141, flags: !0, len: !0, kind: 1 };
+        let diffs: Vec<_> = diff_fields(&a, &b)
+            .map(|(range, field)| (range, field.name)).collect();
+        assert!(diffs == [(4..8, "flags"), (8..10, "len")]);
+        let mut diffs = diff(&a, &b);
+        assert!(diffs.next() == Some(4..10) && diffs.next().is_none());
+    }
+    
+    // Static assertions at module scope
+    safecast::assert_pod!(Au32, [CHeader; 4], U16Be, u64,);
+    safecast::assert_no_padding!(CHeader);
+    safecast::assert_no_padding!(Descriptor);
+    safecast::assert_no_padding!(Au32Pad: u32, u8, [u8; 3]);
+    safecast::assert_layout_eq!(RustHeader, Renamed, fields);
+    safecast::assert_layout_eq!(Au32, u32);
+    
+    #[test]
+    fn check_has_padding() {
+        assert_eq!((safecast::asserts::has_padding::<Au32Pad>(),
+                    safecast::asserts::has_padding::<CHeader>()),
+                   (true, false));
+    }
+    
+    #[safecast_roundtrip_test]
+    #[derive(Safecast)]
+    #[repr(C)]
+    struct Roundtrip { magic: u32, tag: [u8; 3], flag: u8, len: U16Be,
+                       kind: u16 }
+    
+    #[safecast_roundtrip_test(Sized4<12>)]
+    #[derive(Safecast)]
+    #[repr(C)]
+    struct Sized4<const N: usize> { hdr: u32, payload: [u8; N] }
+    
+    #[safecast_roundtrip_test]
+    #[derive(Safecast)]
+    #[repr(C)]
+    struct r#Raw { val: u32 }
+    
+    #[test]
+    #[should_panic="Validation of field `magic` failed"]
+    fn check_roundtrip_validated() {
+        // Validated types must accept every pattern, including zeros
+        safecast::asserts::check_roundtrip::<ValidatedHeader>();
+    }
+}